@@ -0,0 +1,146 @@
+use anyhow::{Context, Result};
+use log::info;
+use std::process::ExitCode;
+use tokio::sync::mpsc::unbounded_channel;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::Config;
+use crate::metadata::{MetadataHandler, MetadataStatus};
+use crate::repository::ListingCache;
+
+/// Command line usage, printed for `--help` and unknown arguments
+const USAGE: &str = "Usage: game-library-manager [--refresh-all | --game <id>]
+
+Without arguments the application window opens.
+
+  --refresh-all   Refresh stale or missing metadata for every game and exit
+  --game <id>     Fetch metadata for one game again, even if still fresh, and exit
+  --help          Show this message";
+
+/// Command run without opening the window
+pub enum Command {
+    /// Refresh metadata for every game in the cached listing
+    RefreshAll,
+    /// Fetch metadata for one game again, by ID
+    RefreshGame(String),
+    /// Print the usage
+    Help,
+}
+
+/// Parse the command line arguments, returning `None` to open the window
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Command>> {
+    let Some(arg) = args.next() else {
+        return Ok(None);
+    };
+    
+    let command = match arg.as_str() {
+        "--refresh-all" => Command::RefreshAll,
+        "--game" => Command::RefreshGame(args.next().context("--game needs a game ID")?),
+        "--help" | "-h" => Command::Help,
+        _ => anyhow::bail!("Unknown argument '{}'", arg),
+    };
+    
+    if let Some(extra) = args.next() {
+        anyhow::bail!("Unexpected argument '{}'", extra);
+    }
+    
+    Ok(Some(command))
+}
+
+/// Print the usage after an argument error
+pub fn print_usage_error(error: &anyhow::Error) {
+    eprintln!("{:#}\n\n{}", error, USAGE);
+}
+
+/// Run a command and report the outcome on the console
+///
+/// Games come from the listing cached by the last repository scan, so the
+/// repository itself is never contacted.
+pub fn run(command: Command, config: Config) -> Result<ExitCode> {
+    let listing = match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            return Ok(ExitCode::SUCCESS);
+        }
+        _ => ListingCache::load(&config.paths.cache_dir)
+            .context("No repository listing is cached, open the application once to scan the repository")?,
+    };
+    
+    let games: Vec<(String, String, Option<u32>)> = listing.games
+        .iter()
+        .filter(|game| match &command {
+            Command::RefreshGame(game_id) => game.id == *game_id,
+            _ => true,
+        })
+        .map(|game| (game.id.clone(), game.title.clone(), game.igdb_id))
+        .collect();
+    
+    if let Command::RefreshGame(game_id) = &command {
+        if games.is_empty() {
+            anyhow::bail!("No game with ID '{}' in the cached listing", game_id);
+        }
+    }
+    
+    let rt = tokio::runtime::Runtime::new().context("Failed to start the async runtime")?;
+    
+    rt.block_on(async {
+        let mut handler = MetadataHandler::new(
+            config.igdb.clone(),
+            config.metadata.clone(),
+            config.paths.cache_dir.clone(),
+        )?;
+        handler.set_offline(config.offline);
+        
+        // A single game is fetched again even when its cached metadata is still fresh
+        if let Command::RefreshGame(_) = &command {
+            let (game_id, title, igdb_id) = &games[0];
+            handler.initialize().await?;
+            
+            return match handler.refresh_metadata(game_id, title, *igdb_id, true).await {
+                Ok(()) => {
+                    println!("Refreshed metadata for {} ({})", title, game_id);
+                    Ok(ExitCode::SUCCESS)
+                }
+                Err(e) => {
+                    eprintln!("Failed: {} ({}): {:#}", title, game_id, e);
+                    Ok(ExitCode::FAILURE)
+                }
+            };
+        }
+        
+        let (tx, mut rx) = unbounded_channel();
+        handler.set_progress_channel(tx);
+        handler.initialize().await?;
+        
+        // Ctrl-C stops new games from starting; the ones in flight finish
+        let cancel = CancellationToken::new();
+        let ctrl_c_cancel = cancel.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                info!("Interrupted, finishing the games in progress");
+                ctrl_c_cancel.cancel();
+            }
+        });
+        
+        println!("Refreshing stale or missing metadata for {} games", games.len());
+        handler.update_library_metadata(&games, &cancel).await?;
+        
+        let mut exit_code = ExitCode::SUCCESS;
+        while let Ok(status) = rx.try_recv() {
+            match status {
+                MetadataStatus::Failed { game_id, game_name, error } => {
+                    eprintln!("Failed: {} ({}): {}", game_name, game_id, error);
+                }
+                MetadataStatus::Completed { successful, failed, total, .. } => {
+                    println!("Refreshed metadata for {}/{} games, {} failed", successful, total, failed);
+                    if failed > 0 {
+                        exit_code = ExitCode::FAILURE;
+                    }
+                }
+                _ => {}
+            }
+        }
+        
+        Ok(exit_code)
+    })
+}
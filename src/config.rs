@@ -1,25 +1,114 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Version of the configuration layout written by this build
+///
+/// Bump this only together with a new step in `Config::migrate` for the old layout. Fields
+/// added with `#[serde(default)]` load from older files as they are and need no bump.
+const CONFIG_VERSION: u32 = 2;
+
+/// Environment variables that override configuration values, e.g. in containers or CI
+///
+/// Repository overrides apply to the active profile. Overridden values are never
+/// written to the config file or the keyring.
+pub const ENV_OVERRIDES: &[&str] = &[
+    "GLM_REPO_SERVER",
+    "GLM_REPO_SHARE",
+    "GLM_REPO_USERNAME",
+    "GLM_REPO_PASSWORD",
+    "GLM_REPO_DOMAIN",
+    "GLM_REPO_BASE_DIR",
+    "GLM_IGDB_CLIENT_ID",
+    "GLM_IGDB_CLIENT_SECRET",
+    "GLM_STEAMGRIDDB_API_KEY",
+];
+
 /// Application configuration
+///
+/// Missing fields fall back to their defaults, so older files still load.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
 pub struct Config {
-    /// SMB repository configuration
-    pub repository: RepositoryConfig,
+    /// Layout version the file was written with (0 for files from before versioning)
+    #[serde(default)]
+    pub version: u32,
+    
+    /// Index of the repository profile edited in the settings and used by default
+    #[serde(default)]
+    pub active_repository: usize,
+    
+    /// SMB repository profiles
+    #[serde(default)]
+    pub repositories: Vec<RepositoryConfig>,
+    
+    /// Single repository of older configs, moved into `repositories` on load
+    #[serde(default, skip_serializing)]
+    repository: Option<RepositoryConfig>,
     
     /// Local paths configuration
     pub paths: PathsConfig,
     
     /// IGDB API configuration
     pub igdb: IgdbConfig,
+    
+    /// Download configuration
+    #[serde(default)]
+    pub download: DownloadConfig,
+    
+    /// Installation configuration
+    #[serde(default)]
+    pub install: InstallConfig,
+    
+    /// Metadata configuration
+    #[serde(default)]
+    pub metadata: MetadataConfig,
+    
+    /// Library configuration
+    #[serde(default)]
+    pub library: LibraryConfig,
+    
+    /// Color theme of the interface
+    #[serde(default)]
+    pub theme: Theme,
+    
+    /// Window geometry restored on startup
+    #[serde(default)]
+    pub window: WindowConfig,
+    
+    /// Log file configuration
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    
+    /// Show a desktop notification when a metadata refresh or installation finishes
+    #[serde(default)]
+    pub notifications: bool,
+    
+    /// Browse the cached listing and metadata without contacting the repository or IGDB
+    #[serde(default)]
+    pub offline: bool,
+    
+    /// Environment variables applied over the file, with the repository profile
+    /// they were applied to and the values they replaced
+    #[serde(skip)]
+    env_overrides: Vec<(&'static str, usize, String)>,
 }
 
 /// SMB repository configuration
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
 pub struct RepositoryConfig {
+    /// Profile name shown in the repository selector and next to each game
+    #[serde(default)]
+    pub name: String,
+    
+    /// Whether games from this repository are listed
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    
     /// SMB server address (e.g., "192.168.1.100")
     pub server: String,
     
@@ -29,15 +118,60 @@ pub struct RepositoryConfig {
     /// Username for SMB authentication
     pub username: String,
     
-    /// Password for SMB authentication
+    /// Windows domain or workgroup for SMB authentication (optional)
+    #[serde(default)]
+    pub domain: String,
+    
+    /// Password for SMB authentication (kept in the OS keyring, or in this file if the keyring fails)
     pub password: String,
     
     /// Base directory within the share
     pub base_dir: String,
+    
+    /// Connection timeout in seconds
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    
+    /// Maximum directory depth scanned below each game directory
+    #[serde(default = "default_scan_depth")]
+    pub scan_depth: usize,
+    
+    /// Hours after which the cached repository listing is rescanned on startup
+    #[serde(default = "default_listing_stale_hours")]
+    pub listing_stale_hours: u64,
+    
+    /// Regex matching installer file names (empty uses the built-in pattern)
+    #[serde(default)]
+    pub installer_pattern: String,
+    
+    /// Regex matching patch file names (empty uses the built-in pattern)
+    #[serde(default)]
+    pub patch_pattern: String,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_timeout_secs() -> u64 {
+    15
+}
+
+fn default_scan_depth() -> usize {
+    2
+}
+
+fn default_listing_stale_hours() -> u64 {
+    24
+}
+
+fn default_match_threshold() -> f32 {
+    0.6
 }
 
 /// Local paths configuration
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
 pub struct PathsConfig {
     /// Directory for game installations
     pub install_dir: PathBuf,
@@ -51,37 +185,291 @@ pub struct PathsConfig {
 
 /// IGDB API configuration
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
 pub struct IgdbConfig {
     /// IGDB Client ID
     pub client_id: String,
     
     /// IGDB Client Secret
     pub client_secret: String,
+    
+    /// Minimum similarity (0.0-1.0) for a search result to count as a match
+    #[serde(default = "default_match_threshold")]
+    pub match_threshold: f32,
+    
+    /// Proxy for IGDB and image requests, e.g. `http://proxy:8080` (empty uses the system settings)
+    pub http_proxy: String,
+    
+    /// Connect and request timeout in seconds for IGDB and image requests (0 keeps the defaults)
+    pub http_timeout_secs: u64,
 }
 
-impl Default for Config {
+/// Metadata configuration
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct MetadataConfig {
+    /// SteamGridDB API key, enables SteamGridDB as a cover fallback when set
+    pub steamgriddb_api_key: String,
+    
+    /// Use cover images found in game directories instead of downloading one
+    pub prefer_local_covers: bool,
+    
+    /// Maximum number of games refreshed at the same time during a library refresh
+    pub max_concurrent_refreshes: usize,
+    
+    /// Days after which cached metadata is fetched again
+    pub stale_days: u64,
+    
+    /// Size limit for cached cover images in megabytes (0 disables the limit)
+    pub max_cover_cache_mb: u64,
+}
+
+impl Default for MetadataConfig {
+    fn default() -> Self {
+        Self {
+            steamgriddb_api_key: String::new(),
+            prefer_local_covers: true,
+            max_concurrent_refreshes: 4,
+            stale_days: 30,
+            max_cover_cache_mb: 512,
+        }
+    }
+}
+
+/// Download configuration
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct DownloadConfig {
+    /// Maximum number of files downloaded at the same time
+    pub max_concurrent_downloads: usize,
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_downloads: 3,
+        }
+    }
+}
+
+/// Installation configuration
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct InstallConfig {
+    /// Default unattended mode for installers without per-game arguments
+    pub silent_mode: SilentInstallMode,
+    /// Folders of imported games not named after their game ID, by game ID
+    pub folders: HashMap<String, String>,
+}
+
+/// Unattended install flags for common installer types
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum SilentInstallMode {
+    /// Run installers interactively
+    #[default]
+    Interactive,
+    /// Inno Setup (used by GOG)
+    Inno,
+    /// Nullsoft Scriptable Install System
+    Nsis,
+}
+
+impl SilentInstallMode {
+    /// Get the installer arguments for this mode, installing into `install_dir`
+    pub fn args(&self, install_dir: &Path) -> Vec<String> {
+        match self {
+            SilentInstallMode::Interactive => Vec::new(),
+            SilentInstallMode::Inno => vec![
+                "/VERYSILENT".to_string(),
+                "/SUPPRESSMSGBOXES".to_string(),
+                "/NORESTART".to_string(),
+                "/SP-".to_string(),
+                format!("/DIR={}", install_dir.display()),
+            ],
+            // NSIS requires /D to be the last argument and unquoted
+            SilentInstallMode::Nsis => vec![
+                "/S".to_string(),
+                format!("/D={}", install_dir.display()),
+            ],
+        }
+    }
+}
+
+/// Color theme of the interface
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Follow the light or dark preference of the OS
+    #[default]
+    System,
+    /// Always use the light theme
+    Light,
+    /// Always use the dark theme
+    Dark,
+}
+
+/// Log file configuration
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Most verbose level that is logged ("error", "warn", "info", "debug" or "trace")
+    pub level: String,
+    
+    /// Size in megabytes after which the log file is rotated
+    pub max_file_size_mb: u64,
+    
+    /// Number of rotated log files kept
+    pub max_files: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            max_file_size_mb: 5,
+            max_files: 3,
+        }
+    }
+}
+
+/// Window geometry, saved when the app closes
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct WindowConfig {
+    /// Inner width of the window when not maximized
+    pub width: f32,
+    
+    /// Inner height of the window when not maximized
+    pub height: f32,
+    
+    /// Position of the window on the desktop, or `None` to let the OS place it
+    pub position: Option<(f32, f32)>,
+    
+    /// Whether the window was maximized
+    pub maximized: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 1280.0,
+            height: 800.0,
+            position: None,
+            maximized: false,
+        }
+    }
+}
+
+/// Library configuration
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct LibraryConfig {
+    /// IDs of the games marked as favorite
+    pub favorites: HashSet<String>,
+    
+    /// Width of a cover in the library grid, in points
+    pub cover_width: f32,
+    
+    /// Number of cover textures kept in memory while scrolling the library
+    pub max_cover_textures: usize,
+    
+    /// How the library grid is split into sections
+    pub group_by: GroupBy,
+}
+
+/// Sections the library grid is grouped into
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// A single grid of all games
+    #[default]
+    None,
+    /// One section per IGDB genre
+    Genre,
+    /// One section per IGDB platform
+    Platform,
+    /// One section per decade of the first release
+    Decade,
+    /// One section per IGDB franchise or collection
+    Series,
+}
+
+impl Default for LibraryConfig {
+    fn default() -> Self {
+        Self {
+            favorites: HashSet::new(),
+            cover_width: 160.0,
+            max_cover_textures: 300,
+            group_by: GroupBy::None,
+        }
+    }
+}
+
+impl Default for RepositoryConfig {
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            enabled: true,
+            server: "".to_string(),
+            share: "Games".to_string(),
+            username: "".to_string(),
+            domain: "".to_string(),
+            password: "".to_string(),
+            base_dir: "Windows".to_string(),
+            timeout_secs: default_timeout_secs(),
+            scan_depth: default_scan_depth(),
+            listing_stale_hours: default_listing_stale_hours(),
+            installer_pattern: "".to_string(),
+            patch_pattern: "".to_string(),
+        }
+    }
+}
+
+impl Default for PathsConfig {
     fn default() -> Self {
         let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
         
         Self {
-            repository: RepositoryConfig {
-                server: "".to_string(),
-                share: "Games".to_string(),
-                username: "".to_string(),
-                password: "".to_string(),
-                base_dir: "Windows".to_string(),
-            },
-            paths: PathsConfig {
-                install_dir: home_dir.join("Games"),
-                cache_dir: dirs::cache_dir()
-                    .unwrap_or_else(|| home_dir.join(".cache"))
-                    .join("game-library-manager"),
-                temp_dir: std::env::temp_dir().join("game-library-manager"),
-            },
-            igdb: IgdbConfig {
-                client_id: "".to_string(),
-                client_secret: "".to_string(),
-            },
+            install_dir: home_dir.join("Games"),
+            cache_dir: dirs::cache_dir()
+                .unwrap_or_else(|| home_dir.join(".cache"))
+                .join("game-library-manager"),
+            temp_dir: std::env::temp_dir().join("game-library-manager"),
+        }
+    }
+}
+
+impl Default for IgdbConfig {
+    fn default() -> Self {
+        Self {
+            client_id: "".to_string(),
+            client_secret: "".to_string(),
+            match_threshold: default_match_threshold(),
+            http_proxy: "".to_string(),
+            http_timeout_secs: 0,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            active_repository: 0,
+            repositories: vec![RepositoryConfig::default()],
+            repository: None,
+            paths: PathsConfig::default(),
+            igdb: IgdbConfig::default(),
+            download: DownloadConfig::default(),
+            install: InstallConfig::default(),
+            metadata: MetadataConfig::default(),
+            library: LibraryConfig::default(),
+            theme: Theme::System,
+            window: WindowConfig::default(),
+            logging: LoggingConfig::default(),
+            notifications: false,
+            offline: false,
+            env_overrides: Vec::new(),
         }
     }
 }
@@ -95,8 +483,15 @@ impl Config {
             .join("config.toml")
     }
     
-    /// Load configuration from file
+    /// Load configuration from file and apply the environment overrides
     pub fn load() -> Result<Self> {
+        let mut config = Self::load_file()?;
+        config.apply_env_overrides();
+        Ok(config)
+    }
+    
+    /// Load configuration from file
+    fn load_file() -> Result<Self> {
         let config_path = Self::config_path();
         
         if !config_path.exists() {
@@ -105,14 +500,93 @@ impl Config {
         }
         
         let config_str = fs::read_to_string(&config_path)?;
-        let config: Config = toml::from_str(&config_str)?;
+        let (mut config, recovered) = Self::parse(&config_str)?;
+        let migrated = config.migrate();
+        
+        // Older configs stored the password inline; keep it until the next save moves it
+        for repository in &mut config.repositories {
+            if repository.password.is_empty() {
+                repository.password = repository.load_password();
+            }
+        }
         
         info!("Configuration loaded from {}", config_path.display());
+        
+        if recovered || migrated {
+            // Keep the original around in case the migration lost something
+            let backup_path = config_path.with_extension("toml.bak");
+            match fs::copy(&config_path, &backup_path) {
+                Ok(_) => info!("Previous configuration backed up to {}", backup_path.display()),
+                Err(e) => warn!("Failed to back up configuration: {}", e),
+            }
+            
+            config.save()?;
+        }
+        
         Ok(config)
     }
     
+    /// Parse a configuration file, keeping every top-level section that is still valid
+    ///
+    /// Returns the configuration and whether invalid sections had to be dropped.
+    fn parse(config_str: &str) -> Result<(Self, bool)> {
+        let error = match toml::from_str::<Config>(config_str) {
+            Ok(config) => return Ok((config, false)),
+            Err(e) => e,
+        };
+        
+        warn!("Configuration does not match the current layout, keeping the valid sections: {}", error);
+        
+        let file: toml::Table = toml::from_str(config_str)
+            .context("Configuration file is not valid TOML")?;
+        
+        let mut valid = toml::Table::new();
+        for (key, value) in file {
+            let mut candidate = valid.clone();
+            candidate.insert(key.clone(), value);
+            
+            if toml::Value::Table(candidate.clone()).try_into::<Config>().is_ok() {
+                valid = candidate;
+            } else {
+                warn!("Dropping invalid configuration section `{}`, its defaults are used instead", key);
+            }
+        }
+        
+        let config = toml::Value::Table(valid).try_into()?;
+        Ok((config, true))
+    }
+    
+    /// Bring a configuration written by an older version up to date
+    ///
+    /// Returns whether the file needs to be rewritten.
+    fn migrate(&mut self) -> bool {
+        let from_version = self.version;
+        
+        // Also fixes up profiles edited by hand, whatever the version
+        self.migrate_repositories();
+        
+        if from_version > CONFIG_VERSION {
+            warn!(
+                "Configuration was written by a newer version (layout {}, expected {}), unknown settings are ignored",
+                from_version, CONFIG_VERSION
+            );
+            return false;
+        }
+        
+        if from_version == CONFIG_VERSION {
+            return false;
+        }
+        
+        self.version = CONFIG_VERSION;
+        info!("Migrated configuration from layout version {} to {}", from_version, CONFIG_VERSION);
+        true
+    }
+    
     /// Save configuration to file
-    pub fn save(&self) -> Result<()> {
+    ///
+    /// Passwords go to the OS keyring. Returns a message for each repository whose
+    /// password could not be stored there and was kept in the file instead.
+    pub fn save(&self) -> Result<Vec<String>> {
         let config_path = Self::config_path();
         
         // Create parent directory if it doesn't exist
@@ -122,11 +596,128 @@ impl Config {
             }
         }
         
-        let config_str = toml::to_string(self)?;
-        fs::write(&config_path, config_str)?;
+        // Environment overrides only last for this run
+        let mut stored = self.clone();
+        stored.remove_env_overrides();
+        
+        // Only passwords the keyring took are left out of the TOML file
+        let mut keyring_failures = Vec::new();
+        for repository in &mut stored.repositories {
+            match repository.store_password() {
+                Ok(()) => repository.password.clear(),
+                Err(e) => {
+                    warn!("Keeping the password for {} in the configuration file: {:#}", repository.name, e);
+                    keyring_failures.push(format!("{}: {:#}", repository.name, e));
+                }
+            }
+        }
+        
+        let config_str = toml::to_string(&stored)?;
+        crate::fs_util::write_atomic(&config_path, config_str)?;
         
         info!("Configuration saved to {}", config_path.display());
-        Ok(())
+        Ok(keyring_failures)
+    }
+    
+    /// Replace configuration values with those of the `ENV_OVERRIDES` variables that are set
+    pub fn apply_env_overrides(&mut self) {
+        let repository_index = self.active_repository.min(self.repositories.len() - 1);
+        
+        for &var in ENV_OVERRIDES {
+            let Ok(value) = std::env::var(var) else {
+                continue;
+            };
+            let Some(field) = self.env_override_field(var, repository_index) else {
+                continue;
+            };
+            
+            let original = std::mem::replace(field, value);
+            info!("Using {} from the environment", var);
+            self.env_overrides.push((var, repository_index, original));
+        }
+    }
+    
+    /// Get the names of the environment variables currently overriding the configuration
+    pub fn env_overrides(&self) -> Vec<&'static str> {
+        self.env_overrides.iter().map(|(var, _, _)| *var).collect()
+    }
+    
+    /// Put back the values the environment overrides replaced
+    fn remove_env_overrides(&mut self) {
+        for (var, repository_index, original) in std::mem::take(&mut self.env_overrides) {
+            if let Some(field) = self.env_override_field(var, repository_index) {
+                *field = original;
+            }
+        }
+    }
+    
+    /// Get the configuration value an environment variable overrides
+    fn env_override_field(&mut self, var: &str, repository_index: usize) -> Option<&mut String> {
+        let repository = self.repositories.get_mut(repository_index);
+        
+        match var {
+            "GLM_REPO_SERVER" => repository.map(|repository| &mut repository.server),
+            "GLM_REPO_SHARE" => repository.map(|repository| &mut repository.share),
+            "GLM_REPO_USERNAME" => repository.map(|repository| &mut repository.username),
+            "GLM_REPO_PASSWORD" => repository.map(|repository| &mut repository.password),
+            "GLM_REPO_DOMAIN" => repository.map(|repository| &mut repository.domain),
+            "GLM_REPO_BASE_DIR" => repository.map(|repository| &mut repository.base_dir),
+            "GLM_IGDB_CLIENT_ID" => Some(&mut self.igdb.client_id),
+            "GLM_IGDB_CLIENT_SECRET" => Some(&mut self.igdb.client_secret),
+            "GLM_STEAMGRIDDB_API_KEY" => Some(&mut self.metadata.steamgriddb_api_key),
+            _ => None,
+        }
+    }
+    
+    /// Move the single repository of older configs into the profile list
+    ///
+    /// Also makes sure there is at least one named profile and that the active index is valid.
+    fn migrate_repositories(&mut self) {
+        if let Some(mut repository) = self.repository.take() {
+            if self.repositories.is_empty() {
+                info!("Moving the repository settings into a repository profile");
+                repository.enabled = true;
+                self.repositories.push(repository);
+            }
+        }
+        
+        if self.repositories.is_empty() {
+            self.repositories.push(RepositoryConfig::default());
+        }
+        
+        for (i, repository) in self.repositories.iter_mut().enumerate() {
+            if repository.name.trim().is_empty() {
+                repository.name = if i == 0 { "Default".to_string() } else { format!("Repository {}", i + 1) };
+            }
+        }
+        
+        self.active_repository = self.active_repository.min(self.repositories.len() - 1);
+    }
+    
+    /// Get the active repository profile
+    pub fn repository(&self) -> &RepositoryConfig {
+        &self.repositories[self.active_repository.min(self.repositories.len() - 1)]
+    }
+    
+    /// Get the active repository profile for editing
+    pub fn repository_mut(&mut self) -> &mut RepositoryConfig {
+        let index = self.active_repository.min(self.repositories.len() - 1);
+        &mut self.repositories[index]
+    }
+    
+    /// Get the repository profile a game was listed from, or the active one if it is gone
+    pub fn repository_for(&self, name: &str) -> &RepositoryConfig {
+        self.repositories.iter()
+            .find(|repository| repository.name == name)
+            .unwrap_or_else(|| self.repository())
+    }
+    
+    /// Get the repository profiles whose games are listed
+    pub fn enabled_repositories(&self) -> Vec<RepositoryConfig> {
+        self.repositories.iter()
+            .filter(|repository| repository.enabled)
+            .cloned()
+            .collect()
     }
     
     /// Ensure all configured directories exist
@@ -144,4 +735,84 @@ impl Config {
         
         Ok(())
     }
-}
\ No newline at end of file
+    
+    /// Create all configured directories and check that files can be written to them
+    pub fn validate_directories(&self) -> Result<()> {
+        self.ensure_directories()?;
+        
+        for (name, dir) in [
+            ("Install", &self.paths.install_dir),
+            ("Cache", &self.paths.cache_dir),
+            ("Temp", &self.paths.temp_dir),
+        ] {
+            tempfile::tempfile_in(dir)
+                .with_context(|| format!("{} directory {} is not writable", name, dir.display()))?;
+        }
+        
+        Ok(())
+    }
+}
+
+impl RepositoryConfig {
+    /// Keyring service name for repository credentials
+    const KEYRING_SERVICE: &'static str = "game-library-manager";
+    
+    /// Get the keyring entry for this server and username, or `None` without both
+    fn keyring_entry(&self) -> Result<Option<keyring::Entry>> {
+        if self.server.is_empty() || self.username.is_empty() {
+            return Ok(None);
+        }
+        
+        let account = format!("{}@{}", self.username, self.server);
+        let entry = keyring::Entry::new(Self::KEYRING_SERVICE, &account)
+            .with_context(|| format!("Failed to open keyring entry for {}", account))?;
+        Ok(Some(entry))
+    }
+    
+    /// Load the password from the OS keyring, returning an empty password if none is stored
+    pub fn load_password(&self) -> String {
+        let entry = match self.keyring_entry() {
+            Ok(Some(entry)) => entry,
+            Ok(None) => return String::new(),
+            Err(e) => {
+                warn!("{:#}", e);
+                return String::new();
+            }
+        };
+        
+        match entry.get_password() {
+            Ok(password) => password,
+            Err(keyring::Error::NoEntry) => {
+                info!("No stored password for {}@{}", self.username, self.server);
+                String::new()
+            }
+            Err(e) => {
+                warn!("Failed to read password from keyring: {}", e);
+                String::new()
+            }
+        }
+    }
+    
+    /// Store the password in the OS keyring, removing the stored one if the password is empty
+    ///
+    /// Fails if a password is set but cannot be stored, in which case the caller has to keep it.
+    pub fn store_password(&self) -> Result<()> {
+        let Some(entry) = self.keyring_entry()? else {
+            if self.password.is_empty() {
+                return Ok(());
+            }
+            return Err(anyhow::anyhow!("A server and username are needed to store the password in the keyring"));
+        };
+        
+        let result = if self.password.is_empty() {
+            match entry.delete_password() {
+                Err(keyring::Error::NoEntry) => Ok(()),
+                result => result,
+            }
+        } else {
+            entry.set_password(&self.password)
+        };
+        
+        result.context("Failed to store password in keyring")
+    }
+}
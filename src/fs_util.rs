@@ -0,0 +1,39 @@
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// Create a temporary file in the same directory as `path`
+///
+/// Persisting it over `path` is a rename on the same filesystem, so readers
+/// see either the old file or the complete new one.
+pub fn temp_file_for(path: &Path) -> Result<NamedTempFile> {
+    let dir = path.parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    
+    NamedTempFile::new_in(dir)
+        .with_context(|| format!("Failed to create temporary file in {}", dir.display()))
+}
+
+/// Move a finished temporary file over `path`
+pub fn persist(file: NamedTempFile, path: &Path) -> Result<()> {
+    file.as_file().sync_all()
+        .with_context(|| format!("Failed to flush {}", path.display()))?;
+    
+    file.persist(path)
+        .map_err(|e| e.error)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    
+    Ok(())
+}
+
+/// Write `contents` to `path` without ever leaving a truncated file behind
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let mut file = temp_file_for(path)?;
+    
+    file.write_all(contents.as_ref())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    
+    persist(file, path)
+}
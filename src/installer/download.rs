@@ -1,15 +1,28 @@
 use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use log::{info, warn, error};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 use crate::config::Config;
 use crate::repository::{GameFile, SmbConnection};
 
+/// Size of each chunk read from the repository
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Minimum number of bytes between progress updates
+const PROGRESS_INTERVAL: u64 = 4 * 1024 * 1024;
+
 /// Download progress
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct DownloadProgress {
+    /// Name of the file being downloaded
+    pub file: String,
     /// Downloaded size in bytes
     pub downloaded: u64,
     /// Total size in bytes
@@ -21,16 +34,41 @@ pub struct DownloadProgress {
 /// Download status message
 #[derive(Debug, Clone)]
 pub enum DownloadStatus {
+    /// Files about to be downloaded, with their total size in bytes
+    Queued { files: usize, size: u64 },
     /// Download started
     Started { file: String, size: u64 },
     /// Download progress
     Progress(DownloadProgress),
     /// Download completed
-    Completed { path: PathBuf },
+    Completed { file: String, path: PathBuf },
     /// Download failed
     Failed { error: String },
 }
 
+/// Total size in bytes of the given files
+pub fn total_size(files: &[GameFile]) -> u64 {
+    files.iter().map(|file| file.size).sum()
+}
+
+/// Check that each directory has at least `required` bytes free
+///
+/// Directories that don't exist yet are checked on their nearest existing ancestor.
+pub fn check_free_space(required: u64, dirs: &[&Path]) -> Result<()> {
+    for dir in dirs {
+        let available = crate::system::available_space(dir)?;
+        
+        if available < required {
+            return Err(anyhow::anyhow!(
+                "Not enough free space on {}: {} bytes required, {} bytes available",
+                dir.display(), required, available
+            ));
+        }
+    }
+    
+    Ok(())
+}
+
 /// Game downloader
 pub struct Downloader {
     /// SMB connection
@@ -39,6 +77,10 @@ pub struct Downloader {
     temp_dir: PathBuf,
     /// Progress channel
     progress_tx: Option<mpsc::Sender<DownloadStatus>>,
+    /// Resume partially downloaded files instead of starting over
+    resume: bool,
+    /// Maximum number of files downloaded at the same time
+    max_concurrent: usize,
 }
 
 impl Downloader {
@@ -48,6 +90,8 @@ impl Downloader {
             smb,
             temp_dir: config.paths.temp_dir.clone(),
             progress_tx: None,
+            resume: true,
+            max_concurrent: config.download.max_concurrent_downloads.max(1),
         }
     }
     
@@ -56,6 +100,11 @@ impl Downloader {
         self.progress_tx = Some(tx);
     }
     
+    /// Set whether partial downloads are resumed
+    pub fn set_resume(&mut self, resume: bool) {
+        self.resume = resume;
+    }
+    
     /// Send download status
     async fn send_status(&self, status: DownloadStatus) {
         if let Some(tx) = &self.progress_tx {
@@ -65,38 +114,38 @@ impl Downloader {
         }
     }
     
+    /// Get the local path a file of a game is downloaded to
+    ///
+    /// Files are kept under `<temp_dir>/<game_id>/<remote_path>`, so games or versions
+    /// sharing a file name never resume or run each other's downloads.
+    fn local_path(&self, game_id: &str, file: &GameFile) -> PathBuf {
+        let mut path = self.temp_dir.join(game_id);
+        for part in file.remote_path.split(['/', '\\']) {
+            if !part.is_empty() && part != "." && part != ".." {
+                path.push(part);
+            }
+        }
+        path
+    }
+    
     /// Download a game file
-    pub async fn download_file(&self, file: &GameFile) -> Result<PathBuf> {
-        // Create temporary directory if it doesn't exist
-        if !self.temp_dir.exists() {
-            std::fs::create_dir_all(&self.temp_dir)
+    pub async fn download_file(&self, game_id: &str, file: &GameFile, cancel: &CancellationToken) -> Result<PathBuf> {
+        let local_path = self.local_path(game_id, file);
+        
+        // Create the file's temporary directory if it doesn't exist
+        if let Some(parent) = local_path.parent() {
+            std::fs::create_dir_all(parent)
                 .context("Failed to create temporary directory")?;
         }
         
-        let local_path = self.temp_dir.join(&file.name);
-        
         // Send started status
         self.send_status(DownloadStatus::Started {
             file: file.name.clone(),
             size: file.size,
         }).await;
         
-        // Simulate progress
-        for i in 1..=10 {
-            let progress = DownloadProgress {
-                downloaded: file.size * i / 10,
-                total: file.size,
-                percentage: (i as f32) * 10.0,
-            };
-            
-            self.send_status(DownloadStatus::Progress(progress)).await;
-            
-            // Simulate delay
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-        }
-        
         // Download file
-        if let Err(e) = self.smb.download_file(&file.remote_path, &local_path).await {
+        if let Err(e) = self.copy_file(file, &local_path, cancel).await {
             self.send_status(DownloadStatus::Failed {
                 error: e.to_string(),
             }).await;
@@ -105,25 +154,217 @@ impl Downloader {
         
         // Send completed status
         self.send_status(DownloadStatus::Completed {
+            file: file.name.clone(),
             path: local_path.clone(),
         }).await;
         
         Ok(local_path)
     }
     
-    /// Download multiple files
-    pub async fn download_files(&self, files: &[GameFile]) -> Result<Vec<PathBuf>> {
-        let mut paths = Vec::new();
+    /// Get the path used for a file while it is still downloading
+    fn partial_path(local_path: &Path) -> PathBuf {
+        let mut name = local_path.as_os_str().to_os_string();
+        name.push(".part");
+        PathBuf::from(name)
+    }
+    
+    /// Stream a file from the repository in chunks, reporting byte progress
+    ///
+    /// Repository and disk reads block, so the copy runs on a blocking thread and
+    /// concurrent downloads actually overlap.
+    async fn copy_file(&self, file: &GameFile, local_path: &Path, cancel: &CancellationToken) -> Result<()> {
+        let smb = self.smb.clone();
+        let progress_tx = self.progress_tx.clone();
+        let resume = self.resume;
+        let file = file.clone();
+        let local_path = local_path.to_path_buf();
+        let cancel = cancel.clone();
+        
+        tokio::task::spawn_blocking(move || {
+            Self::copy_file_blocking(&smb, &file, &local_path, resume, &cancel, progress_tx.as_ref())
+        })
+        .await
+        .context("Download task failed")?
+    }
+    
+    /// Copy a file from the repository on the current thread
+    ///
+    /// Data is written to a `.part` file that is renamed once the download is complete.
+    fn copy_file_blocking(
+        smb: &SmbConnection,
+        file: &GameFile,
+        local_path: &Path,
+        resume: bool,
+        cancel: &CancellationToken,
+        progress_tx: Option<&mpsc::Sender<DownloadStatus>>,
+    ) -> Result<()> {
+        let part_path = Self::partial_path(local_path);
+        
+        let send_progress = |downloaded: u64| {
+            if let Some(tx) = progress_tx {
+                let progress = Self::progress(&file.name, downloaded, file.size);
+                if let Err(e) = tx.blocking_send(DownloadStatus::Progress(progress)) {
+                    warn!("Failed to send download status: {}", e);
+                }
+            }
+        };
+        
+        // Pick up where a previous attempt left off, unless the partial file is unusable
+        let mut offset = if resume {
+            fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0)
+        } else {
+            0
+        };
+        if file.size > 0 && offset > file.size {
+            warn!("Partial file {} is larger than expected, restarting download", part_path.display());
+            offset = 0;
+        }
+        
+        if offset > 0 {
+            info!("Resuming download of {} at {} bytes", file.name, offset);
+        }
+        
+        let mut source = smb.open_file(&file.remote_path, offset)?;
+        let mut target = if offset > 0 {
+            OpenOptions::new().append(true).open(&part_path)
+        } else {
+            File::create(&part_path)
+        }
+        .with_context(|| format!("Failed to open local file: {}", part_path.display()))?;
+        
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let mut downloaded = offset;
+        let mut last_reported = offset;
+        
+        // Hash while streaming, seeding the hasher with any data already on disk
+        let mut hasher = file.sha256.as_ref().map(|_| Sha256::new());
+        if let Some(hasher) = hasher.as_mut() {
+            if offset > 0 {
+                let mut existing = File::open(&part_path)
+                    .with_context(|| format!("Failed to read {}", part_path.display()))?;
+                std::io::copy(&mut existing, hasher)
+                    .with_context(|| format!("Failed to read {}", part_path.display()))?;
+            }
+        }
+        
+        send_progress(downloaded);
+        
+        loop {
+            if cancel.is_cancelled() {
+                return Err(anyhow::anyhow!("Cancelled"));
+            }
+            
+            let read = source.read(&mut buffer)
+                .with_context(|| format!("Failed to read {}", file.remote_path))?;
+            if read == 0 {
+                break;
+            }
+            
+            target.write_all(&buffer[..read])
+                .with_context(|| format!("Failed to write {}", part_path.display()))?;
+            if let Some(hasher) = hasher.as_mut() {
+                hasher.update(&buffer[..read]);
+            }
+            downloaded += read as u64;
+            
+            if downloaded - last_reported >= PROGRESS_INTERVAL {
+                last_reported = downloaded;
+                send_progress(downloaded);
+            }
+        }
+        
+        target.flush()
+            .with_context(|| format!("Failed to write {}", part_path.display()))?;
+        drop(target);
+        
+        send_progress(downloaded);
+        
+        if file.size > 0 && downloaded != file.size {
+            return Err(anyhow::anyhow!(
+                "Downloaded size of {} does not match: expected {} bytes, got {}",
+                file.name, file.size, downloaded
+            ));
+        }
+        
+        if let (Some(expected), Some(hasher)) = (&file.sha256, hasher) {
+            let actual = format!("{:x}", hasher.finalize());
+            
+            if !actual.eq_ignore_ascii_case(expected) {
+                if let Err(e) = fs::remove_file(&part_path) {
+                    warn!("Failed to remove corrupt download {}: {}", part_path.display(), e);
+                }
+                return Err(anyhow::anyhow!(
+                    "Checksum mismatch for {}: expected {}, got {}",
+                    file.name, expected, actual
+                ));
+            }
+            
+            info!("Verified checksum for {}", file.name);
+        }
+        
+        fs::rename(&part_path, local_path)
+            .with_context(|| format!("Failed to move {} into place", part_path.display()))?;
+        
+        info!("Downloaded {} ({} bytes)", file.name, downloaded);
+        Ok(())
+    }
+    
+    /// Build a progress update, using the downloaded size when the total is unknown
+    fn progress(file: &str, downloaded: u64, total: u64) -> DownloadProgress {
+        let total = total.max(downloaded);
+        let percentage = if total == 0 {
+            100.0
+        } else {
+            (downloaded as f64 / total as f64 * 100.0) as f32
+        };
         
-        for file in files {
-            let path = self.download_file(file).await?;
-            paths.push(path);
+        DownloadProgress {
+            file: file.to_string(),
+            downloaded,
+            total,
+            percentage,
+        }
+    }
+    
+    /// Download multiple files, up to `max_concurrent` at a time
+    pub async fn download_files(&self, game_id: &str, files: &[GameFile], cancel: &CancellationToken) -> Result<Vec<PathBuf>> {
+        if let Err(e) = check_free_space(total_size(files), &[&self.temp_dir]) {
+            self.send_status(DownloadStatus::Failed {
+                error: e.to_string(),
+            }).await;
+            return Err(e);
         }
         
-        Ok(paths)
+        self.send_status(DownloadStatus::Queued {
+            files: files.len(),
+            size: total_size(files),
+        }).await;
+        
+        // `buffered` runs downloads concurrently but yields results in input order
+        stream::iter(files)
+            .map(|file| async move {
+                if cancel.is_cancelled() {
+                    return Err(anyhow::anyhow!("Cancelled"));
+                }
+                self.download_file(game_id, file, cancel).await
+            })
+            .buffered(self.max_concurrent)
+            .try_collect()
+            .await
+    }
+    
+    /// Get every local path a download of these files may have written, including partial files
+    pub fn temp_paths(&self, game_id: &str, files: &[GameFile]) -> Vec<PathBuf> {
+        files.iter()
+            .flat_map(|file| {
+                let local_path = self.local_path(game_id, file);
+                let part_path = Self::partial_path(&local_path);
+                [local_path, part_path]
+            })
+            .collect()
     }
     
-    /// Clean up downloaded files
+    /// Clean up downloaded files and the directories they leave empty
     pub fn cleanup(&self, paths: &[PathBuf]) -> Result<()> {
         for path in paths {
             if path.exists() {
@@ -133,6 +374,15 @@ impl Downloader {
             }
         }
         
+        for path in paths {
+            // Removing a directory fails while it still has files, which stops the walk
+            for dir in path.ancestors().skip(1).take_while(|dir| *dir != self.temp_dir) {
+                if !dir.starts_with(&self.temp_dir) || std::fs::remove_dir(dir).is_err() {
+                    break;
+                }
+            }
+        }
+        
         Ok(())
     }
 }
\ No newline at end of file
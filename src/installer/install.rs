@@ -1,14 +1,20 @@
 use anyhow::{Context, Result};
 use log::{info, warn, error};
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Child, Command};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::task;
+use regex::Regex;
+use walkdir::WalkDir;
+use tokio_util::sync::CancellationToken;
 
 use crate::config::Config;
-use crate::repository::{GameInfo, GameVersion, FileType, GameFile}; // Added GameFile import
-use super::download::{Downloader, DownloadStatus};
+use crate::metadata::igdb::title_similarity;
+use crate::repository::{GameInfo, GameVersion, FileType, GameFile, follow_patch_chain};
+use super::download::{self, Downloader, DownloadStatus};
 use super::version::VersionManager;
 
 /// Installation status
@@ -24,6 +30,57 @@ pub enum InstallStatus {
     Failed { error: String },
 }
 
+/// Name of the structured installation marker file
+const INSTALL_MARKER: &str = "installed.json";
+
+/// Name of the free-text marker written by older versions
+const LEGACY_INSTALL_MARKER: &str = "installed.txt";
+
+/// Installed version recorded in the installation marker
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InstalledVersion {
+    /// Game ID (directory name)
+    pub game_id: String,
+    /// Installed version name
+    pub version_name: String,
+    /// Installed build number
+    pub build: u32,
+    /// Names of the installer and patch files that were applied
+    pub files: Vec<String>,
+    /// Installation time (RFC 3339)
+    pub installed_at: String,
+    /// Main executable, relative to the install directory
+    #[serde(default)]
+    pub executable: Option<String>,
+    /// Last time the game was launched (RFC 3339)
+    #[serde(default)]
+    pub last_played: Option<String>,
+    /// Accumulated playtime in seconds
+    #[serde(default)]
+    pub total_playtime_secs: u64,
+    /// Files in the install directory when the installation finished
+    #[serde(default)]
+    pub contents: Vec<InstalledFile>,
+}
+
+/// File recorded in the installation marker, to verify the installation against
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InstalledFile {
+    /// Path relative to the install directory, with `/` separators
+    pub path: String,
+    /// Size in bytes
+    pub size: u64,
+}
+
+/// Outcome of scanning the install directory for games installed without this application
+#[derive(Debug, Clone, Default)]
+pub struct InstallScan {
+    /// Games that were registered, as (game ID, folder name)
+    pub matched: Vec<(String, String)>,
+    /// Folders that could not be matched to a library game
+    pub unmatched: Vec<String>,
+}
+
 /// Game installer (Windows-only implementation)
 pub struct Installer {
     /// Configuration
@@ -66,8 +123,24 @@ impl Installer {
         self.send_status(InstallStatus::Downloading(status)).await;
     }
     
+    /// Remove the downloaded files of a cancelled installation
+    ///
+    /// The returned error is reported by the caller like any other failure.
+    async fn handle_cancelled(&self, game_id: &str, files: &[GameFile]) -> Result<()> {
+        info!("Installation cancelled, cleaning up downloaded files");
+        
+        if let Err(e) = self.downloader.cleanup(&self.downloader.temp_paths(game_id, files)) {
+            warn!("Failed to clean up downloaded files: {}", e);
+        }
+        
+        Err(anyhow::anyhow!("Cancelled"))
+    }
+    
     /// Install a game version (Windows-only implementation)
-    pub async fn install_version(&self, game: &GameInfo, version: &GameVersion) -> Result<()> {
+    ///
+    /// The installation stops between files and download chunks once `cancel` is triggered.
+    /// Failures are returned rather than sent as `InstallStatus::Failed`, so the caller reports them once.
+    pub async fn install_version(&self, game: &GameInfo, version: &GameVersion, cancel: &CancellationToken) -> Result<()> {
         info!("Installing {} version {}", game.title, version.name);
         
         // Send installing status
@@ -76,51 +149,104 @@ impl Installer {
             version: version.name.clone(),
         }).await;
         
+        // Versions that only ship as archives would need extracting, which isn't implemented
+        if !version.files.iter().any(|file| file.file_type == FileType::Installer) {
+            return Err(anyhow::anyhow!(
+                "{} version {} has no installer, archive installs are not supported yet",
+                game.title, version.name
+            ));
+        }
+        
+        // Resolve the patch order up front so a broken chain fails before downloading
+        let ordered_patches: Vec<GameFile> = self.version_manager.get_ordered_patches(version)?
+            .into_iter()
+            .cloned()
+            .collect();
+        
+        // Files needed for this version (installers and patches)
+        let required_files: Vec<GameFile> = self.version_manager.get_required_files(version)
+            .into_iter().cloned().collect();
+        
+        // Make sure the download and the installation both fit before touching anything
+        let required_space = download::total_size(&required_files);
+        download::check_free_space(
+            required_space,
+            &[&self.config.paths.temp_dir, &self.config.paths.install_dir],
+        )?;
+        
         // Determine the installation directory (this is the game install directory)
-        let install_dir = self.config.paths.install_dir.join(&game.id);
+        let install_dir = self.game_dir(game);
         if !install_dir.exists() {
             std::fs::create_dir_all(&install_dir)
                 .context("Failed to create installation directory")?;
         }
         
-        // Download required files (installers and patches)
-        let required_files: Vec<GameFile> = self.version_manager.get_required_files(version)
-            .into_iter().cloned().collect();
-        let downloaded_paths = self.downloader.download_files(&required_files).await?;
-        
-        // For each installer file, if its type is Installer, execute it.
-        for file in &version.files {
-            if file.file_type == FileType::Installer {
-                // Find the local path corresponding to the installer file
-                let file_path = downloaded_paths.iter()
-                    .find(|p| p.ends_with(&file.name))
-                    .ok_or_else(|| anyhow::anyhow!("Installer file '{}' not found", file.name))?
-                    .clone();
-                
-                // Run the installer executable (Windows-only)
-                let install_result = task::spawn_blocking({
-                    let file_path = file_path.clone();
-                    move || {
-                        Command::new(&file_path)
-                            .spawn()
-                            .and_then(|mut child| child.wait())
-                    }
-                }).await??;
-                
-                if !install_result.success() {
-                    self.send_status(InstallStatus::Failed { 
-                        error: format!("Installer exited with status: {:?}", install_result) 
-                    }).await;
-                    return Err(anyhow::anyhow!("Installation failed with status: {:?}", install_result));
-                }
+        // Download required files
+        let downloaded_paths = match self.downloader.download_files(&game.id, &required_files, cancel).await {
+            Ok(paths) => paths,
+            Err(_) if cancel.is_cancelled() => return self.handle_cancelled(&game.id, &required_files).await,
+            Err(e) => return Err(e),
+        };
+        
+        // Per-game arguments take precedence over the configured silent mode
+        let install_args = game.install_args.clone()
+            .unwrap_or_else(|| self.config.install.silent_mode.args(&install_dir));
+        
+        // Run the base installers first, then each patch in upgrade order, then any DLC
+        let base_installers = version.files.iter()
+            .filter(|file| file.file_type == FileType::Installer);
+        let dlc_installers = version.files.iter()
+            .filter(|file| file.file_type == FileType::Dlc);
+        
+        for file in base_installers.chain(ordered_patches.iter()).chain(dlc_installers) {
+            if cancel.is_cancelled() {
+                return self.handle_cancelled(&game.id, &required_files).await;
+            }
+            
+            // `download_files` returns one path per required file, in the same order
+            let file_path = required_files.iter()
+                .position(|required| required.remote_path == file.remote_path)
+                .and_then(|index| downloaded_paths.get(index))
+                .ok_or_else(|| anyhow::anyhow!("Installer file '{}' not found", file.name))?
+                .clone();
+            
+            match file.file_type {
+                FileType::Patch => info!("Applying patch {}", file.name),
+                FileType::Dlc => info!("Installing DLC {}", file.name),
+                _ => {}
             }
+            
+            self.run_executable(&file_path, &install_args).await?;
         }
         
         // Mark installation complete by writing a marker file in the game install directory
-        let install_marker = install_dir.join("installed.txt");
-        std::fs::write(&install_marker, format!("Game: {}\nVersion: {}\nInstalled: {}", 
-            game.title, version.name, chrono::Local::now()))
-            .context("Failed to write installation marker")?;
+        let (_, installed_build) = follow_patch_chain(version.build, &ordered_patches.iter().collect::<Vec<_>>());
+        let mut installed = InstalledVersion {
+            game_id: game.id.clone(),
+            version_name: version.name.clone(),
+            build: installed_build,
+            files: required_files.iter().map(|file| file.name.clone()).collect(),
+            installed_at: chrono::Local::now().to_rfc3339(),
+            executable: Self::detect_executable(&install_dir)
+                .and_then(|exe| exe.strip_prefix(&install_dir).ok().map(|p| p.to_string_lossy().to_string())),
+            last_played: None,
+            total_playtime_secs: 0,
+            contents: Self::list_contents(&install_dir),
+        };
+        
+        // A repair only re-runs some of the files, so keep what the earlier run recorded
+        if let Some(previous) = self.installed_version(game).filter(|previous| previous.version_name == version.name) {
+            installed.build = installed.build.max(previous.build);
+            for file in previous.files {
+                if !installed.files.contains(&file) {
+                    installed.files.push(file);
+                }
+            }
+            installed.last_played = previous.last_played;
+            installed.total_playtime_secs = previous.total_playtime_secs;
+        }
+        
+        self.write_marker(&install_dir, &installed)?;
         
         self.send_status(InstallStatus::Completed {
             game: game.title.clone(),
@@ -131,10 +257,30 @@ impl Installer {
         Ok(())
     }
     
+    /// Run an installer or patch executable and wait for it to finish (Windows-only)
+    async fn run_executable(&self, file_path: &Path, args: &[String]) -> Result<()> {
+        let install_result = task::spawn_blocking({
+            let file_path = file_path.to_path_buf();
+            let args = args.to_vec();
+            move || {
+                Command::new(&file_path)
+                    .args(&args)
+                    .spawn()
+                    .and_then(|mut child| child.wait())
+            }
+        }).await??;
+        
+        if !install_result.success() {
+            return Err(anyhow::anyhow!("Installation failed with status: {:?}", install_result));
+        }
+        
+        Ok(())
+    }
+    
     /// Uninstall a game by removing its install directory
     pub fn uninstall_game(&self, game: &GameInfo) -> Result<()> {
         info!("Uninstalling {}", game.title);
-        let install_dir = self.config.paths.install_dir.join(&game.id);
+        let install_dir = self.game_dir(game);
         if !install_dir.exists() {
             return Err(anyhow::anyhow!("Game is not installed"));
         }
@@ -146,9 +292,272 @@ impl Installer {
     
     /// Check if a game is installed (by checking for the marker file)
     pub fn is_installed(&self, game: &GameInfo) -> bool {
-        let install_dir = self.config.paths.install_dir.join(&game.id);
-        let install_marker = install_dir.join("installed.txt");
-        install_marker.exists()
+        Self::has_marker(&self.game_dir(game))
+    }
+    
+    /// Check whether a directory contains an installation marker
+    fn has_marker(install_dir: &Path) -> bool {
+        install_dir.join(INSTALL_MARKER).exists() || install_dir.join(LEGACY_INSTALL_MARKER).exists()
+    }
+    
+    /// Get the directory a game is installed in
+    ///
+    /// This is the game ID under the install directory, unless the game was imported from another folder.
+    pub fn game_dir(&self, game: &GameInfo) -> PathBuf {
+        let folder = self.config.install.folders.get(&game.id).unwrap_or(&game.id);
+        self.config.paths.install_dir.join(folder)
+    }
+    
+    /// Get the installed version of a game from its marker file
+    pub fn installed_version(&self, game: &GameInfo) -> Option<InstalledVersion> {
+        let marker_path = self.game_dir(game).join(INSTALL_MARKER);
+        
+        if !marker_path.exists() {
+            return None;
+        }
+        
+        let result = std::fs::read_to_string(&marker_path)
+            .context("Failed to read installation marker")
+            .and_then(|json_str| {
+                serde_json::from_str::<InstalledVersion>(&json_str)
+                    .context("Failed to parse installation marker")
+            });
+        
+        match result {
+            Ok(installed) => Some(installed),
+            Err(e) => {
+                warn!("{}: {}", marker_path.display(), e);
+                None
+            }
+        }
+    }
+    
+    /// Find the main executable of an installed game
+    ///
+    /// Uses the executable recorded in the marker, falling back to scanning the install directory.
+    pub fn find_executable(&self, game: &GameInfo) -> Option<PathBuf> {
+        let install_dir = self.game_dir(game);
+        
+        let recorded = self.installed_version(game)
+            .and_then(|installed| installed.executable)
+            .map(|exe| install_dir.join(exe))
+            .filter(|exe| exe.exists());
+        
+        recorded.or_else(|| Self::detect_executable(&install_dir))
+    }
+    
+    /// Pick the largest executable in a directory that isn't an installer, patch or helper tool
+    fn detect_executable(install_dir: &Path) -> Option<PathBuf> {
+        let excluded = Regex::new(r"(?i)(unins|setup|install|patch|update|redist|vcredist|dxsetup|crash|report)").unwrap();
+        
+        WalkDir::new(install_dir)
+            .max_depth(3)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| {
+                let name = entry.file_name().to_string_lossy();
+                name.to_lowercase().ends_with(".exe") && !excluded.is_match(&name)
+            })
+            .max_by_key(|entry| entry.metadata().map(|m| m.len()).unwrap_or(0))
+            .map(|entry| entry.into_path())
+    }
+    
+    /// List the files in an install directory, leaving out the installation markers
+    fn list_contents(install_dir: &Path) -> Vec<InstalledFile> {
+        WalkDir::new(install_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| entry.depth() > 1 || (entry.file_name() != INSTALL_MARKER && entry.file_name() != LEGACY_INSTALL_MARKER))
+            .filter_map(|entry| {
+                let path = entry.path().strip_prefix(install_dir).ok()?;
+                let path = path.components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                Some(InstalledFile { path, size: entry.metadata().ok()?.len() })
+            })
+            .collect()
+    }
+    
+    /// Check an installed game against its marker
+    ///
+    /// Returns a description of every installer of `version` that never completed and every
+    /// recorded file that is missing or changed size. An empty list means the installation is intact.
+    pub fn verify(&self, game: &GameInfo, version: &GameVersion) -> Vec<String> {
+        let Some(installed) = self.installed_version(game) else {
+            return vec!["Installation marker is missing".to_string()];
+        };
+        
+        let mut problems: Vec<String> = self.version_manager.get_required_files(version)
+            .into_iter()
+            .filter(|file| !installed.files.contains(&file.name))
+            .map(|file| format!("Not installed: {}", file.name))
+            .collect();
+        
+        problems.extend(self.damaged_contents(game, &installed));
+        problems
+    }
+    
+    /// Describe the recorded files of an installation that are missing or changed size
+    fn damaged_contents(&self, game: &GameInfo, installed: &InstalledVersion) -> Vec<String> {
+        let install_dir = self.game_dir(game);
+        
+        installed.contents.iter()
+            .filter_map(|file| match std::fs::metadata(install_dir.join(&file.path)) {
+                Ok(metadata) if metadata.len() == file.size => None,
+                Ok(metadata) => Some(format!(
+                    "Corrupt: {} ({} bytes, expected {})",
+                    file.path, metadata.len(), file.size
+                )),
+                Err(_) => Some(format!("Missing: {}", file.path)),
+            })
+            .collect()
+    }
+    
+    /// Narrow a version down to the files that have to be installed again to repair it
+    ///
+    /// Installers that never completed are enough when the recorded files are intact. Missing
+    /// or changed files could come from any installer, so then the version and its patches are
+    /// installed again along with the DLC that was installed before.
+    pub fn repair_version(&self, game: &GameInfo, version: &GameVersion) -> GameVersion {
+        let installed = self.installed_version(game);
+        let applied: Vec<String> = installed.as_ref()
+            .map(|installed| installed.files.clone())
+            .unwrap_or_default();
+        
+        let contents_intact = installed.as_ref()
+            .map_or(false, |installed| self.damaged_contents(game, installed).is_empty());
+        let patches_applied = version.required_patches.iter()
+            .all(|patch| applied.contains(&patch.name));
+        
+        let mut repair = version.clone();
+        repair.files.extend(game.dlc.iter().filter(|file| applied.contains(&file.name)).cloned());
+        
+        if contents_intact && patches_applied {
+            repair.files.retain(|file| !applied.contains(&file.name));
+            repair.required_patches.clear();
+        }
+        
+        repair
+    }
+    
+    /// Launch an installed game and record when it was played
+    pub fn launch(&self, game: &GameInfo) -> Result<Child> {
+        let executable = self.find_executable(game)
+            .ok_or_else(|| anyhow::anyhow!("No executable found for {}", game.title))?;
+        
+        info!("Launching {}: {}", game.title, executable.display());
+        
+        let mut command = Command::new(&executable);
+        if let Some(working_dir) = executable.parent() {
+            command.current_dir(working_dir);
+        }
+        
+        let child = command.spawn()
+            .with_context(|| format!("Failed to launch {}", executable.display()))?;
+        
+        if let Some(mut installed) = self.installed_version(game) {
+            installed.last_played = Some(chrono::Local::now().to_rfc3339());
+            if let Err(e) = self.write_marker(&self.game_dir(game), &installed) {
+                warn!("Failed to record last played time for {}: {}", game.title, e);
+            }
+        }
+        
+        Ok(child)
+    }
+    
+    /// Add a finished play session to the game's accumulated playtime
+    pub fn record_playtime(&self, game: &GameInfo, played: Duration) -> Result<()> {
+        let mut installed = self.installed_version(game)
+            .ok_or_else(|| anyhow::anyhow!("{} has no installation marker", game.title))?;
+        
+        installed.total_playtime_secs += played.as_secs();
+        
+        self.write_marker(&self.game_dir(game), &installed)?;
+        
+        info!("Recorded {}s of playtime for {}", played.as_secs(), game.title);
+        Ok(())
+    }
+    
+    /// Register games that were installed without this application
+    ///
+    /// Each folder of the install directory without a marker is matched to a library game by ID,
+    /// or failing that by title, and gets a marker so the game shows as installed. Matches whose
+    /// folder isn't named after the game ID only count once added to `InstallConfig::folders`.
+    pub fn scan_install_dir(&self, games: &[GameInfo]) -> Result<InstallScan> {
+        let install_root = &self.config.paths.install_dir;
+        info!("Scanning {} for installed games", install_root.display());
+        
+        let entries = std::fs::read_dir(install_root)
+            .with_context(|| format!("Failed to read {}", install_root.display()))?;
+        
+        let mut folders: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().map_or(false, |file_type| file_type.is_dir()))
+            .filter(|entry| !Self::has_marker(&entry.path()))
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        folders.sort();
+        
+        // Each game can only be matched to one folder
+        let mut candidates: Vec<&GameInfo> = games.iter()
+            .filter(|game| !self.is_installed(game))
+            .collect();
+        let threshold = self.config.igdb.match_threshold;
+        
+        let mut scan = InstallScan::default();
+        for folder in folders {
+            let by_id = candidates.iter()
+                .position(|game| game.id.eq_ignore_ascii_case(&folder));
+            let by_title = || {
+                candidates.iter()
+                    .enumerate()
+                    .map(|(index, game)| (index, title_similarity(&folder, &game.title)))
+                    .filter(|(_, similarity)| *similarity >= threshold)
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(index, _)| index)
+            };
+            
+            let Some(index) = by_id.or_else(by_title) else {
+                scan.unmatched.push(folder);
+                continue;
+            };
+            let game = candidates.remove(index);
+            
+            let install_dir = install_root.join(&folder);
+            let installed = InstalledVersion {
+                game_id: game.id.clone(),
+                version_name: "Unknown".to_string(),
+                build: 0,
+                files: Vec::new(),
+                installed_at: chrono::Local::now().to_rfc3339(),
+                executable: Self::detect_executable(&install_dir)
+                    .and_then(|exe| exe.strip_prefix(&install_dir).ok().map(|p| p.to_string_lossy().to_string())),
+                last_played: None,
+                total_playtime_secs: 0,
+                contents: Self::list_contents(&install_dir),
+            };
+            self.write_marker(&install_dir, &installed)
+                .with_context(|| format!("Failed to register {}", install_dir.display()))?;
+            
+            info!("Registered {} as installed in {}", game.title, install_dir.display());
+            scan.matched.push((game.id.clone(), folder));
+        }
+        
+        Ok(scan)
+    }
+    
+    /// Write the installation marker for a game
+    fn write_marker(&self, install_dir: &Path, installed: &InstalledVersion) -> Result<()> {
+        let json_str = serde_json::to_string_pretty(installed)
+            .context("Failed to serialize installation marker")?;
+        
+        std::fs::write(install_dir.join(INSTALL_MARKER), json_str)
+            .context("Failed to write installation marker")?;
+        
+        Ok(())
     }
 }
 
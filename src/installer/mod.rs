@@ -1,7 +1,9 @@
 pub mod download;
 pub mod install;
+pub mod queue;
 pub mod version;
 
 pub use download::Downloader;
-pub use install::Installer;
+pub use install::{InstallScan, Installer, InstalledVersion};
+pub use queue::{JobStatus, QueuedJob};
 pub use version::VersionManager;
\ No newline at end of file
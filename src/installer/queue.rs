@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::repository::{GameInfo, GameVersion};
+
+/// State of a job in the installation queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum JobStatus {
+    /// Waiting for the jobs before it
+    Queued,
+    /// Being downloaded and installed
+    Active,
+    /// Installed successfully
+    Done,
+    /// Failed or cancelled
+    Failed,
+}
+
+/// Installation waiting in, running from or finished from the queue
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct QueuedJob {
+    /// Number identifying the job in the queue
+    pub id: u64,
+    /// Game to install
+    pub game: GameInfo,
+    /// Version to install, with any chosen DLC added to its files
+    pub version: GameVersion,
+    /// Current state
+    pub status: JobStatus,
+    /// Outcome of a finished job
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl QueuedJob {
+    /// Check whether the job still has to run or is running
+    pub fn is_pending(&self) -> bool {
+        matches!(self.status, JobStatus::Queued | JobStatus::Active)
+    }
+}
+
+/// Get the path of the queue file
+pub fn path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join("install_queue.json")
+}
+
+/// Load the queue saved by the last run
+///
+/// A job that was running when the application closed is queued again.
+pub fn load(cache_dir: &Path) -> Vec<QueuedJob> {
+    let path = path(cache_dir);
+    
+    if !path.exists() {
+        return Vec::new();
+    }
+    
+    let result = fs::read_to_string(&path)
+        .context("Failed to read installation queue")
+        .and_then(|json_str| {
+            serde_json::from_str::<Vec<QueuedJob>>(&json_str)
+                .context("Failed to parse installation queue")
+        });
+    
+    match result {
+        Ok(mut jobs) => {
+            for job in jobs.iter_mut().filter(|job| job.status == JobStatus::Active) {
+                job.status = JobStatus::Queued;
+            }
+            jobs
+        }
+        Err(e) => {
+            warn!("{}: {}", path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+/// Save the queue to the cache directory
+pub fn save(cache_dir: &Path, jobs: &[QueuedJob]) -> Result<()> {
+    if !cache_dir.exists() {
+        fs::create_dir_all(cache_dir)?;
+    }
+    
+    let json_str = serde_json::to_string(jobs)
+        .context("Failed to serialize installation queue")?;
+    
+    crate::fs_util::write_atomic(&path(cache_dir), json_str)
+}
@@ -3,7 +3,7 @@ use log::{info, warn, error};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-use crate::repository::{GameInfo, GameVersion, GameFile, FileType};
+use crate::repository::{GameInfo, GameVersion, GameFile, FileType, follow_patch_chain};
 
 /// Manager for game versions and patches
 #[derive(Clone)]
@@ -31,9 +31,9 @@ impl VersionManager {
     pub fn get_required_files<'a>(&self, version: &'a GameVersion) -> Vec<&'a GameFile> {
         let mut files = Vec::new();
         
-        // Add installer files
+        // Add installer and chosen DLC files; archives cannot be installed yet
         for file in &version.files {
-            if file.file_type == FileType::Installer {
+            if matches!(file.file_type, FileType::Installer | FileType::Dlc) {
                 files.push(file);
             }
         }
@@ -52,9 +52,107 @@ impl VersionManager {
     }
     
     /// Get patch files ordered by version sequence
-    pub fn get_ordered_patches<'a>(&self, version: &'a GameVersion) -> Vec<&'a GameFile> {
-        // In a full implementation, this would sort patches in proper sequence
-        // For now, we'll just return them as-is
-        version.required_patches.iter().collect()
+    ///
+    /// Patches are chained by their from/to build numbers starting at the version's build.
+    /// Patches without build numbers are applied last, in name order. Returns an error if a
+    /// patch expects a build that no earlier step produces.
+    pub fn get_ordered_patches<'a>(&self, version: &'a GameVersion) -> Result<Vec<&'a GameFile>> {
+        let (versioned, mut unversioned): (Vec<&GameFile>, Vec<&GameFile>) = version.required_patches
+            .iter()
+            .partition(|patch| patch.patch_builds().is_some());
+        
+        let (mut ordered, end_build) = follow_patch_chain(version.build, &versioned);
+        
+        // Anything left over is unreachable from the base build
+        if let Some(stranded) = versioned.iter().find(|patch| !ordered.iter().any(|p| std::ptr::eq(*p, **patch))) {
+            let (from, _) = stranded.patch_builds().unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "Patch chain has a gap: {} expects build {} but the chain from build {} ends at build {}",
+                stranded.name, from, version.build, end_build
+            ));
+        }
+        
+        unversioned.sort_by(|a, b| a.name.cmp(&b.name));
+        ordered.extend(unversioned);
+        
+        Ok(ordered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn file(name: &str, file_type: FileType) -> GameFile {
+        GameFile {
+            name: name.to_string(),
+            remote_path: format!("game/{}", name),
+            size: 0,
+            file_type,
+            sha256: None,
+        }
+    }
+    
+    /// Parse the versions of a game with the given installers and patches
+    fn game_with(installers: &[&str], patches: &[&str]) -> GameInfo {
+        let mut game = GameInfo {
+            files: installers.iter().map(|name| file(name, FileType::Installer))
+                .chain(patches.iter().map(|name| file(name, FileType::Patch)))
+                .collect(),
+            ..Default::default()
+        };
+        game.parse_versions();
+        game
     }
-}
\ No newline at end of file
+    
+    fn ordered_names(version: &GameVersion) -> Vec<String> {
+        VersionManager::new()
+            .get_ordered_patches(version)
+            .unwrap()
+            .iter()
+            .map(|patch| patch.name.clone())
+            .collect()
+    }
+    
+    #[test]
+    fn installs_default_version_next_to_numbered_patches() {
+        let game = game_with(
+            &["setup_game.exe"],
+            &["patch_game_build_2055_(37083)_to_build_2172_(47150).exe", "patch_game_hotfix.exe"],
+        );
+        
+        assert_eq!(ordered_names(&game.versions[0]), ["patch_game_hotfix.exe"]);
+    }
+    
+    #[test]
+    fn installs_each_release_line_with_its_own_patches() {
+        let game = game_with(
+            &["setup_game_build_1000.exe", "setup_game_build_2000.exe"],
+            &[
+                "patch_game_build_1500_to_build_1600.exe",
+                "patch_game_build_1000_to_build_1500.exe",
+                "patch_game_build_2000_to_build_2100.exe",
+            ],
+        );
+        
+        let by_name = |name: &str| game.versions.iter().find(|version| version.name == name).unwrap();
+        assert_eq!(ordered_names(by_name("Build 1000")), [
+            "patch_game_build_1000_to_build_1500.exe",
+            "patch_game_build_1500_to_build_1600.exe",
+        ]);
+        assert_eq!(ordered_names(by_name("Build 2000")), ["patch_game_build_2000_to_build_2100.exe"]);
+    }
+    
+    #[test]
+    fn rejects_patches_that_do_not_follow_the_build() {
+        let version = GameVersion {
+            name: "Build 1000".to_string(),
+            build: 1000,
+            version_key: (1000, 0, 0, 0),
+            files: vec![file("setup_game_build_1000.exe", FileType::Installer)],
+            required_patches: vec![file("patch_game_build_1600_to_build_1700.exe", FileType::Patch)],
+        };
+        
+        assert!(VersionManager::new().get_ordered_patches(&version).is_err());
+    }
+}
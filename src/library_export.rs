@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::fs_util;
+use crate::metadata::handler::MetadataHandler;
+use crate::metadata::UserOverrides;
+use crate::repository::GameInfo;
+
+/// Column headers of the CSV export, in the order of the `LibraryRow` fields
+const CSV_HEADERS: [&str; 10] = [
+    "id",
+    "title",
+    "developer",
+    "publisher",
+    "release_date",
+    "rating",
+    "genres",
+    "version_count",
+    "installed",
+    "total_size",
+];
+
+/// One game of a library export
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryRow {
+    pub id: String,
+    pub title: String,
+    pub developer: Option<String>,
+    pub publisher: Option<String>,
+    pub release_date: Option<String>,
+    /// IGDB total rating (0 - 100)
+    pub rating: Option<f32>,
+    pub genres: Vec<String>,
+    pub version_count: usize,
+    pub installed: bool,
+    /// Size in bytes of the files of every version
+    pub total_size: u64,
+}
+
+impl LibraryRow {
+    /// Build the row for a game, preferring values entered by the user, then cached
+    /// IGDB metadata, then the repository info
+    pub fn new(game: &GameInfo, installed: bool, metadata_handler: Option<&MetadataHandler>) -> Self {
+        let metadata = metadata_handler.and_then(|handler| handler.get_metadata(&game.id));
+        let igdb_data = metadata.and_then(|metadata| metadata.igdb_data.as_ref());
+        let overrides = metadata.map(|metadata| &metadata.user_overrides);
+        let user_value = |field: fn(&UserOverrides) -> &Option<String>| overrides.and_then(|o| field(o).clone());
+        
+        let companies = |developer: bool| {
+            let names: Vec<&str> = igdb_data
+                .and_then(|igdb_data| igdb_data.involved_companies.as_ref())
+                .into_iter()
+                .flatten()
+                .filter(|c| if developer { c.developer } else { c.publisher })
+                .map(|c| c.company.name.as_str())
+                .collect();
+            
+            (!names.is_empty()).then(|| names.join(", "))
+        };
+        
+        let release_date = igdb_data
+            .and_then(|igdb_data| igdb_data.release_date())
+            .map(|date| date.format("%Y-%m-%d").to_string());
+        
+        Self {
+            id: game.id.clone(),
+            title: user_value(|o| &o.title).unwrap_or_else(|| game.title.clone()),
+            developer: user_value(|o| &o.developer)
+                .or_else(|| companies(true))
+                .or_else(|| game.developer.clone()),
+            publisher: user_value(|o| &o.publisher)
+                .or_else(|| companies(false))
+                .or_else(|| game.publisher.clone()),
+            release_date: user_value(|o| &o.release_date)
+                .or(release_date)
+                .or_else(|| game.release_date.clone()),
+            rating: igdb_data.and_then(|igdb_data| igdb_data.total_rating),
+            genres: igdb_data
+                .and_then(|igdb_data| igdb_data.genres.as_ref())
+                .into_iter()
+                .flatten()
+                .map(|genre| genre.name.clone())
+                .collect(),
+            version_count: game.versions.len(),
+            installed,
+            total_size: game.versions_size(),
+        }
+    }
+    
+    /// Values of the row as CSV fields
+    fn csv_fields(&self) -> [String; 10] {
+        [
+            self.id.clone(),
+            self.title.clone(),
+            self.developer.clone().unwrap_or_default(),
+            self.publisher.clone().unwrap_or_default(),
+            self.release_date.clone().unwrap_or_default(),
+            self.rating.map(|rating| format!("{:.1}", rating)).unwrap_or_default(),
+            self.genres.join("; "),
+            self.version_count.to_string(),
+            self.installed.to_string(),
+            self.total_size.to_string(),
+        ]
+    }
+}
+
+/// Build the export rows for every game in the library
+pub fn library_rows(
+    games: &[GameInfo],
+    installed_games: &HashSet<String>,
+    metadata_handler: Option<&MetadataHandler>,
+) -> Vec<LibraryRow> {
+    games.iter()
+        .map(|game| LibraryRow::new(game, installed_games.contains(&game.id), metadata_handler))
+        .collect()
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render rows as CSV with a header line
+fn to_csv(rows: &[LibraryRow]) -> String {
+    let mut csv = CSV_HEADERS.join(",");
+    csv.push_str("\r\n");
+    
+    for row in rows {
+        let fields: Vec<String> = row.csv_fields().iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    
+    csv
+}
+
+/// Write rows to `path`, as JSON if it has a `.json` extension and as CSV otherwise
+pub fn write_library(path: &Path, rows: &[LibraryRow]) -> Result<()> {
+    let is_json = path.extension()
+        .map_or(false, |extension| extension.eq_ignore_ascii_case("json"));
+    
+    let contents = if is_json {
+        serde_json::to_string_pretty(rows).context("Failed to serialize library")?
+    } else {
+        to_csv(rows)
+    };
+    
+    fs_util::write_atomic(path, contents)
+}
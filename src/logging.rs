@@ -0,0 +1,187 @@
+use anyhow::{Context, Result};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+use crate::config::LoggingConfig;
+
+/// Name of the current log file in the log directory
+const LOG_FILE_NAME: &str = "app.log";
+
+/// Lines kept in memory until the log file is opened
+const MAX_PENDING_LINES: usize = 1000;
+
+static LOGGER: OnceLock<AppLogger> = OnceLock::new();
+
+/// Logger writing to stderr and, once configured, to a rotating log file
+struct AppLogger {
+    stderr: env_logger::Logger,
+    file: Mutex<FileTarget>,
+}
+
+/// Where file log lines go
+enum FileTarget {
+    /// Lines logged before the log directory is known
+    Pending(Vec<String>),
+    /// Log file that is open for appending
+    Open(RotatingFile),
+}
+
+/// Log file that is moved aside once it grows past a size limit
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    /// Open `path` for appending
+    fn open(path: PathBuf, max_size: u64, max_files: usize) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+        let size = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        
+        Ok(Self { path, file, size, max_size, max_files })
+    }
+    
+    /// Append a line, rotating first if it would push the file past its limit
+    fn write_line(&mut self, line: &str) {
+        if self.size > 0 && self.size + line.len() as u64 > self.max_size {
+            if let Err(e) = self.rotate() {
+                eprintln!("Failed to rotate log file: {:#}", e);
+            }
+        }
+        
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+    
+    /// Shift `app.log.N` to `app.log.N+1`, dropping the oldest, and start a new file
+    fn rotate(&mut self) -> Result<()> {
+        for index in (1..self.max_files).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        
+        if self.max_files > 0 {
+            fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        } else {
+            fs::remove_file(&self.path)?;
+        }
+        
+        *self = Self::open(self.path.clone(), self.max_size, self.max_files)?;
+        Ok(())
+    }
+}
+
+/// Path of the `index`th rotated log file
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+    
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        
+        self.stderr.log(record);
+        
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        
+        match &mut *self.file.lock().unwrap() {
+            FileTarget::Pending(lines) => {
+                if lines.len() < MAX_PENDING_LINES {
+                    lines.push(line);
+                }
+            }
+            FileTarget::Open(file) => file.write_line(&line),
+        }
+    }
+    
+    fn flush(&self) {
+        self.stderr.flush();
+        if let FileTarget::Open(file) = &mut *self.file.lock().unwrap() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+/// Install the logger, writing to stderr until `start_file_logging` is called
+pub fn init() {
+    let stderr = env_logger::Builder::new()
+        .filter_level(LevelFilter::Trace)
+        .parse_default_env()
+        .build();
+    
+    let logger = LOGGER.get_or_init(|| AppLogger {
+        stderr,
+        file: Mutex::new(FileTarget::Pending(Vec::new())),
+    });
+    
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(LevelFilter::Info);
+    }
+}
+
+/// Directory the log files are written to
+pub fn log_dir(cache_dir: &Path) -> PathBuf {
+    cache_dir.join("logs")
+}
+
+/// Open the log file in `log_dir` and apply the configured level
+///
+/// Lines logged since `init` are written to the file first.
+pub fn start_file_logging(config: &LoggingConfig, log_dir: &Path) -> Result<()> {
+    let level = LevelFilter::from_str(&config.level).unwrap_or_else(|_| {
+        log::warn!("Unknown log level '{}', using info", config.level);
+        LevelFilter::Info
+    });
+    log::set_max_level(level);
+    
+    let Some(logger) = LOGGER.get() else {
+        return Ok(());
+    };
+    
+    fs::create_dir_all(log_dir)
+        .with_context(|| format!("Failed to create log directory {}", log_dir.display()))?;
+    
+    let mut file = RotatingFile::open(
+        log_dir.join(LOG_FILE_NAME),
+        config.max_file_size_mb.max(1) * 1024 * 1024,
+        config.max_files,
+    )?;
+    
+    let mut target = logger.file.lock().unwrap();
+    if let FileTarget::Pending(lines) = &*target {
+        for line in lines {
+            file.write_line(line);
+        }
+    }
+    *target = FileTarget::Open(file);
+    
+    Ok(())
+}
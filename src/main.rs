@@ -1,22 +1,34 @@
+mod cli;
 mod config;
+mod fs_util;
+mod logging;
 mod repository;
 mod metadata;
 mod installer;
+mod library_export;
+mod system;
 mod ui;
 
 use anyhow::Result;
 use eframe::NativeOptions;
-use log::{info, LevelFilter};
+use log::{info, warn};
 use std::path::PathBuf;
+use std::process::ExitCode;
 
 use config::Config;
 use ui::app::GameLibraryApp;
 
-fn main() -> Result<()> {
-    // Initialize logger
-    env_logger::Builder::new()
-        .filter_level(LevelFilter::Info)
-        .init();
+fn main() -> Result<ExitCode> {
+    // Initialize logger; the log file is opened once the configuration is known
+    logging::init();
+    
+    let command = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            cli::print_usage_error(&e);
+            return Ok(ExitCode::from(2));
+        }
+    };
     
     info!("Starting Game Library Manager");
     
@@ -24,17 +36,30 @@ fn main() -> Result<()> {
     let config = match Config::load() {
         Ok(config) => config,
         Err(e) => {
-            eprintln!("Error loading configuration: {}", e);
-            // Create default configuration if it doesn't exist
-            let config = Config::default();
-            config.save()?;
+            // Leave the unreadable file alone so it can be fixed by hand
+            eprintln!("Error loading configuration, using defaults: {:#}", e);
+            let mut config = Config::default();
+            config.apply_env_overrides();
             config
         }
     };
     
-    // GUI Options
+    let log_dir = logging::log_dir(&config.paths.cache_dir);
+    if let Err(e) = logging::start_file_logging(&config.logging, &log_dir) {
+        warn!("Failed to start file logging: {:#}", e);
+    }
+    
+    // Headless commands exit without creating the window
+    if let Some(command) = command {
+        return cli::run(command, config);
+    }
+    
+    // GUI Options, restoring the window from the last session
+    let window = &config.window;
     let options = NativeOptions {
-        initial_window_size: Some(egui::vec2(1280.0, 800.0)),
+        initial_window_size: Some(egui::vec2(window.width, window.height)),
+        initial_window_pos: window.position.map(|(x, y)| egui::pos2(x, y)),
+        maximized: window.maximized,
         icon_data: None, // TODO: Add application icon
         ..Default::default()
     };
@@ -45,5 +70,7 @@ fn main() -> Result<()> {
         options,
         Box::new(|cc| Box::new(GameLibraryApp::new(cc, config))),
     )
-    .map_err(|e| anyhow::anyhow!("Failed to run application: {}", e))
+    .map_err(|e| anyhow::anyhow!("Failed to run application: {}", e))?;
+    
+    Ok(ExitCode::SUCCESS)
 }
\ No newline at end of file
@@ -1,12 +1,16 @@
 use anyhow::{Context, Result};
 use log::{info, warn, error};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 use super::igdb::IgdbGame;
 
+/// Version of the metadata file format written by this build
+const SCHEMA_VERSION: u32 = 1;
+
 /// Metadata cache for storing and retrieving metadata
 #[derive(Clone)]
 pub struct MetadataCache {
@@ -16,6 +20,19 @@ pub struct MetadataCache {
     metadata: HashMap<String, CachedMetadata>,
 }
 
+/// Disk usage of the metadata cache
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    /// Number of games with a metadata file
+    pub game_count: usize,
+    /// Total size of the metadata files
+    pub metadata_bytes: u64,
+    /// Total size of the cached images
+    pub image_bytes: u64,
+    /// Number of cached images
+    pub image_count: usize,
+}
+
 /// Cached metadata entry
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CachedMetadata {
@@ -23,12 +40,108 @@ pub struct CachedMetadata {
     pub game_id: String,
     /// IGDB ID
     pub igdb_id: Option<u32>,
+    /// IGDB ID pinned by the user, overriding name matching
+    #[serde(default)]
+    pub igdb_id_override: Option<u32>,
+    /// Name of the provider the metadata came from
+    #[serde(default)]
+    pub source: Option<String>,
     /// IGDB metadata
     pub igdb_data: Option<IgdbGame>,
     /// Cover image path (relative to cache directory)
     pub cover_path: Option<String>,
     /// Last update timestamp
     pub last_updated: u64,
+    /// Version of the file format the entry was written with
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Values entered by the user, kept across re-fetches
+    #[serde(default)]
+    pub user_overrides: UserOverrides,
+}
+
+/// Metadata entered by the user, shown instead of the IGDB and repository values
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UserOverrides {
+    pub title: Option<String>,
+    pub developer: Option<String>,
+    pub publisher: Option<String>,
+    /// Release date as entered, such as "1993-12-10"
+    pub release_date: Option<String>,
+    pub description: Option<String>,
+}
+
+impl UserOverrides {
+    /// Check whether no value is overridden
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Upgrade a raw metadata entry from schema version `from` to the current one
+fn migrate_metadata(value: &mut serde_json::Value, from: u32) {
+    let Some(entry) = value.as_object_mut() else {
+        return;
+    };
+    
+    // Version 0 files predate versioning but already match version 1; add a
+    // step here for every later format change
+    debug_assert!(from < SCHEMA_VERSION);
+    
+    entry.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+}
+
+/// Parse a metadata entry, migrating it if it was written in an older format
+///
+/// Also returns whether the entry was migrated.
+fn parse_metadata(json_str: &str, game_id: &str) -> Result<(CachedMetadata, bool)> {
+    let mut value: serde_json::Value = serde_json::from_str(json_str)?;
+    
+    let version = value.get("schema_version")
+        .and_then(|version| version.as_u64())
+        .unwrap_or(0);
+    
+    if version >= u64::from(SCHEMA_VERSION) {
+        return Ok((serde_json::from_value(value)?, false));
+    }
+    
+    info!("Migrating metadata for game {} from schema version {}", game_id, version);
+    migrate_metadata(&mut value, version as u32);
+    
+    let metadata = match serde_json::from_value(value.clone()) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            warn!("Metadata for game {} could not be migrated, it will be fetched again: {}", game_id, e);
+            salvage_metadata(&value, game_id)
+        }
+    };
+    
+    Ok((metadata, true))
+}
+
+/// Rebuild an entry that no longer deserializes, keeping what identifies the game
+///
+/// The IGDB data is dropped and the entry marked as never updated so the
+/// next refresh fetches it again.
+fn salvage_metadata(value: &serde_json::Value, game_id: &str) -> CachedMetadata {
+    let id_field = |name: &str| value.get(name)
+        .and_then(|field| field.as_u64())
+        .and_then(|id| u32::try_from(id).ok());
+    
+    CachedMetadata {
+        game_id: game_id.to_string(),
+        igdb_id: id_field("igdb_id"),
+        igdb_id_override: id_field("igdb_id_override"),
+        source: None,
+        igdb_data: None,
+        cover_path: value.get("cover_path").and_then(|path| path.as_str()).map(str::to_string),
+        last_updated: 0,
+        schema_version: SCHEMA_VERSION,
+        user_overrides: value.get("user_overrides")
+            .and_then(|overrides| serde_json::from_value(overrides.clone()).ok())
+            .unwrap_or_default(),
+    }
 }
 
 impl MetadataCache {
@@ -52,6 +165,16 @@ impl MetadataCache {
         Ok(cache)
     }
     
+    /// Copy of the cache that shares its directory but holds no entries
+    ///
+    /// Entries are read in with `reload_metadata` as they are needed.
+    pub fn detached(&self) -> Self {
+        Self {
+            cache_dir: self.cache_dir.clone(),
+            metadata: HashMap::new(),
+        }
+    }
+    
     /// Get metadata directory
     pub fn metadata_dir(&self) -> PathBuf {
         self.cache_dir.join("metadata")
@@ -83,6 +206,7 @@ impl MetadataCache {
             .with_context(|| format!("Failed to read metadata directory: {}", metadata_dir.display()))?;
         
         let mut loaded = 0;
+        let mut migrated = 0;
         
         for entry in entries {
             let entry = entry?;
@@ -101,9 +225,10 @@ impl MetadataCache {
             
             // Load metadata
             match self.load_metadata_file(&path) {
-                Ok(metadata) => {
+                Ok((metadata, was_migrated)) => {
                     self.metadata.insert(game_id.to_string(), metadata);
                     loaded += 1;
+                    migrated += usize::from(was_migrated);
                 }
                 Err(e) => {
                     warn!("Failed to load metadata for game {}: {}", game_id, e);
@@ -111,19 +236,32 @@ impl MetadataCache {
             }
         }
         
-        info!("Loaded metadata for {} games", loaded);
+        info!("Loaded metadata for {} games ({} migrated)", loaded, migrated);
         Ok(())
     }
     
-    /// Load metadata from a file
-    fn load_metadata_file(&self, path: &Path) -> Result<CachedMetadata> {
+    /// Load metadata from a file, upgrading entries written in an older format
+    ///
+    /// Also returns whether the entry was migrated. Migrated entries are
+    /// written back so each file is only upgraded once.
+    fn load_metadata_file(&self, path: &Path) -> Result<(CachedMetadata, bool)> {
         let json_str = fs::read_to_string(path)
             .with_context(|| format!("Failed to read metadata file: {}", path.display()))?;
         
-        let metadata: CachedMetadata = serde_json::from_str(&json_str)
+        let game_id = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+        let (metadata, migrated) = parse_metadata(&json_str, game_id)
             .with_context(|| format!("Failed to parse metadata file: {}", path.display()))?;
         
-        Ok(metadata)
+        if !migrated {
+            return Ok((metadata, false));
+        }
+        
+        let json_str = serde_json::to_string_pretty(&metadata)
+            .context("Failed to serialize metadata")?;
+        crate::fs_util::write_atomic(path, json_str)
+            .with_context(|| format!("Failed to write metadata file: {}", path.display()))?;
+        
+        Ok((metadata, true))
     }
     
     /// Load metadata for a specific game
@@ -136,7 +274,7 @@ impl MetadataCache {
         let path = self.get_metadata_path(game_id);
         
         if path.exists() {
-            let metadata = self.load_metadata_file(&path)?;
+            let (metadata, _) = self.load_metadata_file(&path)?;
             self.metadata.insert(game_id.to_string(), metadata.clone());
             return Ok(metadata);
         }
@@ -148,6 +286,18 @@ impl MetadataCache {
         Ok(metadata)
     }
     
+    /// Re-read metadata for a specific game from disk
+    pub fn reload_metadata(&mut self, game_id: &str) -> Result<()> {
+        let path = self.get_metadata_path(game_id);
+        
+        if path.exists() {
+            let (metadata, _) = self.load_metadata_file(&path)?;
+            self.metadata.insert(game_id.to_string(), metadata);
+        }
+        
+        Ok(())
+    }
+    
     /// Save metadata for a specific game
     pub fn save_metadata(&mut self, metadata: CachedMetadata) -> Result<()> {
         let game_id = metadata.game_id.clone();
@@ -168,7 +318,7 @@ impl MetadataCache {
         let json_str = serde_json::to_string_pretty(&metadata)
             .context("Failed to serialize metadata")?;
         
-        fs::write(&path, json_str)
+        crate::fs_util::write_atomic(&path, json_str)
             .with_context(|| format!("Failed to write metadata file: {}", path.display()))?;
         
         info!("Saved metadata for game {}", game_id);
@@ -200,22 +350,49 @@ impl MetadataCache {
         self.get_cover_path(game_id).exists()
     }
     
+    /// Get path for a cached hero artwork image
+    pub fn get_hero_path(&self, game_id: &str) -> PathBuf {
+        self.images_dir().join(format!("{}_hero.jpg", game_id))
+    }
+    
+    /// Check if a hero artwork image exists
+    pub fn has_hero(&self, game_id: &str) -> bool {
+        self.get_hero_path(game_id).exists()
+    }
+    
+    /// Get path for the `index`-th cached screenshot
+    pub fn get_screenshot_path(&self, game_id: &str, index: usize) -> PathBuf {
+        self.images_dir().join(format!("{}_screenshot_{}.jpg", game_id, index))
+    }
+    
+    /// Get paths of all downloaded screenshots, in order
+    pub fn get_screenshot_paths(&self, game_id: &str) -> Vec<PathBuf> {
+        (0..)
+            .map(|index| self.get_screenshot_path(game_id, index))
+            .take_while(|path| path.exists())
+            .collect()
+    }
+    
     /// Create a new metadata entry
     pub fn create_metadata(&self, game_id: &str) -> CachedMetadata {
         CachedMetadata {
             game_id: game_id.to_string(),
             igdb_id: None,
+            igdb_id_override: None,
+            source: None,
             igdb_data: None,
             cover_path: None,
             last_updated: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
+            schema_version: SCHEMA_VERSION,
+            user_overrides: UserOverrides::default(),
         }
     }
     
     /// Update metadata with IGDB data
-    pub fn update_with_igdb(&mut self, game_id: &str, igdb_game: IgdbGame) -> Result<()> {
+    pub fn update_with_igdb(&mut self, game_id: &str, igdb_game: IgdbGame, source: &str) -> Result<()> {
         // Load existing metadata or create new
         let mut metadata = if self.has_metadata(game_id) {
             self.get_metadata(game_id)
@@ -227,6 +404,7 @@ impl MetadataCache {
         
         // Update fields
         metadata.igdb_id = Some(igdb_game.id);
+        metadata.source = Some(source.to_string());
         metadata.igdb_data = Some(igdb_game);
         metadata.last_updated = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -237,6 +415,27 @@ impl MetadataCache {
         self.save_metadata(metadata)
     }
     
+    /// Record that a game's cover has been placed at the cached cover path
+    pub fn set_cover_file(&mut self, game_id: &str) -> Result<()> {
+        let mut metadata = self.load_metadata(game_id)?;
+        metadata.cover_path = Some(format!("images/{}_cover.jpg", game_id));
+        self.save_metadata(metadata)
+    }
+    
+    /// Pin or unpin the IGDB ID used for a game
+    pub fn set_igdb_id_override(&mut self, game_id: &str, igdb_id: Option<u32>) -> Result<()> {
+        let mut metadata = self.load_metadata(game_id)?;
+        metadata.igdb_id_override = igdb_id;
+        self.save_metadata(metadata)
+    }
+    
+    /// Replace the values the user entered for a game
+    pub fn set_user_overrides(&mut self, game_id: &str, overrides: UserOverrides) -> Result<()> {
+        let mut metadata = self.load_metadata(game_id)?;
+        metadata.user_overrides = overrides;
+        self.save_metadata(metadata)
+    }
+    
     /// Update cover path in metadata
     pub fn update_cover_path(&mut self, game_id: &str, relative_path: &str) -> Result<()> {
         if let Some(metadata) = self.get_metadata_mut(game_id) {
@@ -254,6 +453,279 @@ impl MetadataCache {
         Ok(())
     }
     
+    /// Count the files in a cache directory and add up their sizes
+    fn dir_usage(dir: &Path) -> Result<(usize, u64)> {
+        let mut count = 0;
+        let mut bytes = 0;
+        
+        if !dir.exists() {
+            return Ok((count, bytes));
+        }
+        
+        let entries = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read cache directory: {}", dir.display()))?;
+        
+        for entry in entries {
+            let metadata = entry?.metadata()?;
+            if metadata.is_file() {
+                count += 1;
+                bytes += metadata.len();
+            }
+        }
+        
+        Ok((count, bytes))
+    }
+    
+    /// Measure how much disk space the cache is using
+    pub fn stats(&self) -> Result<CacheStats> {
+        let (game_count, metadata_bytes) = Self::dir_usage(&self.metadata_dir())?;
+        let (image_count, image_bytes) = Self::dir_usage(&self.images_dir())?;
+        
+        Ok(CacheStats {
+            game_count,
+            metadata_bytes,
+            image_bytes,
+            image_count,
+        })
+    }
+    
+    /// Delete the least recently used covers until they fit in `max_bytes`
+    ///
+    /// Covers of games outside `library` are evicted first; covers of library
+    /// games only go if that is not enough. Returns how many were deleted.
+    pub fn enforce_cover_budget(&mut self, max_bytes: u64, library: &HashSet<String>) -> Result<usize> {
+        let images_dir = self.images_dir();
+        if !images_dir.exists() {
+            return Ok(0);
+        }
+        
+        let entries = fs::read_dir(&images_dir)
+            .with_context(|| format!("Failed to read cache directory: {}", images_dir.display()))?;
+        
+        let mut covers = Vec::new();
+        let mut total = 0;
+        
+        for entry in entries {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(game_id) = file_name.strip_suffix("_cover.jpg") else {
+                continue;
+            };
+            
+            let metadata = entry.metadata()?;
+            let last_used = metadata.accessed()
+                .or_else(|_| metadata.modified())
+                .unwrap_or(UNIX_EPOCH);
+            
+            total += metadata.len();
+            covers.push((library.contains(game_id), last_used, metadata.len(), game_id.to_string(), entry.path()));
+        }
+        
+        if total <= max_bytes {
+            return Ok(0);
+        }
+        
+        // Games outside the library first, then oldest first
+        covers.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+        
+        let mut evicted = 0;
+        
+        for (_, _, size, game_id, path) in covers {
+            if total <= max_bytes {
+                break;
+            }
+            
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Failed to evict cover {}: {}", path.display(), e);
+                continue;
+            }
+            
+            total -= size;
+            evicted += 1;
+            
+            let metadata = self.load_metadata(&game_id)?;
+            if metadata.cover_path.is_some() {
+                self.save_metadata(CachedMetadata { cover_path: None, ..metadata })?;
+            }
+        }
+        
+        info!("Evicted {} covers to stay within {} bytes", evicted, max_bytes);
+        Ok(evicted)
+    }
+    
+    /// Bundle all cached metadata into a single zip file at `path`
+    ///
+    /// Cover images are included when `include_covers` is set. Returns the
+    /// number of games exported.
+    pub fn export_all(&self, path: &Path, include_covers: bool) -> Result<usize> {
+        let file = crate::fs_util::temp_file_for(path)?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::FileOptions::default();
+        
+        for (game_id, metadata) in &self.metadata {
+            let json_str = serde_json::to_string_pretty(metadata)
+                .context("Failed to serialize metadata")?;
+            zip.start_file(format!("metadata/{}.json", game_id), options)?;
+            zip.write_all(json_str.as_bytes())?;
+            
+            let cover_path = self.get_cover_path(game_id);
+            if include_covers && cover_path.exists() {
+                let bytes = fs::read(&cover_path)
+                    .with_context(|| format!("Failed to read cover: {}", cover_path.display()))?;
+                zip.start_file(format!("images/{}_cover.jpg", game_id), options)?;
+                zip.write_all(&bytes)?;
+            }
+        }
+        
+        let file = zip.finish()
+            .with_context(|| format!("Failed to write metadata export: {}", path.display()))?;
+        crate::fs_util::persist(file, path)?;
+        
+        info!("Exported metadata for {} games to {}", self.metadata.len(), path.display());
+        Ok(self.metadata.len())
+    }
+    
+    /// Restore metadata from a bundle written by `export_all`
+    ///
+    /// An imported entry replaces an existing one unless it is older. Returns
+    /// the number of games imported.
+    pub fn import_all(&mut self, path: &Path) -> Result<usize> {
+        let file = fs::File::open(path)
+            .with_context(|| format!("Failed to open metadata export: {}", path.display()))?;
+        let mut zip = zip::ZipArchive::new(file)
+            .with_context(|| format!("Not a metadata export: {}", path.display()))?;
+        
+        let game_ids: Vec<String> = zip.file_names()
+            .filter_map(|name| name.strip_prefix("metadata/")?.strip_suffix(".json"))
+            // Entry names become file names, so never let them leave the cache
+            .filter(|game_id| !game_id.is_empty() && !game_id.contains(['/', '\\']) && *game_id != "..")
+            .map(str::to_string)
+            .collect();
+        
+        let mut imported = 0;
+        
+        for game_id in game_ids {
+            let mut json_str = String::new();
+            zip.by_name(&format!("metadata/{}.json", game_id))?
+                .read_to_string(&mut json_str)?;
+            
+            let (mut metadata, _) = match parse_metadata(&json_str, &game_id) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    warn!("Skipping unreadable metadata for game {}: {}", game_id, e);
+                    continue;
+                }
+            };
+            
+            let existing_updated = self.get_metadata(&game_id).map(|existing| existing.last_updated);
+            if existing_updated.map_or(false, |updated| updated > metadata.last_updated) {
+                info!("Keeping newer cached metadata for game {}", game_id);
+                continue;
+            }
+            
+            let cover_entry = format!("images/{}_cover.jpg", game_id);
+            if let Ok(mut cover) = zip.by_name(&cover_entry) {
+                let mut bytes = Vec::new();
+                cover.read_to_end(&mut bytes)?;
+                crate::fs_util::write_atomic(&self.get_cover_path(&game_id), bytes)?;
+                metadata.cover_path = Some(cover_entry);
+            }
+            
+            metadata.game_id = game_id;
+            self.save_metadata(metadata)?;
+            imported += 1;
+        }
+        
+        info!("Imported metadata for {} games from {}", imported, path.display());
+        Ok(imported)
+    }
+    
+    /// Entry holding only the values the user entered, if there are any
+    fn user_entry(&self, metadata: &CachedMetadata) -> Option<CachedMetadata> {
+        if metadata.user_overrides.is_empty() && metadata.igdb_id_override.is_none() {
+            return None;
+        }
+        
+        Some(CachedMetadata {
+            igdb_id_override: metadata.igdb_id_override,
+            user_overrides: metadata.user_overrides.clone(),
+            // Never fresh, so the next refresh fetches everything else again
+            last_updated: 0,
+            ..self.create_metadata(&metadata.game_id)
+        })
+    }
+    
+    /// Remove cached metadata and images for a single game
+    ///
+    /// Values the user entered and a pinned IGDB ID are not cache and are kept.
+    pub fn clear_game(&mut self, game_id: &str) -> Result<()> {
+        let kept = self.load_metadata(game_id).ok()
+            .and_then(|metadata| self.user_entry(&metadata));
+        
+        let mut paths = vec![
+            self.get_metadata_path(game_id),
+            self.get_cover_path(game_id),
+            self.get_hero_path(game_id),
+        ];
+        paths.extend(self.get_screenshot_paths(game_id));
+        
+        for path in paths.iter().filter(|path| path.exists()) {
+            fs::remove_file(path)
+                .with_context(|| format!("Failed to remove cached file: {}", path.display()))?;
+        }
+        
+        self.metadata.remove(game_id);
+        
+        if let Some(kept) = kept {
+            self.save_metadata(kept)?;
+        }
+        
+        info!("Cleared cached metadata for game {}", game_id);
+        Ok(())
+    }
+    
+    /// Remove all cached metadata and images
+    ///
+    /// Values the user entered and pinned IGDB IDs are not cache and are kept.
+    pub fn clear_all(&mut self) -> Result<()> {
+        let kept: Vec<CachedMetadata> = fs::read_dir(self.metadata_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+            .filter_map(|path| self.load_metadata_file(&path).ok())
+            .filter_map(|(metadata, _)| self.user_entry(&metadata))
+            .collect();
+        
+        for dir in [self.metadata_dir(), self.images_dir()] {
+            if !dir.exists() {
+                continue;
+            }
+            
+            let entries = fs::read_dir(&dir)
+                .with_context(|| format!("Failed to read cache directory: {}", dir.display()))?;
+            
+            for entry in entries {
+                let path = entry?.path();
+                if path.is_file() {
+                    fs::remove_file(&path)
+                        .with_context(|| format!("Failed to remove cached file: {}", path.display()))?;
+                }
+            }
+        }
+        
+        self.metadata.clear();
+        
+        let kept_count = kept.len();
+        for metadata in kept {
+            self.save_metadata(metadata)?;
+        }
+        
+        info!("Cleared metadata cache in {}, kept user values for {} games", self.cache_dir.display(), kept_count);
+        Ok(())
+    }
+    
     /// Check if metadata is stale (older than specified days)
     pub fn is_stale(&self, game_id: &str, days: u64) -> bool {
         if let Some(metadata) = self.get_metadata(game_id) {
@@ -263,9 +735,8 @@ impl MetadataCache {
                 .as_secs();
             
             let age_seconds = now.saturating_sub(metadata.last_updated);
-            let age_days = age_seconds / 86400; // 86400 seconds in a day
             
-            age_days > days
+            age_seconds > days.saturating_mul(86400) // 86400 seconds in a day
         } else {
             true
         }
@@ -1,11 +1,18 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use futures::future;
+use futures::stream::{self, StreamExt};
 use log::{info, warn, error};
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc::UnboundedSender; // Updated import
-use crate::config::IgdbConfig;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use crate::config::{IgdbConfig, MetadataConfig};
+use crate::repository::GameInfo;
 use super::igdb::{IgdbClient, IgdbGame};
-use super::cache::{MetadataCache, CachedMetadata};
+use super::cache::{MetadataCache, CachedMetadata, CacheStats, UserOverrides};
+use super::local::LocalMetadataProvider;
+use super::provider::MetadataProvider;
+use super::steamgriddb::SteamGridDbClient;
 
 /// Metadata operation status
 #[derive(Debug, Clone)]
@@ -18,34 +25,91 @@ pub enum MetadataStatus {
     Failed { game_id: String, game_name: String, error: String },
     /// Progress update
     Progress { completed: usize, total: usize },
-    /// Operation completed
-    Completed { successful: usize, failed: usize, total: usize },
+    /// Operation completed, listing the `(game_id, game_name)` pairs that failed
+    Completed { successful: usize, failed: usize, total: usize, failed_games: Vec<(String, String)> },
+}
+
+/// Why no metadata could be fetched for a game
+#[derive(Debug, thiserror::Error)]
+pub enum MetadataError {
+    /// No metadata provider has the credentials or files it needs
+    #[error("No metadata source is configured, add an IGDB Client ID and Secret in the settings")]
+    NotConfigured,
+    /// Every provider was asked and none knew the game
+    #[error("No matching game found on IGDB")]
+    NoMatch,
+    /// Offline mode is enabled and nothing is cached for the game
+    #[error("Offline, and no metadata is cached")]
+    Offline,
 }
 
 /// Metadata handler for managing game metadata
 #[derive(Clone)]
 pub struct MetadataHandler {
-    igdb_client: IgdbClient,
+    /// Metadata sources, tried in order
+    providers: Vec<Box<dyn MetadataProvider>>,
+    /// Cover fallback for games whose metadata has no cover
+    steamgriddb: SteamGridDbClient,
     cache: MetadataCache,
-    progress_tx: Option<UnboundedSender<MetadataStatus>>, // Updated field type
+    progress_tx: Option<UnboundedSender<MetadataStatus>>,
     last_refresh: std::collections::HashMap<String, Instant>,
+    /// Maximum number of games refreshed at the same time
+    max_concurrent: usize,
+    /// Days after which cached metadata is considered stale
+    stale_days: u64,
+    /// Size limit for cached covers in bytes (0 disables the limit)
+    max_cover_bytes: u64,
+    /// Serve metadata and images from the cache only, never contacting a provider
+    offline: bool,
 }
 
 impl MetadataHandler {
     /// Create a new metadata handler
-    pub fn new(igdb_config: IgdbConfig, cache_dir: PathBuf) -> Result<Self> {
-        let igdb_client = IgdbClient::new(igdb_config);
+    pub fn new(igdb_config: IgdbConfig, metadata_config: MetadataConfig, cache_dir: PathBuf) -> Result<Self> {
+        let match_threshold = igdb_config.match_threshold;
+        let max_concurrent = metadata_config.max_concurrent_refreshes.max(1);
+        let igdb_client = IgdbClient::new(igdb_config)
+            .with_token_cache(cache_dir.join("igdb_token.json"));
+        // SteamGridDB covers go through the same proxy and timeouts as IGDB
+        let steamgriddb = SteamGridDbClient::new(metadata_config.steamgriddb_api_key, igdb_client.http_client());
+        let local_provider = LocalMetadataProvider::new(cache_dir.join("local"), match_threshold);
         let cache = MetadataCache::new(cache_dir)?;
         
         Ok(Self {
-            igdb_client,
+            providers: vec![Box::new(igdb_client), Box::new(local_provider)],
+            steamgriddb,
             cache,
             progress_tx: None,
             last_refresh: std::collections::HashMap::new(),
+            max_concurrent,
+            stale_days: metadata_config.stale_days,
+            max_cover_bytes: metadata_config.max_cover_cache_mb.saturating_mul(1024 * 1024),
+            offline: false,
         })
     }
     
+    /// Serve everything from the cache without contacting any provider
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+    
+    /// Return an error if the handler is in offline mode
+    fn ensure_online(&self) -> Result<()> {
+        if self.offline {
+            return Err(MetadataError::Offline.into());
+        }
+        Ok(())
+    }
+    
+    /// Check whether IGDB credentials are set, without which most lookups find nothing
+    pub fn is_igdb_configured(&self) -> bool {
+        self.providers.iter().any(|provider| provider.name() == "igdb" && provider.is_configured())
+    }
+    
     /// Set progress channel
+    ///
+    /// The channel is unbounded so status can be sent from sync code and
+    /// drained with `try_recv` on the UI thread.
     pub fn set_progress_channel(&mut self, tx: UnboundedSender<MetadataStatus>) {
         self.progress_tx = Some(tx);
     }
@@ -64,24 +128,50 @@ impl MetadataHandler {
         // Load cached metadata
         self.cache.load_all()?;
         
-        // Try to authenticate with IGDB if credentials are configured
-        if self.igdb_client.is_configured() {
-            match self.igdb_client.authenticate().await {
-                Ok(_) => info!("Successfully authenticated with IGDB"),
-                Err(e) => warn!("Failed to authenticate with IGDB: {}", e),
-            }
-        } else {
-            warn!("IGDB credentials not configured");
+        if self.offline {
+            info!("Offline mode, serving metadata from the cache only");
+            return Ok(());
         }
         
+        self.initialize_providers().await;
+        
         Ok(())
     }
     
+    /// Prepare every configured provider, e.g. authenticate with IGDB
+    ///
+    /// Providers that are already prepared, such as IGDB with a valid token, return straight away.
+    async fn initialize_providers(&mut self) {
+        for provider in &mut self.providers {
+            if !provider.is_configured() {
+                warn!("Metadata provider {} not configured", provider.name());
+                continue;
+            }
+            
+            match provider.initialize().await {
+                Ok(_) => info!("Initialized metadata provider {}", provider.name()),
+                Err(e) => warn!("Failed to initialize metadata provider {}: {}", provider.name(), e),
+            }
+        }
+    }
+    
     /// Get metadata for a game
     pub fn get_metadata(&self, game_id: &str) -> Option<&CachedMetadata> {
         self.cache.get_metadata(game_id)
     }
     
+    /// Get the values the user entered for a game
+    pub fn user_overrides(&self, game_id: &str) -> Option<&UserOverrides> {
+        self.get_metadata(game_id).map(|metadata| &metadata.user_overrides)
+    }
+    
+    /// Get the title to show for a game, the user's own if they entered one
+    pub fn display_title<'a>(&'a self, game: &'a GameInfo) -> &'a str {
+        self.user_overrides(&game.id)
+            .and_then(|overrides| overrides.title.as_deref())
+            .unwrap_or(&game.title)
+    }
+    
     /// Check if a game has IGDB metadata
     pub fn has_igdb_metadata(&self, game_id: &str) -> bool {
         if let Some(metadata) = self.cache.get_metadata(game_id) {
@@ -101,96 +191,296 @@ impl MetadataHandler {
         self.cache.get_cover_path(game_id)
     }
     
-    /// Search IGDB for a game by name
+    /// Check if a game has a hero artwork image
+    pub fn has_hero(&self, game_id: &str) -> bool {
+        self.cache.has_hero(game_id)
+    }
+    
+    /// Get hero artwork image path
+    pub fn get_hero_path(&self, game_id: &str) -> PathBuf {
+        self.cache.get_hero_path(game_id)
+    }
+    
+    /// Record a cover copied into the cache from outside the handler
+    pub fn register_cover(&mut self, game_id: &str) -> Result<()> {
+        let expected = format!("images/{}_cover.jpg", game_id);
+        let registered = self.get_metadata(game_id)
+            .map_or(false, |metadata| metadata.cover_path.as_deref() == Some(expected.as_str()));
+        
+        if registered {
+            return Ok(());
+        }
+        
+        self.cache.set_cover_file(game_id)
+    }
+    
+    /// Replace a game's cover with a user-supplied image file
+    pub fn import_cover(&mut self, game_id: &str, source: &std::path::Path) -> Result<()> {
+        // Decode first so an unreadable file never replaces a working cover
+        let image = image::open(source)
+            .with_context(|| format!("Not a readable image: {}", source.display()))?;
+        
+        let mut bytes = Vec::new();
+        image.to_rgb8()
+            .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageOutputFormat::Jpeg(75))
+            .context("Failed to encode cover")?;
+        
+        let cover_path = self.cache.get_cover_path(game_id);
+        crate::fs_util::write_atomic(&cover_path, bytes)
+            .with_context(|| format!("Failed to write cover: {}", cover_path.display()))?;
+        
+        info!("Set custom cover for game {} from {}", game_id, source.display());
+        self.cache.set_cover_file(game_id)
+    }
+    
+    /// Get paths of downloaded screenshots
+    pub fn get_screenshot_paths(&self, game_id: &str) -> Vec<PathBuf> {
+        self.cache.get_screenshot_paths(game_id)
+    }
+    
+    /// Re-read a game's metadata from disk after another handler updated it
+    pub fn reload_metadata(&mut self, game_id: &str) -> Result<()> {
+        self.cache.reload_metadata(game_id)
+    }
+    
+    /// Get the disk usage of the metadata cache
+    pub fn cache_stats(&self) -> Result<CacheStats> {
+        self.cache.stats()
+    }
+    
+    /// Evict old covers once they exceed the configured size limit
+    ///
+    /// `library` holds the IDs of the games currently in the library, whose
+    /// covers are kept for as long as possible.
+    pub fn enforce_cover_budget(&mut self, library: &std::collections::HashSet<String>) -> Result<usize> {
+        if self.max_cover_bytes == 0 {
+            return Ok(0);
+        }
+        
+        self.cache.enforce_cover_budget(self.max_cover_bytes, library)
+    }
+    
+    /// Bundle all cached metadata, and optionally covers, into one file
+    pub fn export_metadata(&self, path: &std::path::Path, include_covers: bool) -> Result<usize> {
+        self.cache.export_all(path, include_covers)
+    }
+    
+    /// Restore metadata from a file written by `export_metadata`
+    pub fn import_metadata(&mut self, path: &std::path::Path) -> Result<usize> {
+        self.cache.import_all(path)
+    }
+    
+    /// Drop a game's cached metadata and images
+    pub fn clear_game_metadata(&mut self, game_id: &str) -> Result<()> {
+        self.last_refresh.remove(game_id);
+        self.cache.clear_game(game_id)
+    }
+    
+    /// Drop all cached metadata and images
+    pub fn clear_cache(&mut self) -> Result<()> {
+        self.last_refresh.clear();
+        self.cache.clear_all()
+    }
+    
+    /// Pin the IGDB ID for a game so refreshes skip name matching
+    pub fn set_igdb_id_override(&mut self, game_id: &str, igdb_id: Option<u32>) -> Result<()> {
+        self.cache.set_igdb_id_override(game_id, igdb_id)
+    }
+    
+    /// Replace the values the user entered for a game
+    pub fn set_user_overrides(&mut self, game_id: &str, overrides: UserOverrides) -> Result<()> {
+        self.cache.set_user_overrides(game_id, overrides)
+    }
+    
+    /// Get the provider that supplied a game's metadata, defaulting to the first one
+    fn provider_for(&mut self, game_id: &str) -> Option<&mut Box<dyn MetadataProvider>> {
+        let source = self.get_metadata(game_id).and_then(|metadata| metadata.source.clone());
+        
+        match source {
+            Some(source) => self.providers.iter_mut().find(|provider| provider.name() == source),
+            None => self.providers.first_mut(),
+        }
+    }
+    
+    /// Search for a game by name, returning results from the first provider that has any
     pub async fn search_game(&mut self, name: &str) -> Result<Vec<IgdbGame>> {
-        self.igdb_client.search_game(name).await
+        self.ensure_online()?;
+        
+        let mut last_error = None;
+        
+        for provider in self.providers.iter_mut().filter(|provider| provider.is_configured()) {
+            match provider.search(name).await {
+                Ok(games) if !games.is_empty() => return Ok(games),
+                Ok(_) => {}
+                Err(e) => {
+                    warn!("{} search failed for '{}': {}", provider.name(), name, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(Vec::new()),
+        }
     }
     
     /// Find best match for a game name
     pub async fn find_best_match(&mut self, name: &str) -> Result<Option<IgdbGame>> {
-        self.igdb_client.find_best_match(name).await
+        Ok(self.lookup(name, None).await?.map(|(_, game)| game))
+    }
+    
+    /// Look a game up by ID or name in each provider in turn
+    ///
+    /// Returns the name of the provider that matched along with the game. If
+    /// no provider matched and any of them failed, the last error is returned.
+    /// Fails with `MetadataError::NotConfigured` only when no provider is configured.
+    async fn lookup(&mut self, name: &str, id: Option<u32>) -> Result<Option<(String, IgdbGame)>> {
+        self.ensure_online()?;
+        
+        let mut last_error = None;
+        let mut asked_any = false;
+        
+        for provider in self.providers.iter_mut().filter(|provider| provider.is_configured()) {
+            asked_any = true;
+            let result = match id {
+                Some(id) => provider.get_by_id(id).await,
+                None => provider.find_best_match(name).await,
+            };
+            
+            match result {
+                Ok(Some(game)) => return Ok(Some((provider.name().to_string(), game))),
+                Ok(None) => info!("No {} match found for game: {}", provider.name(), name),
+                Err(e) => {
+                    warn!("{} lookup failed for game {}: {}", provider.name(), name, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        
+        if !asked_any {
+            return Err(MetadataError::NotConfigured.into());
+        }
+        
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
     }
     
     /// Fetch metadata for a game and update cache
-    pub async fn fetch_and_cache_metadata(&mut self, game_id: &str, game_name: &str) -> Result<bool> {
+    ///
+    /// A user-pinned IGDB ID takes precedence over `igdb_id` from the game's
+    /// info file; with neither, the game is matched by name. Fresh cached
+    /// metadata is reused unless `force` is set. In offline mode any cached
+    /// metadata is reused, however old.
+    ///
+    /// Fails with a `MetadataError` when nothing could be looked up or matched.
+    pub async fn fetch_and_cache_metadata(&mut self, game_id: &str, game_name: &str, igdb_id: Option<u32>, force: bool) -> Result<()> {
         self.send_status(MetadataStatus::Started {
             game_id: game_id.to_string(),
             game_name: game_name.to_string(),
         });
         
-        if self.has_igdb_metadata(game_id) && !self.cache.is_stale(game_id, 30) {
-            info!("Using cached metadata for game {}", game_id);
-            self.last_refresh.insert(game_id.to_string(), Instant::now());
-            self.send_status(MetadataStatus::Success {
+        let result = self.fetch_metadata(game_id, game_name, igdb_id, force).await;
+        
+        match &result {
+            Ok(()) => self.send_status(MetadataStatus::Success {
                 game_id: game_id.to_string(),
                 game_name: game_name.to_string(),
-            });
-            return Ok(true);
+            }),
+            Err(e) => self.send_status(MetadataStatus::Failed {
+                game_id: game_id.to_string(),
+                game_name: game_name.to_string(),
+                error: format!("{:#}", e),
+            }),
+        }
+        
+        result
+    }
+    
+    /// Fetch metadata for a game and update cache without reporting its status
+    ///
+    /// Library refreshes report each game themselves once its images are done.
+    async fn fetch_metadata(&mut self, game_id: &str, game_name: &str, igdb_id: Option<u32>, force: bool) -> Result<()> {
+        let cached = self.get_metadata(game_id);
+        let pinned_id = cached.and_then(|metadata| metadata.igdb_id_override).or(igdb_id);
+        let matches_pin = pinned_id.is_none() || cached.and_then(|metadata| metadata.igdb_id) == pinned_id;
+        
+        let is_fresh = !force && !self.cache.is_stale(game_id, self.stale_days) && matches_pin;
+        
+        if self.has_igdb_metadata(game_id) && (is_fresh || self.offline) {
+            info!("Using cached metadata for game {}", game_id);
+            self.last_refresh.insert(game_id.to_string(), Instant::now());
+            return Ok(());
         }
         
         info!("Fetching metadata for game: {} ({})", game_id, game_name);
         
-        let igdb_game = match self.find_best_match(game_name).await {
-            Ok(Some(game)) => game,
+        if let Some(id) = pinned_id {
+            info!("Using IGDB ID {} for game {}", id, game_id);
+        }
+        
+        let (source, igdb_game) = match self.lookup(game_name, pinned_id).await {
+            Ok(Some(found)) => found,
             Ok(None) => {
                 warn!("No IGDB match found for game: {}", game_name);
-                self.send_status(MetadataStatus::Failed {
-                    game_id: game_id.to_string(),
-                    game_name: game_name.to_string(),
-                    error: "No matching game found on IGDB".to_string(),
-                });
-                return Ok(false);
+                return Err(MetadataError::NoMatch.into());
+            }
+            Err(e) if e.is::<MetadataError>() => {
+                warn!("Skipping metadata lookup for game {}: {}", game_name, e);
+                return Err(e);
             }
             Err(e) => {
                 error!("IGDB search error for game {}: {}", game_name, e);
-                self.send_status(MetadataStatus::Failed {
-                    game_id: game_id.to_string(),
-                    game_name: game_name.to_string(),
-                    error: format!("IGDB API error: {}", e),
-                });
-                return Err(e);
+                return Err(e.context("IGDB API error"));
             }
         };
         
-        info!("Found IGDB match for {}: {} (ID: {})", 
-            game_name, igdb_game.name, igdb_game.id);
+        info!("Found {} match for {}: {} (ID: {})", 
+            source, game_name, igdb_game.name, igdb_game.id);
         
-        self.cache.update_with_igdb(game_id, igdb_game)?;
+        self.cache.update_with_igdb(game_id, igdb_game, &source)?;
         self.last_refresh.insert(game_id.to_string(), Instant::now());
-        self.send_status(MetadataStatus::Success {
-            game_id: game_id.to_string(),
-            game_name: game_name.to_string(),
-        });
         
-        Ok(true)
+        Ok(())
     }
     
-    /// Download and cache cover image
-    pub async fn download_cover(&mut self, game_id: &str, size: &str) -> Result<bool> {
-        let cover_image_id: Option<String> = {
-            match self.get_metadata(game_id) {
-                Some(metadata) => match &metadata.igdb_data {
-                    Some(igdb_data) => igdb_data.cover.as_ref().map(|cover| cover.image_id.clone()),
-                    None => None,
-                },
-                None => None,
-            }
-        };
-        
-        let cover_image_id = match cover_image_id {
-            Some(id) => id,
+    /// Download and cache cover image, replacing an existing one if `force` is set
+    pub async fn download_cover(&mut self, game_id: &str, size: &str, force: bool) -> Result<bool> {
+        let (game_name, cover_image_id) = match self.get_metadata(game_id) {
+            Some(metadata) => match &metadata.igdb_data {
+                Some(igdb_data) => (
+                    igdb_data.name.clone(),
+                    igdb_data.cover.as_ref().map(|cover| cover.image_id.clone()),
+                ),
+                None => return Ok(false),
+            },
             None => return Ok(false),
         };
         
         let cover_path = self.cache.get_cover_path(game_id);
         
-        if cover_path.exists() {
+        if cover_path.exists() && !force {
             return Ok(true);
         }
         
+        if self.offline {
+            return Ok(cover_path.exists());
+        }
+        
+        let cover_image_id = match cover_image_id {
+            Some(id) => id,
+            None => return self.download_fallback_cover(game_id, &game_name, &cover_path).await,
+        };
+        
         info!("Downloading cover for game {}", game_id);
         
-        match self.igdb_client.download_cover(&cover_image_id, size, &cover_path).await {
+        let Some(provider) = self.provider_for(game_id) else {
+            return Ok(false);
+        };
+        
+        match provider.download_cover(&cover_image_id, size, &cover_path).await {
             Ok(_) => {
                 let relative_path = format!("images/{}_cover.jpg", game_id);
                 self.cache.update_cover_path(game_id, &relative_path)?;
@@ -203,87 +493,244 @@ impl MetadataHandler {
         }
     }
     
+    /// Download a cover from SteamGridDB for a game whose metadata has none
+    async fn download_fallback_cover(&mut self, game_id: &str, game_name: &str, cover_path: &std::path::Path) -> Result<bool> {
+        if !self.steamgriddb.is_configured() {
+            return Ok(false);
+        }
+        
+        info!("No cover in metadata for game {}, trying SteamGridDB", game_id);
+        
+        match self.steamgriddb.download_cover(game_name, cover_path).await {
+            Ok(true) => {
+                let relative_path = format!("images/{}_cover.jpg", game_id);
+                self.cache.update_cover_path(game_id, &relative_path)?;
+                Ok(true)
+            }
+            Ok(false) => Ok(false),
+            Err(e) => {
+                error!("Failed to download SteamGridDB cover for game {}: {}", game_id, e);
+                Ok(false)
+            }
+        }
+    }
+    
+    /// Download and cache the first artwork as the hero banner
+    pub async fn download_hero(&mut self, game_id: &str, size: &str) -> Result<bool> {
+        let artwork_image_id = self.get_metadata(game_id)
+            .and_then(|metadata| metadata.igdb_data.as_ref())
+            .and_then(|igdb_data| igdb_data.artworks.as_ref())
+            .and_then(|artworks| artworks.first())
+            .map(|artwork| artwork.image_id.clone());
+        
+        let artwork_image_id = match artwork_image_id {
+            Some(id) => id,
+            None => return Ok(false),
+        };
+        
+        let hero_path = self.cache.get_hero_path(game_id);
+        
+        if hero_path.exists() {
+            return Ok(true);
+        }
+        
+        if self.offline {
+            return Ok(false);
+        }
+        
+        info!("Downloading hero artwork for game {}", game_id);
+        
+        let Some(provider) = self.provider_for(game_id) else {
+            return Ok(false);
+        };
+        
+        match provider.download_artwork(&artwork_image_id, size, &hero_path).await {
+            Ok(_) => Ok(true),
+            Err(e) => {
+                error!("Failed to download hero artwork for game {}: {}", game_id, e);
+                Ok(false)
+            }
+        }
+    }
+    
+    /// Download and cache screenshots, returning how many are available
+    pub async fn download_screenshots(&mut self, game_id: &str, size: &str) -> Result<usize> {
+        let image_ids: Vec<String> = self.get_metadata(game_id)
+            .and_then(|metadata| metadata.igdb_data.as_ref())
+            .and_then(|igdb_data| igdb_data.screenshots.as_ref())
+            .map(|screenshots| screenshots.iter().map(|s| s.image_id.clone()).collect())
+            .unwrap_or_default();
+        
+        let mut available = 0;
+        
+        for (index, image_id) in image_ids.iter().enumerate() {
+            let path = self.cache.get_screenshot_path(game_id, index);
+            
+            if !path.exists() {
+                if self.offline {
+                    break;
+                }
+                
+                let Some(provider) = self.provider_for(game_id) else {
+                    break;
+                };
+                
+                if let Err(e) = provider.download_screenshot(image_id, size, &path).await {
+                    // Screenshots are numbered contiguously, so stop at the first gap
+                    error!("Failed to download screenshot {} for game {}: {}", index, game_id, e);
+                    break;
+                }
+            }
+            
+            available += 1;
+        }
+        
+        Ok(available)
+    }
+    
     /// Refresh metadata for a game
-    pub async fn refresh_metadata(&mut self, game_id: &str, game_name: &str) -> Result<bool> {
+    ///
+    /// With `force` set, IGDB is queried and the cover downloaded again even
+    /// if the cached entry is still fresh.
+    pub async fn refresh_metadata(&mut self, game_id: &str, game_name: &str, igdb_id: Option<u32>, force: bool) -> Result<()> {
         info!("Refreshing metadata for game: {} ({})", game_id, game_name);
         
-        let result = self.fetch_and_cache_metadata(game_id, game_name).await?;
+        self.fetch_and_cache_metadata(game_id, game_name, igdb_id, force).await?;
+        
+        if self.has_igdb_metadata(game_id) {
+            self.download_cover(game_id, "cover_big", force).await?;
+            self.download_hero(game_id, "720p").await?;
+            self.download_screenshots(game_id, "screenshot_med").await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Copy of the handler for library refresh tasks, without any cached entries
+    ///
+    /// Cloning the whole cache for every game would copy every entry once per game.
+    fn detached(&self) -> Self {
+        Self {
+            providers: self.providers.clone(),
+            steamgriddb: self.steamgriddb.clone(),
+            cache: self.cache.detached(),
+            progress_tx: self.progress_tx.clone(),
+            last_refresh: std::collections::HashMap::new(),
+            max_concurrent: self.max_concurrent,
+            stale_days: self.stale_days,
+            max_cover_bytes: self.max_cover_bytes,
+            offline: self.offline,
+        }
+    }
+    
+    /// Fetch metadata and images for one game of a library refresh
+    ///
+    /// Runs on a detached copy of the handler so several games can be refreshed
+    /// at once. The game's entry is read from the cache files, and the results
+    /// reach other handlers the same way.
+    async fn refresh_library_game(mut self, game_id: String, game_name: String, igdb_id: Option<u32>) -> (String, String, Result<()>) {
+        self.send_status(MetadataStatus::Started {
+            game_id: game_id.clone(),
+            game_name: game_name.clone(),
+        });
+        
+        if let Err(e) = self.cache.reload_metadata(&game_id) {
+            warn!("Failed to load cached metadata for game {}: {}", game_id, e);
+        }
+        
+        if self.has_igdb_metadata(&game_id) && (self.offline || !self.cache.is_stale(&game_id, self.stale_days)) {
+            return (game_id, game_name, Ok(()));
+        }
+        
+        let result = self.fetch_metadata(&game_id, &game_name, igdb_id, false).await;
         
-        if result && self.has_igdb_metadata(game_id) {
-            self.download_cover(game_id, "cover_big").await?;
+        if result.is_ok() {
+            let _ = self.download_cover(&game_id, "cover_big", false).await;
+            let _ = self.download_hero(&game_id, "720p").await;
+            let _ = self.download_screenshots(&game_id, "screenshot_med").await;
         }
         
-        Ok(result)
+        (game_id, game_name, result)
     }
     
     /// Update metadata for all games in the library
+    ///
+    /// Up to `max_concurrent_refreshes` games are fetched at a time and their
+    /// statuses are reported in the order they finish. Once `cancel` fires no
+    /// further games are started; games already in flight are allowed to finish.
     pub async fn update_library_metadata(
         &mut self,
-        games: &[(String, String)],
+        games: &[(String, String, Option<u32>)],
+        cancel: &CancellationToken,
     ) -> Result<()> {
         let total = games.len();
         let mut updated = 0;
-        let mut failed = 0;
+        let mut failed_games = Vec::new();
+        let mut completed = 0;
         
-        info!("Updating metadata for {} games", total);
+        info!("Updating metadata for {} games, {} at a time", total, self.max_concurrent);
         
         self.send_status(MetadataStatus::Progress {
             completed: 0,
             total,
         });
         
-        for (i, (game_id, game_name)) in games.iter().enumerate() {
-            info!("Processing game {}/{}: {}", i + 1, total, game_name);
-            self.send_status(MetadataStatus::Started {
-                game_id: game_id.to_string(),
-                game_name: game_name.to_string(),
-            });
+        // Authenticate once here, otherwise every concurrent copy would request its own token
+        if !self.offline {
+            self.initialize_providers().await;
+        }
+        
+        let template = self.detached();
+        let mut results = stream::iter(games)
+            .take_while(|_| future::ready(!cancel.is_cancelled()))
+            .map(move |(game_id, game_name, igdb_id)| {
+                template.clone().refresh_library_game(game_id.clone(), game_name.clone(), *igdb_id)
+            })
+            .buffer_unordered(self.max_concurrent);
+        
+        while let Some((game_id, game_name, result)) = results.next().await {
+            completed += 1;
+            info!("Processed game {}/{}: {}", completed, total, game_name);
             
-            if !self.has_igdb_metadata(game_id) || self.cache.is_stale(game_id, 30) {
-                match self.fetch_and_cache_metadata(game_id, game_name).await {
-                    Ok(true) => {
-                        let _ = self.download_cover(game_id, "cover_big").await;
-                        updated += 1;
-                        self.send_status(MetadataStatus::Success {
-                            game_id: game_id.to_string(),
-                            game_name: game_name.to_string(),
-                        });
-                    }
-                    Ok(false) => {
-                        failed += 1;
-                        self.send_status(MetadataStatus::Failed {
-                            game_id: game_id.to_string(),
-                            game_name: game_name.to_string(),
-                            error: "Could not find metadata".to_string(),
-                        });
-                    }
-                    Err(e) => {
-                        error!("Error updating metadata for game {}: {}", game_name, e);
-                        failed += 1;
-                        self.send_status(MetadataStatus::Failed {
-                            game_id: game_id.to_string(),
-                            game_name: game_name.to_string(),
-                            error: e.to_string(),
-                        });
-                    }
+            // The refresh ran on a copy, so pick its changes up from disk
+            if let Err(e) = self.cache.reload_metadata(&game_id) {
+                warn!("Failed to reload metadata for game {}: {}", game_id, e);
+            }
+            
+            match result {
+                Ok(()) => {
+                    updated += 1;
+                    self.last_refresh.insert(game_id.clone(), Instant::now());
+                    self.send_status(MetadataStatus::Success { game_id, game_name });
+                }
+                Err(e) => {
+                    error!("Error updating metadata for game {}: {:#}", game_name, e);
+                    failed_games.push((game_id.clone(), game_name.clone()));
+                    self.send_status(MetadataStatus::Failed {
+                        game_id,
+                        game_name,
+                        error: format!("{:#}", e),
+                    });
                 }
-            } else {
-                self.send_status(MetadataStatus::Success {
-                    game_id: game_id.to_string(),
-                    game_name: game_name.to_string(),
-                });
             }
             
             self.send_status(MetadataStatus::Progress {
-                completed: i + 1,
+                completed,
                 total,
             });
         }
         
+        if cancel.is_cancelled() {
+            info!("Metadata update cancelled after {}/{} games", completed, total);
+        }
+        
+        let failed = failed_games.len();
+        
         self.send_status(MetadataStatus::Completed {
             successful: updated,
             failed,
             total,
+            failed_games,
         });
         
         info!("Updated metadata for {}/{} games ({} failed)", updated, total, failed);
@@ -298,12 +745,12 @@ impl MetadataHandler {
         
         info!("Starting batch metadata update for {} games", total);
         
-        let game_pairs: Vec<(String, String)> = games
+        let game_pairs: Vec<(String, String, Option<u32>)> = games
             .iter()
-            .map(|(id, name)| (id.to_string(), name.to_string()))
+            .map(|(id, name)| (id.to_string(), name.to_string(), None))
             .collect();
         
-        self.update_library_metadata(&game_pairs).await?;
+        self.update_library_metadata(&game_pairs, &CancellationToken::new()).await?;
         
         Ok(())
     }
@@ -2,7 +2,10 @@ use anyhow::{Context, Result};
 use log::{info, warn, error};
 use reqwest::{Client, header};
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
 use crate::config::IgdbConfig;
 
 /// IGDB game information
@@ -10,17 +13,60 @@ use crate::config::IgdbConfig;
 pub struct IgdbGame {
     pub id: u32,
     pub name: String,
+    pub alternative_names: Option<Vec<IgdbAlternativeName>>,
     pub summary: Option<String>,
     pub storyline: Option<String>,
-    pub first_release_date: Option<u64>,
+    pub first_release_date: Option<i64>,
     pub cover: Option<IgdbCover>,
+    pub screenshots: Option<Vec<IgdbScreenshot>>,
+    pub artworks: Option<Vec<IgdbArtwork>>,
     pub involved_companies: Option<Vec<IgdbCompany>>,
     pub genres: Option<Vec<IgdbGenre>>,
     pub platforms: Option<Vec<IgdbPlatform>>,
+    pub game_modes: Option<Vec<IgdbGameMode>>,
+    pub game_engines: Option<Vec<IgdbGameEngine>>,
+    pub franchise: Option<IgdbFranchise>,
+    pub franchises: Option<Vec<IgdbFranchise>>,
+    pub collection: Option<IgdbCollection>,
+    pub similar_games: Option<Vec<IgdbSimilarGame>>,
     pub slug: Option<String>,
     pub url: Option<String>,
     pub total_rating: Option<f32>,
     pub total_rating_count: Option<u32>,
+    pub age_ratings: Option<Vec<IgdbAgeRating>>,
+}
+
+/// Latest release timestamp taken at face value (2100-01-01 UTC)
+const MAX_RELEASE_TIMESTAMP: i64 = 4_102_444_800;
+
+impl IgdbGame {
+    /// First release date in local time
+    ///
+    /// IGDB occasionally has zero, negative or far-future timestamps, which give `None`.
+    pub fn release_date(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        self.first_release_date
+            .filter(|timestamp| (1..MAX_RELEASE_TIMESTAMP).contains(timestamp))
+            .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp, 0))
+            .map(|date| date.with_timezone(&chrono::Local))
+    }
+    
+    /// Names of the series the game belongs to, from its collection and franchises, without duplicates
+    pub fn series(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        
+        let collection = self.collection.iter().map(|collection| collection.name.as_str());
+        let franchises = self.franchise.iter()
+            .chain(self.franchises.iter().flatten())
+            .map(|franchise| franchise.name.as_str());
+        
+        for name in collection.chain(franchises) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        
+        names
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -30,6 +76,26 @@ pub struct IgdbCover {
     pub image_id: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IgdbAlternativeName {
+    pub id: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IgdbScreenshot {
+    pub id: u32,
+    pub url: Option<String>,
+    pub image_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IgdbArtwork {
+    pub id: u32,
+    pub url: Option<String>,
+    pub image_id: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct IgdbCompany {
     pub id: u32,
@@ -50,6 +116,40 @@ pub struct IgdbGenre {
     pub name: String,
 }
 
+/// Game mode, such as single player or co-operative
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IgdbGameMode {
+    pub id: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IgdbGameEngine {
+    pub id: u32,
+    pub name: String,
+}
+
+/// Franchise, such as all Doom games
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IgdbFranchise {
+    pub id: u32,
+    pub name: String,
+}
+
+/// Series of games, usually narrower than a franchise
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IgdbCollection {
+    pub id: u32,
+    pub name: String,
+}
+
+/// Game IGDB recommends alongside another, with just enough to show and find it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IgdbSimilarGame {
+    pub id: u32,
+    pub name: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct IgdbPlatform {
     pub id: u32,
@@ -57,6 +157,46 @@ pub struct IgdbPlatform {
     pub slug: Option<String>,
 }
 
+/// Rating of a game by one age rating board
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IgdbAgeRating {
+    pub id: u32,
+    /// Rating board (1 = ESRB, 2 = PEGI, others are not shown)
+    pub category: Option<u32>,
+    /// Rating within the board
+    pub rating: Option<u32>,
+}
+
+impl IgdbAgeRating {
+    /// Name of the rating board, for the ESRB and PEGI boards only
+    pub fn board(&self) -> Option<&'static str> {
+        match self.category? {
+            1 => Some("ESRB"),
+            2 => Some("PEGI"),
+            _ => None,
+        }
+    }
+    
+    /// Badge label and description of the rating, such as ("M", "Mature 17+")
+    pub fn rating(&self) -> Option<(&'static str, &'static str)> {
+        match (self.category?, self.rating?) {
+            (1, 6) => Some(("RP", "Rating pending")),
+            (1, 7) => Some(("EC", "Early childhood")),
+            (1, 8) => Some(("E", "Everyone")),
+            (1, 9) => Some(("E10+", "Everyone 10+")),
+            (1, 10) => Some(("T", "Teen")),
+            (1, 11) => Some(("M", "Mature 17+")),
+            (1, 12) => Some(("AO", "Adults only 18+")),
+            (2, 1) => Some(("3", "Suitable for ages 3 and over")),
+            (2, 2) => Some(("7", "Suitable for ages 7 and over")),
+            (2, 3) => Some(("12", "Suitable for ages 12 and over")),
+            (2, 4) => Some(("16", "Suitable for ages 16 and over")),
+            (2, 5) => Some(("18", "Suitable for adults only")),
+            _ => None,
+        }
+    }
+}
+
 /// Twitch OAuth token response
 #[derive(Debug, Deserialize)]
 struct TwitchAuthResponse {
@@ -64,25 +204,313 @@ struct TwitchAuthResponse {
     expires_in: u64,
 }
 
+/// Maximum number of IGDB API requests per second
+const IGDB_REQUESTS_PER_SECOND: u32 = 4;
+
+/// Maximum number of retries for throttled or failed IGDB requests
+const MAX_RETRIES: u32 = 3;
+
+/// Initial delay between retries when no Retry-After header is sent
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Upper bound on how long a Retry-After header may make us wait
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Parse the Retry-After header of a response as a number of seconds
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|secs| Duration::from_secs(secs).min(MAX_RETRY_AFTER))
+}
+
+/// Request pacer shared by every clone of a client
+#[derive(Clone)]
+struct RateLimiter {
+    /// Minimum time between two requests
+    interval: Duration,
+    /// Earliest time the next request may be sent
+    next_slot: Arc<Mutex<tokio::time::Instant>>,
+}
+
+impl RateLimiter {
+    /// Create a rate limiter allowing `per_second` requests each second
+    fn new(per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / per_second.max(1),
+            next_slot: Arc::new(Mutex::new(tokio::time::Instant::now())),
+        }
+    }
+    
+    /// Wait until a request may be sent
+    async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(tokio::time::Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Lowercase a title and reduce it to alphanumeric words separated by single spaces
+fn normalize_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Levenshtein edit distance between two strings, by character
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current = vec![0; b_chars.len() + 1];
+    
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    
+    previous[b_chars.len()]
+}
+
+/// Normalized Levenshtein similarity between two strings (1.0 = identical)
+fn levenshtein_ratio(a: &str, b: &str) -> f32 {
+    let max_len = a.chars().count().max(b.chars().count());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f32 / max_len as f32
+}
+
+/// Token-set similarity, tolerant of word order and extra words like subtitles
+fn token_set_ratio(a: &str, b: &str) -> f32 {
+    let a_tokens: std::collections::BTreeSet<&str> = a.split(' ').collect();
+    let b_tokens: std::collections::BTreeSet<&str> = b.split(' ').collect();
+    
+    let join = |tokens: Vec<&str>| tokens.join(" ");
+    let common = join(a_tokens.intersection(&b_tokens).copied().collect());
+    let a_only = join(a_tokens.difference(&b_tokens).copied().collect());
+    let b_only = join(b_tokens.difference(&a_tokens).copied().collect());
+    
+    let combined = |rest: &str| if rest.is_empty() { common.clone() } else { format!("{} {}", common, rest).trim().to_string() };
+    let a_combined = combined(&a_only);
+    let b_combined = combined(&b_only);
+    
+    levenshtein_ratio(&common, &a_combined)
+        .max(levenshtein_ratio(&common, &b_combined))
+        .max(levenshtein_ratio(&a_combined, &b_combined))
+}
+
+/// Similarity between a search query and a candidate title (0.0-1.0)
+pub(crate) fn title_similarity(query: &str, candidate: &str) -> f32 {
+    let query = normalize_title(query);
+    let candidate = normalize_title(candidate);
+    
+    if query.is_empty() || candidate.is_empty() {
+        return 0.0;
+    }
+    
+    // Token-set matches are discounted slightly so an exact title beats a superset of it
+    levenshtein_ratio(&query, &candidate).max(0.95 * token_set_ratio(&query, &candidate))
+}
+
+/// Best similarity between a search query and a game's name or any of its alternative names
+fn game_similarity(query: &str, game: &IgdbGame) -> f32 {
+    let alternative_names = game.alternative_names.iter().flatten().map(|alt| alt.name.as_str());
+    
+    std::iter::once(game.name.as_str())
+        .chain(alternative_names)
+        .map(|candidate| title_similarity(query, candidate))
+        .fold(0.0, f32::max)
+}
+
+/// Pick the candidate most similar to `name`, or `None` if none reaches `threshold`
+///
+/// Ties keep the order the candidates were given in.
+pub(crate) fn pick_best_match(name: &str, games: Vec<IgdbGame>, threshold: f32) -> Option<IgdbGame> {
+    let best = games
+        .into_iter()
+        .map(|game| (game_similarity(name, &game), game))
+        .fold(None, |best: Option<(f32, IgdbGame)>, (score, game)| match best {
+            Some((best_score, _)) if best_score >= score => best,
+            _ => Some((score, game)),
+        });
+    
+    match best {
+        Some((score, game)) if score >= threshold => {
+            info!("Best match for '{}': {} (ID: {}, score {:.2})", name, game.name, game.id, score);
+            Some(game)
+        }
+        Some((score, game)) => {
+            warn!(
+                "Best match for '{}' is {} (ID: {}) with score {:.2}, below threshold {:.2}",
+                name, game.name, game.id, score, threshold
+            );
+            None
+        }
+        None => None,
+    }
+}
+
+/// Access token persisted between runs
+#[derive(Debug, Deserialize, Serialize)]
+struct StoredToken {
+    access_token: String,
+    /// Expiry as seconds since the Unix epoch
+    expires_at: u64,
+}
+
+/// Build the HTTP client for IGDB and image requests from the proxy and timeout settings
+///
+/// Without either setting this is the same as `Client::new()`.
+pub fn build_http_client(config: &IgdbConfig) -> Result<Client> {
+    let mut builder = Client::builder();
+    
+    let proxy = config.http_proxy.trim();
+    if !proxy.is_empty() {
+        let proxy = reqwest::Proxy::all(proxy)
+            .with_context(|| format!("Invalid HTTP proxy '{}'", proxy))?;
+        builder = builder.proxy(proxy);
+    }
+    
+    if config.http_timeout_secs > 0 {
+        let timeout = Duration::from_secs(config.http_timeout_secs);
+        builder = builder.connect_timeout(timeout).timeout(timeout);
+    }
+    
+    builder.build().context("Failed to create HTTP client")
+}
+
 /// IGDB API client
 #[derive(Clone)]
 pub struct IgdbClient {
     config: IgdbConfig,
     client: Client,
     access_token: Option<String>,
-    token_expiry: Option<Instant>,
+    token_expiry: Option<SystemTime>,
+    /// File the access token is persisted to, if any
+    token_path: Option<PathBuf>,
     base_url: String,
+    rate_limiter: RateLimiter,
+    /// Refuse every request instead of contacting IGDB
+    offline: bool,
 }
 
 impl IgdbClient {
     /// Create a new IGDB client
+    ///
+    /// Invalid proxy settings are logged and the default HTTP client is used instead.
     pub fn new(config: IgdbConfig) -> Self {
+        let client = build_http_client(&config).unwrap_or_else(|e| {
+            warn!("{:#}, using the default HTTP settings", e);
+            Client::new()
+        });
+        
         Self {
             config,
-            client: Client::new(),
+            client,
             access_token: None,
             token_expiry: None,
+            token_path: None,
             base_url: String::from("https://api.igdb.com/v4"),
+            rate_limiter: RateLimiter::new(IGDB_REQUESTS_PER_SECOND),
+            offline: false,
+        }
+    }
+    
+    /// Get the HTTP client, to share its proxy and timeout settings
+    pub fn http_client(&self) -> Client {
+        self.client.clone()
+    }
+    
+    /// Fail every request with an offline error instead of contacting IGDB
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+    
+    /// Return an error if the client is in offline mode
+    fn ensure_online(&self) -> Result<()> {
+        if self.offline {
+            return Err(anyhow::anyhow!("Offline mode is enabled, IGDB was not contacted"));
+        }
+        Ok(())
+    }
+    
+    /// Persist the access token to `path` and reuse a valid one from a previous run
+    pub fn with_token_cache(mut self, path: PathBuf) -> Self {
+        self.token_path = Some(path);
+        self.load_token();
+        self
+    }
+    
+    /// Load a previously persisted access token
+    fn load_token(&mut self) {
+        let Some(path) = &self.token_path else { return };
+        if !path.exists() {
+            return;
+        }
+        
+        let stored = std::fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| serde_json::from_str::<StoredToken>(&content).map_err(anyhow::Error::from));
+        
+        match stored {
+            Ok(stored) => {
+                self.access_token = Some(stored.access_token);
+                self.token_expiry = Some(UNIX_EPOCH + Duration::from_secs(stored.expires_at));
+                info!("Loaded IGDB access token from {}", path.display());
+            }
+            Err(e) => warn!("Failed to load IGDB access token from {}: {}", path.display(), e),
+        }
+    }
+    
+    /// Persist the current access token
+    fn save_token(&self) {
+        let (Some(path), Some(token), Some(expiry)) = (&self.token_path, &self.access_token, self.token_expiry) else {
+            return;
+        };
+        
+        let stored = StoredToken {
+            access_token: token.clone(),
+            expires_at: expiry.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        };
+        
+        let result = serde_json::to_string_pretty(&stored)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| std::fs::write(path, content).map_err(anyhow::Error::from));
+        
+        if let Err(e) = result {
+            warn!("Failed to save IGDB access token to {}: {}", path.display(), e);
+        }
+    }
+    
+    /// Forget the current access token, both in memory and on disk
+    fn invalidate_token(&mut self) {
+        self.access_token = None;
+        self.token_expiry = None;
+        
+        if let Some(path) = &self.token_path {
+            if path.exists() {
+                if let Err(e) = std::fs::remove_file(path) {
+                    warn!("Failed to remove IGDB access token {}: {}", path.display(), e);
+                }
+            }
         }
     }
     
@@ -96,7 +524,7 @@ impl IgdbClient {
         match (self.access_token.as_ref(), self.token_expiry) {
             (Some(_), Some(expiry)) => {
                 // Refresh token if it will expire in less than 5 minutes
-                expiry <= Instant::now() + Duration::from_secs(300)
+                expiry <= SystemTime::now() + Duration::from_secs(300)
             }
             _ => true,
         }
@@ -104,6 +532,8 @@ impl IgdbClient {
     
     /// Authenticate with Twitch API to get access token
     pub async fn authenticate(&mut self) -> Result<()> {
+        self.ensure_online()?;
+        
         if !self.is_configured() {
             return Err(anyhow::anyhow!("IGDB credentials not configured"));
         }
@@ -147,7 +577,8 @@ impl IgdbClient {
         
         // Store token and expiry
         self.access_token = Some(auth.access_token);
-        self.token_expiry = Some(Instant::now() + Duration::from_secs(auth.expires_in));
+        self.token_expiry = Some(SystemTime::now() + Duration::from_secs(auth.expires_in));
+        self.save_token();
         
         info!("Successfully authenticated with Twitch API");
         Ok(())
@@ -198,30 +629,63 @@ impl IgdbClient {
         endpoint: &str,
         query: &str,
     ) -> Result<Vec<T>> {
-        // Ensure we're authenticated
+        // Ensure we're online and authenticated
+        self.ensure_online()?;
         self.ensure_authenticated().await?;
         
         // Build request URL
         let url = format!("{}/{}", self.base_url, endpoint);
         
         // Create headers
-        let headers = self.create_headers()?;
-        
-        // Send request
-        let response = self.client
-            .post(&url)
-            .headers(headers)
-            .body(query.to_string())
-            .send()
-            .await
-            .context(format!("Failed to send request to {}", endpoint))?;
+        let mut headers = self.create_headers()?;
         
-        // Check response status
-        if !response.status().is_success() {
+        let mut attempt = 0;
+        let mut reauthenticated = false;
+        let response = loop {
+            // Stay under the IGDB request limit across all clones of this client
+            self.rate_limiter.acquire().await;
+            
+            // Send request
+            let response = self.client
+                .post(&url)
+                .headers(headers.clone())
+                .body(query.to_string())
+                .send()
+                .await
+                .context(format!("Failed to send request to {}", endpoint))?;
+            
             let status = response.status();
+            if status.is_success() {
+                break response;
+            }
+            
+            // The token was revoked or expired early, so get a new one once
+            if status == reqwest::StatusCode::UNAUTHORIZED && !reauthenticated {
+                warn!("IGDB API rejected the access token, re-authenticating");
+                reauthenticated = true;
+                self.invalidate_token();
+                self.authenticate().await?;
+                headers = self.create_headers()?;
+                continue;
+            }
+            
+            // Retry throttled and server errors until the budget is spent
+            let retryable = status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+            if retryable && attempt < MAX_RETRIES {
+                let delay = retry_after(&response)
+                    .unwrap_or_else(|| RETRY_BASE_DELAY * 2u32.pow(attempt));
+                attempt += 1;
+                warn!(
+                    "IGDB API returned {} for {}, retrying in {:?} (attempt {}/{})",
+                    status, endpoint, delay, attempt, MAX_RETRIES
+                );
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+            
             let text = response.text().await.unwrap_or_default();
             return Err(anyhow::anyhow!("IGDB API error: {} - {}", status, text));
-        }
+        };
         
         // Parse response
         let results: Vec<T> = response
@@ -240,12 +704,18 @@ impl IgdbClient {
         // This query includes all fields we want to retrieve
         let query = format!(
             r#"search "{}";
-            fields id,name,summary,storyline,first_release_date,
+            fields id,name,alternative_names.name,summary,storyline,first_release_date,
             cover.image_id,
+            screenshots.image_id,
+            artworks.image_id,
             involved_companies.company.name,involved_companies.developer,involved_companies.publisher,
             genres.name,
             platforms.name,platforms.slug,
-            slug,url,total_rating,total_rating_count;
+            game_modes.name,game_engines.name,
+            franchise.name,franchises.name,collection.name,
+            similar_games.name,
+            slug,url,total_rating,total_rating_count,
+            age_ratings.category,age_ratings.rating;
             limit 10;"#,
             name
         );
@@ -265,12 +735,18 @@ impl IgdbClient {
         // Build IGDB query
         let query = format!(
             r#"where id = {};
-            fields id,name,summary,storyline,first_release_date,
+            fields id,name,alternative_names.name,summary,storyline,first_release_date,
             cover.image_id,
+            screenshots.image_id,
+            artworks.image_id,
             involved_companies.company.name,involved_companies.developer,involved_companies.publisher,
             genres.name,
             platforms.name,platforms.slug,
-            slug,url,total_rating,total_rating_count;
+            game_modes.name,game_engines.name,
+            franchise.name,franchises.name,collection.name,
+            similar_games.name,
+            slug,url,total_rating,total_rating_count,
+            age_ratings.category,age_ratings.rating;
             limit 1;"#,
             id
         );
@@ -286,12 +762,41 @@ impl IgdbClient {
         format!("https://images.igdb.com/igdb/image/upload/t_{}/{}.jpg", size, image_id)
     }
     
+    /// Get screenshot URL for a game
+    pub fn get_screenshot_url(&self, image_id: &str, size: &str) -> String {
+        format!("https://images.igdb.com/igdb/image/upload/t_{}/{}.jpg", size, image_id)
+    }
+    
     /// Download cover image
     pub async fn download_cover(&mut self, image_id: &str, size: &str, path: &std::path::Path) -> Result<()> {
         info!("Downloading cover image {} to {}", image_id, path.display());
         
-        // Get image URL
         let url = self.get_cover_url(image_id, size);
+        self.download_image(&url, "cover", path).await?;
+        
+        info!("Cover image successfully downloaded to {}", path.display());
+        Ok(())
+    }
+    
+    /// Download screenshot image
+    pub async fn download_screenshot(&mut self, image_id: &str, size: &str, path: &std::path::Path) -> Result<()> {
+        info!("Downloading screenshot {} to {}", image_id, path.display());
+        
+        let url = self.get_screenshot_url(image_id, size);
+        self.download_image(&url, "screenshot", path).await
+    }
+    
+    /// Download artwork image
+    pub async fn download_artwork(&mut self, image_id: &str, size: &str, path: &std::path::Path) -> Result<()> {
+        info!("Downloading artwork {} to {}", image_id, path.display());
+        
+        let url = self.get_cover_url(image_id, size);
+        self.download_image(&url, "artwork", path).await
+    }
+    
+    /// Download an image from the IGDB CDN to `path`
+    async fn download_image(&self, url: &str, kind: &str, path: &std::path::Path) -> Result<()> {
+        self.ensure_online()?;
         
         // Create parent directory if it doesn't exist
         if let Some(parent) = path.parent() {
@@ -303,27 +808,26 @@ impl IgdbClient {
         
         // Download image
         let response = self.client
-            .get(&url)
+            .get(url)
             .send()
             .await
-            .context("Failed to download cover image")?;
+            .with_context(|| format!("Failed to download {} image", kind))?;
         
         // Check response status
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to download cover image: {}", response.status()));
+            return Err(anyhow::anyhow!("Failed to download {} image: {}", kind, response.status()));
         }
         
         // Get image bytes
         let bytes = response
             .bytes()
             .await
-            .context("Failed to read cover image data")?;
+            .with_context(|| format!("Failed to read {} image data", kind))?;
         
         // Write image to file
-        std::fs::write(path, bytes)
+        crate::fs_util::write_atomic(path, bytes)
             .context("Failed to write image file")?;
-            
-        info!("Cover image successfully downloaded to {}", path.display());
+        
         Ok(())
     }
     
@@ -332,18 +836,6 @@ impl IgdbClient {
         // Search for games
         let games = self.search_game(name).await?;
         
-        if games.is_empty() {
-            return Ok(None);
-        }
-        
-        // Start with exact match
-        for game in &games {
-            if game.name.to_lowercase() == name.to_lowercase() {
-                return Ok(Some(game.clone()));
-            }
-        }
-        
-        // Otherwise, return the first result
-        Ok(Some(games[0].clone()))
+        Ok(pick_best_match(name, games, self.config.match_threshold))
     }
 }
\ No newline at end of file
@@ -12,10 +12,12 @@ pub async fn test_igdb_api(client_id: &str, client_secret: &str, cache_dir: Path
     let config = IgdbConfig {
         client_id: client_id.to_string(),
         client_secret: client_secret.to_string(),
+        match_threshold: 0.6,
+        ..Default::default()
     };
     
     // Create handler
-    let mut handler = MetadataHandler::new(config.clone(), cache_dir)?;
+    let mut handler = MetadataHandler::new(config.clone(), Default::default(), cache_dir)?;
     
     // Initialize (authenticates with IGDB)
     println!("Authenticating with IGDB...");
@@ -31,11 +33,8 @@ pub async fn test_igdb_api(client_id: &str, client_secret: &str, cache_dir: Path
     
     for (i, game) in results.iter().enumerate() {
         println!("Result {}: {} (ID: {})", i + 1, game.name, game.id);
-        println!("  Released: {}", game.first_release_date
-            .map(|ts| {
-                let dt = chrono::NaiveDateTime::from_timestamp(ts as i64, 0);
-                dt.format("%Y-%m-%d").to_string()
-            })
+        println!("  Released: {}", game.release_date()
+            .map(|date| date.format("%Y-%m-%d").to_string())
             .unwrap_or_else(|| "Unknown".to_string()));
         
         if let Some(summary) = &game.summary {
@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use log::{info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use super::igdb::{pick_best_match, IgdbCover, IgdbGame};
+use super::provider::MetadataProvider;
+
+/// Metadata provider backed by hand-written `metadata.json` files
+///
+/// Each game has its own folder in the provider directory containing a
+/// `metadata.json` in the same shape as an IGDB game and, optionally, a
+/// `cover.jpg` next to it.
+#[derive(Clone)]
+pub struct LocalMetadataProvider {
+    /// Directory holding one folder per game
+    dir: PathBuf,
+    /// Minimum similarity for a name match
+    match_threshold: f32,
+}
+
+impl LocalMetadataProvider {
+    /// Create a new local metadata provider
+    pub fn new(dir: PathBuf, match_threshold: f32) -> Self {
+        Self { dir, match_threshold }
+    }
+    
+    /// Load every `metadata.json` in the provider directory
+    fn load_entries(&self) -> Result<Vec<IgdbGame>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let entries = fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read local metadata directory: {}", self.dir.display()))?;
+        
+        let mut games = Vec::new();
+        
+        for entry in entries.flatten() {
+            let folder = entry.path();
+            let metadata_path = folder.join("metadata.json");
+            
+            if !metadata_path.is_file() {
+                continue;
+            }
+            
+            match Self::load_entry(&folder, &metadata_path) {
+                Ok(game) => games.push(game),
+                Err(e) => warn!("Failed to load local metadata {}: {}", metadata_path.display(), e),
+            }
+        }
+        
+        Ok(games)
+    }
+    
+    /// Load a single entry, resolving its cover to a path in the game folder
+    fn load_entry(folder: &Path, metadata_path: &Path) -> Result<IgdbGame> {
+        let json_str = fs::read_to_string(metadata_path)?;
+        let mut game: IgdbGame = serde_json::from_str(&json_str)?;
+        
+        // The cover "image ID" of a local entry is the path to the image file
+        let cover_file = game.cover
+            .as_ref()
+            .map(|cover| folder.join(&cover.image_id))
+            .unwrap_or_else(|| folder.join("cover.jpg"));
+        
+        game.cover = cover_file.is_file().then(|| IgdbCover {
+            id: 0,
+            url: None,
+            image_id: cover_file.to_string_lossy().into_owned(),
+        });
+        
+        Ok(game)
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for LocalMetadataProvider {
+    fn name(&self) -> &str {
+        "local"
+    }
+    
+    fn is_configured(&self) -> bool {
+        self.dir.exists()
+    }
+    
+    async fn search(&mut self, name: &str) -> Result<Vec<IgdbGame>> {
+        let games = self.load_entries()?;
+        info!("Found {} local metadata entries while searching for '{}'", games.len(), name);
+        Ok(games)
+    }
+    
+    async fn find_best_match(&mut self, name: &str) -> Result<Option<IgdbGame>> {
+        let games = self.search(name).await?;
+        Ok(pick_best_match(name, games, self.match_threshold))
+    }
+    
+    async fn get_by_id(&mut self, id: u32) -> Result<Option<IgdbGame>> {
+        Ok(self.load_entries()?.into_iter().find(|game| game.id == id))
+    }
+    
+    async fn download_cover(&mut self, image_id: &str, _size: &str, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .context("Failed to create parent directory")?;
+        }
+        
+        let bytes = fs::read(image_id)
+            .with_context(|| format!("Failed to read local cover {}", image_id))?;
+        
+        crate::fs_util::write_atomic(path, bytes)
+            .with_context(|| format!("Failed to copy local cover {}", image_id))
+    }
+    
+    fn clone_box(&self) -> Box<dyn MetadataProvider> {
+        Box::new(self.clone())
+    }
+}
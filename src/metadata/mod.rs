@@ -1,9 +1,14 @@
 pub mod igdb;
 pub mod cache;
 pub mod handler;
+pub mod provider;
+pub mod local;
+pub mod steamgriddb;
 pub mod igdb_test;
 
 pub use igdb::IgdbClient;
-pub use cache::MetadataCache;
+pub use cache::{MetadataCache, CacheStats, UserOverrides};
 pub use handler::MetadataHandler;
-pub use handler::MetadataStatus;
\ No newline at end of file
+pub use handler::{MetadataError, MetadataStatus};
+pub use provider::MetadataProvider;
+pub use local::LocalMetadataProvider;
\ No newline at end of file
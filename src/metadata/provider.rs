@@ -0,0 +1,96 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::Path;
+use super::igdb::{IgdbClient, IgdbGame};
+
+/// A source of game metadata
+///
+/// Every provider returns metadata in the `IgdbGame` shape so the cache and
+/// UI don't need to know where an entry came from.
+#[async_trait]
+pub trait MetadataProvider: Send + Sync {
+    /// Stable provider name, recorded with cached metadata
+    fn name(&self) -> &str;
+    
+    /// Check if the provider has everything it needs to run
+    fn is_configured(&self) -> bool;
+    
+    /// Prepare the provider for use (e.g. authenticate)
+    async fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+    
+    /// Search for games by name
+    async fn search(&mut self, name: &str) -> Result<Vec<IgdbGame>>;
+    
+    /// Find the best match for a game name
+    async fn find_best_match(&mut self, name: &str) -> Result<Option<IgdbGame>>;
+    
+    /// Get a game by its provider-specific ID
+    async fn get_by_id(&mut self, id: u32) -> Result<Option<IgdbGame>>;
+    
+    /// Download a cover image to `path`
+    async fn download_cover(&mut self, image_id: &str, size: &str, path: &Path) -> Result<()>;
+    
+    /// Download a screenshot to `path`
+    async fn download_screenshot(&mut self, _image_id: &str, _size: &str, _path: &Path) -> Result<()> {
+        Err(anyhow::anyhow!("{} does not provide screenshots", self.name()))
+    }
+    
+    /// Download an artwork image to `path`
+    async fn download_artwork(&mut self, _image_id: &str, _size: &str, _path: &Path) -> Result<()> {
+        Err(anyhow::anyhow!("{} does not provide artworks", self.name()))
+    }
+    
+    /// Clone the provider into a new box
+    fn clone_box(&self) -> Box<dyn MetadataProvider>;
+}
+
+impl Clone for Box<dyn MetadataProvider> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for IgdbClient {
+    fn name(&self) -> &str {
+        "igdb"
+    }
+    
+    fn is_configured(&self) -> bool {
+        IgdbClient::is_configured(self)
+    }
+    
+    async fn initialize(&mut self) -> Result<()> {
+        self.authenticate().await
+    }
+    
+    async fn search(&mut self, name: &str) -> Result<Vec<IgdbGame>> {
+        self.search_game(name).await
+    }
+    
+    async fn find_best_match(&mut self, name: &str) -> Result<Option<IgdbGame>> {
+        IgdbClient::find_best_match(self, name).await
+    }
+    
+    async fn get_by_id(&mut self, id: u32) -> Result<Option<IgdbGame>> {
+        self.get_game(id).await
+    }
+    
+    async fn download_cover(&mut self, image_id: &str, size: &str, path: &Path) -> Result<()> {
+        IgdbClient::download_cover(self, image_id, size, path).await
+    }
+    
+    async fn download_screenshot(&mut self, image_id: &str, size: &str, path: &Path) -> Result<()> {
+        IgdbClient::download_screenshot(self, image_id, size, path).await
+    }
+    
+    async fn download_artwork(&mut self, image_id: &str, size: &str, path: &Path) -> Result<()> {
+        IgdbClient::download_artwork(self, image_id, size, path).await
+    }
+    
+    fn clone_box(&self) -> Box<dyn MetadataProvider> {
+        Box::new(self.clone())
+    }
+}
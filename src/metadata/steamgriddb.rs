@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use log::info;
+use reqwest::Client;
+use serde::Deserialize;
+use std::path::Path;
+
+/// SteamGridDB API response wrapper
+#[derive(Debug, Deserialize)]
+struct SteamGridDbResponse<T> {
+    success: bool,
+    #[serde(default)]
+    data: Vec<T>,
+}
+
+/// SteamGridDB game search result
+#[derive(Debug, Deserialize)]
+struct SteamGridDbGame {
+    id: u32,
+    name: String,
+}
+
+/// SteamGridDB grid (cover) image
+#[derive(Debug, Deserialize)]
+struct SteamGridDbGrid {
+    url: String,
+}
+
+/// SteamGridDB API client, used as a cover art fallback
+#[derive(Clone)]
+pub struct SteamGridDbClient {
+    api_key: String,
+    client: Client,
+    base_url: String,
+}
+
+impl SteamGridDbClient {
+    /// Create a new SteamGridDB client sending its requests through `client`
+    pub fn new(api_key: String, client: Client) -> Self {
+        Self {
+            api_key,
+            client,
+            base_url: String::from("https://www.steamgriddb.com/api/v2"),
+        }
+    }
+    
+    /// Check if an API key is configured
+    pub fn is_configured(&self) -> bool {
+        !self.api_key.is_empty()
+    }
+    
+    /// Send a GET request and return the response data
+    async fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<Vec<T>> {
+        let url = format!("{}/{}", self.base_url, path);
+        
+        let response = self.client
+            .get(&url)
+            .bearer_auth(&self.api_key)
+            .send()
+            .await
+            .with_context(|| format!("Failed to send request to SteamGridDB {}", path))?;
+        
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("SteamGridDB API error: {} - {}", status, text));
+        }
+        
+        let body: SteamGridDbResponse<T> = response
+            .json()
+            .await
+            .context("Failed to parse SteamGridDB response")?;
+        
+        if !body.success {
+            return Err(anyhow::anyhow!("SteamGridDB request {} was not successful", path));
+        }
+        
+        Ok(body.data)
+    }
+    
+    /// Find a game by name and download its top-rated portrait grid to `path`
+    ///
+    /// Returns false when SteamGridDB has no matching game or grid.
+    pub async fn download_cover(&self, name: &str, path: &Path) -> Result<bool> {
+        let games: Vec<SteamGridDbGame> = self
+            .get(&format!("search/autocomplete/{}", urlencode(name)))
+            .await?;
+        
+        let Some(game) = games.into_iter().next() else {
+            info!("No SteamGridDB game found for '{}'", name);
+            return Ok(false);
+        };
+        
+        // Portrait grids match the shape of IGDB covers
+        let grids: Vec<SteamGridDbGrid> = self
+            .get(&format!("grids/game/{}?dimensions=600x900", game.id))
+            .await?;
+        
+        let Some(grid) = grids.into_iter().next() else {
+            info!("No SteamGridDB grid found for {} (ID: {})", game.name, game.id);
+            return Ok(false);
+        };
+        
+        info!("Downloading SteamGridDB cover for {} from {}", game.name, grid.url);
+        
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .context("Failed to create parent directory")?;
+        }
+        
+        let response = self.client
+            .get(&grid.url)
+            .send()
+            .await
+            .context("Failed to download SteamGridDB cover")?;
+        
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to download SteamGridDB cover: {}", response.status()));
+        }
+        
+        let bytes = response
+            .bytes()
+            .await
+            .context("Failed to read SteamGridDB cover data")?;
+        
+        crate::fs_util::write_atomic(path, bytes)
+            .context("Failed to write image file")?;
+        
+        Ok(true)
+    }
+}
+
+/// Percent-encode a search term for use in a URL path segment
+fn urlencode(term: &str) -> String {
+    url::form_urlencoded::byte_serialize(term.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
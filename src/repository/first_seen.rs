@@ -0,0 +1,96 @@
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::game_info::GameInfo;
+
+/// When each game first appeared in the repository
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct FirstSeen {
+    /// Seconds since the Unix epoch by game ID, 0 for games that predate tracking
+    pub games: HashMap<String, u64>,
+}
+
+impl FirstSeen {
+    /// Get the path of the first-seen file
+    pub fn path(cache_dir: &Path) -> PathBuf {
+        cache_dir.join("first_seen.json")
+    }
+    
+    /// Load the first-seen times, if they have been recorded before
+    pub fn load(cache_dir: &Path) -> Option<Self> {
+        let path = Self::path(cache_dir);
+        
+        if !path.exists() {
+            return None;
+        }
+        
+        let result = fs::read_to_string(&path)
+            .context("Failed to read first-seen times")
+            .and_then(|json_str| {
+                serde_json::from_str::<Self>(&json_str)
+                    .context("Failed to parse first-seen times")
+            });
+        
+        match result {
+            Ok(first_seen) => Some(first_seen),
+            Err(e) => {
+                warn!("{}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+    
+    /// Save the first-seen times to the cache directory
+    pub fn save(&self, cache_dir: &Path) -> Result<()> {
+        if !cache_dir.exists() {
+            fs::create_dir_all(cache_dir)?;
+        }
+        
+        let path = Self::path(cache_dir);
+        let json_str = serde_json::to_string(self)
+            .context("Failed to serialize first-seen times")?;
+        
+        crate::fs_util::write_atomic(&path, json_str)
+    }
+    
+    /// Record the games found by a repository scan and save the result
+    ///
+    /// New games are stamped with the current time and games that left the
+    /// repository are forgotten. On the very first scan every game is stamped
+    /// 0, so an existing library does not all show up as recently added.
+    pub fn record_scan(cache_dir: &Path, games: &[GameInfo]) -> Result<Self> {
+        let (mut first_seen, seen_at) = match Self::load(cache_dir) {
+            Some(first_seen) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                (first_seen, now)
+            }
+            None => (Self::default(), 0),
+        };
+        
+        let ids: HashSet<&str> = games.iter().map(|game| game.id.as_str()).collect();
+        first_seen.games.retain(|id, _| ids.contains(id.as_str()));
+        
+        let mut added = 0;
+        for id in ids {
+            if !first_seen.games.contains_key(id) {
+                first_seen.games.insert(id.to_string(), seen_at);
+                added += 1;
+            }
+        }
+        
+        if added > 0 && seen_at > 0 {
+            info!("{} new games in repository", added);
+        }
+        
+        first_seen.save(cache_dir)?;
+        Ok(first_seen)
+    }
+}
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use log::{debug, info, warn};
+use once_cell::sync::Lazy;
 use regex::Regex;
 
 /// Type of game file
@@ -9,8 +10,12 @@ use regex::Regex;
 pub enum FileType {
     /// Game installer
     Installer,
+    /// Game archive (zip/rar/7z) that is extracted rather than executed
+    Archive,
     /// Game patch
     Patch,
+    /// Downloadable content installer, run after the base game
+    Dlc,
     /// Other file
     Other,
 }
@@ -26,6 +31,68 @@ pub struct GameFile {
     pub size: u64,
     /// File type
     pub file_type: FileType,
+    /// Expected SHA-256 checksum (lowercase hex), if known
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+/// Pattern for the (from, to) builds in a patch file name, compiled once as patch chains call it in loops
+static PATCH_BUILDS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:patch|update).*?(?:build|v)_?(\d+[a-z]?)(?:_|\s|-).*?(?:to|-).*?(?:build|v)_?(\d+[a-z]?)").unwrap()
+});
+
+impl GameFile {
+    /// Parse the (from, to) build numbers out of a patch file name
+    ///
+    /// Matches names such as `patch_game_build_2055_(37083)_to_build_2172_(47150).exe`.
+    pub fn patch_builds(&self) -> Option<(u32, u32)> {
+        let patch_name = self.name.to_lowercase();
+        let captures = PATCH_BUILDS_REGEX.captures(&patch_name)?;
+        
+        let from_build = parse_build_number(captures.get(1)?.as_str())?;
+        let to_build = parse_build_number(captures.get(2)?.as_str())?;
+        
+        Some((from_build, to_build))
+    }
+}
+
+/// Parse the leading digits of a build string such as "2055a"
+fn parse_build_number(build: &str) -> Option<u32> {
+    let digits: String = build.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Parse a dotted version such as "1.12.0" into (major, minor, patch, extra) for ordering
+///
+/// Missing components count as 0 and anything after the fourth is ignored, so
+/// "2.0" sorts after "1.12.0", which sorts after "1.2.3.4".
+pub fn parse_version_key(version: &str) -> (u32, u32, u32, u32) {
+    let mut parts = version.split('.').map(|part| parse_build_number(part.trim()).unwrap_or(0));
+    let mut next = || parts.next().unwrap_or(0);
+    
+    (next(), next(), next(), next())
+}
+
+/// Follow the patch upgrade chain starting at `base_build`
+///
+/// Returns the patches in the order they must be applied and the build the chain ends at.
+/// Patches without parseable build numbers are ignored.
+pub fn follow_patch_chain<'a>(base_build: u32, patches: &[&'a GameFile]) -> (Vec<&'a GameFile>, u32) {
+    let mut remaining: Vec<&GameFile> = patches.to_vec();
+    let mut chain = Vec::new();
+    let mut current = base_build;
+    
+    while let Some(pos) = remaining.iter()
+        .position(|patch| patch.patch_builds().map_or(false, |(from, _)| from == current))
+    {
+        let patch = remaining.remove(pos);
+        if let Some((_, to)) = patch.patch_builds() {
+            current = to;
+        }
+        chain.push(patch);
+    }
+    
+    (chain, current)
 }
 
 /// Information about a game version
@@ -33,14 +100,67 @@ pub struct GameFile {
 pub struct GameVersion {
     /// Version name
     pub name: String,
-    /// Build number
+    /// Build number, used to follow patch chains
     pub build: u32,
+    /// Parsed (major, minor, patch, extra) version, used to order versions
+    #[serde(default)]
+    pub version_key: (u32, u32, u32, u32),
     /// Files for this version
     pub files: Vec<GameFile>,
     /// Required patches to install from base version
     pub required_patches: Vec<GameFile>,
 }
 
+impl GameVersion {
+    /// Total size in bytes of the files and required patches of this version
+    pub fn total_size(&self) -> u64 {
+        self.files.iter()
+            .chain(&self.required_patches)
+            .map(|file| file.size)
+            .sum()
+    }
+    
+    /// Describe gaps and overlaps in the patch chain of this version
+    ///
+    /// Looks at every numbered patch of `game`, not only the ones this version applies.
+    /// A gap is a patch past the end of this version's chain expecting a build that
+    /// no installer or patch provides. An overlap is several patches starting from a
+    /// build the chain passes through. These are warnings only and never stop an install.
+    pub fn patch_chain_issues(&self, game: &GameInfo) -> Vec<String> {
+        let patches: Vec<(&GameFile, u32, u32)> = game.files.iter()
+            .filter(|file| file.file_type == FileType::Patch)
+            .filter_map(|patch| patch.patch_builds().map(|(from, to)| (patch, from, to)))
+            .collect();
+        let patch_refs: Vec<&GameFile> = patches.iter().map(|(patch, _, _)| *patch).collect();
+        
+        let (chain, end_build) = follow_patch_chain(self.build, &patch_refs);
+        
+        let mut issues = Vec::new();
+        
+        let passed_builds = std::iter::once(self.build)
+            .chain(chain.iter().filter_map(|patch| patch.patch_builds().map(|(_, to)| to)));
+        for build in passed_builds {
+            let names: Vec<&str> = patches.iter()
+                .filter(|(_, from, _)| *from == build)
+                .map(|(patch, _, _)| patch.name.as_str())
+                .collect();
+            if names.len() > 1 {
+                issues.push(format!("{} patches start from build {}: {}", names.len(), build, names.join(", ")));
+            }
+        }
+        
+        for (patch, from, _) in patches.iter().filter(|(_, from, _)| *from > end_build) {
+            let provided = patches.iter().any(|(_, _, to)| to == from)
+                || game.versions.iter().any(|version| version.build == *from);
+            if !provided {
+                issues.push(format!("{} expects build {}, which no installer or patch provides", patch.name, from));
+            }
+        }
+        
+        issues
+    }
+}
+
 /// Information about a game
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct GameInfo {
@@ -62,11 +182,61 @@ pub struct GameInfo {
     pub files: Vec<GameFile>,
     /// Available versions
     pub versions: Vec<GameVersion>,
-    /// Cover image path
+    /// DLC installers, which are not part of any version
+    #[serde(default)]
+    pub dlc: Vec<GameFile>,
+    /// Repository path of a cover image shipped in the game directory
     pub cover_image: Option<PathBuf>,
+    /// Command-line arguments passed to the installer and patches
+    #[serde(default)]
+    pub install_args: Option<Vec<String>>,
+    /// GOG product ID, from a `goggame-<id>.info` file
+    #[serde(default)]
+    pub gog_id: Option<u64>,
+    /// Game version reported by GOG, from a `goggame-<id>.info` file
+    #[serde(default)]
+    pub gog_version: Option<String>,
+    /// Name of the repository profile the game was listed from
+    #[serde(default)]
+    pub source: String,
+}
+
+/// Structured metadata from an `info.yaml` or `info.json` file in the game directory
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct MetadataSidecar {
+    title: Option<String>,
+    developer: Option<String>,
+    publisher: Option<String>,
+    release_date: Option<String>,
+    description: Option<String>,
+    igdb_id: Option<u32>,
+    install_args: Option<Vec<String>>,
+}
+
+/// Contents of the `goggame-<id>.info` JSON file that GOG installers ship
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GogGameInfo {
+    game_id: Option<String>,
+    name: Option<String>,
+    /// Usually the file format version (a number), but some builds put the game version here
+    version: Option<serde_json::Value>,
+    version_name: Option<String>,
 }
 
 impl GameInfo {
+    /// Total size in bytes of the files of every version, counting shared files once
+    pub fn versions_size(&self) -> u64 {
+        let mut seen = HashSet::new();
+        
+        self.versions.iter()
+            .flat_map(|version| version.files.iter().chain(&version.required_patches))
+            .filter(|file| seen.insert(file.remote_path.as_str()))
+            .map(|file| file.size)
+            .sum()
+    }
+    
     /// Parse metadata from !info.txt
     pub fn parse_metadata(&mut self, content: &str) {
         // Track keys we've seen to handle multi-line values
@@ -119,6 +289,67 @@ impl GameInfo {
         }
     }
     
+    /// Parse metadata from an `info.yaml` file
+    pub fn parse_metadata_yaml(&mut self, content: &str) -> Result<(), serde_yaml::Error> {
+        let sidecar: MetadataSidecar = serde_yaml::from_str(content)?;
+        self.apply_sidecar(sidecar);
+        Ok(())
+    }
+    
+    /// Parse metadata from an `info.json` file
+    pub fn parse_metadata_json(&mut self, content: &str) -> Result<(), serde_json::Error> {
+        let sidecar: MetadataSidecar = serde_json::from_str(content)?;
+        self.apply_sidecar(sidecar);
+        Ok(())
+    }
+    
+    /// Copy the fields set in a structured metadata file
+    fn apply_sidecar(&mut self, sidecar: MetadataSidecar) {
+        if let Some(title) = sidecar.title.filter(|title| !title.trim().is_empty()) {
+            self.title = title;
+        }
+        if sidecar.developer.is_some() {
+            self.developer = sidecar.developer;
+        }
+        if sidecar.publisher.is_some() {
+            self.publisher = sidecar.publisher;
+        }
+        if sidecar.release_date.is_some() {
+            self.release_date = sidecar.release_date;
+        }
+        if sidecar.description.is_some() {
+            self.description = sidecar.description;
+        }
+        if sidecar.igdb_id.is_some() {
+            self.igdb_id = sidecar.igdb_id;
+        }
+        if sidecar.install_args.is_some() {
+            self.install_args = sidecar.install_args;
+        }
+    }
+    
+    /// Parse metadata from a GOG `goggame-<id>.info` file
+    ///
+    /// Sets the title, GOG ID and GOG version when present. Call this before
+    /// `parse_metadata` so a hand-written `info.txt` still wins.
+    pub fn parse_gog_info(&mut self, content: &str) -> Result<(), serde_json::Error> {
+        let gog_info: GogGameInfo = serde_json::from_str(content)?;
+        
+        if let Some(name) = gog_info.name.filter(|name| !name.trim().is_empty()) {
+            self.title = name.trim().to_string();
+        }
+        
+        self.gog_id = gog_info.game_id.and_then(|id| id.trim().parse().ok());
+        
+        let version = match gog_info.version {
+            Some(serde_json::Value::String(version)) => Some(version),
+            _ => gog_info.version_name,
+        };
+        self.gog_version = version.filter(|version| !version.trim().is_empty());
+        
+        Ok(())
+    }
+    
     /// Set a metadata value based on key
     fn set_metadata_value(&mut self, key: &str, value: &str) {
         match key {
@@ -128,6 +359,9 @@ impl GameInfo {
             "release" | "release date" | "date" => self.release_date = Some(value.to_string()),
             "description" | "desc" | "about" => self.description = Some(value.to_string()),
             "igdb" | "igdb_id" | "igdb id" => self.igdb_id = value.parse().ok(),
+            "silent_args" | "silent args" | "install_args" | "install args" => {
+                self.install_args = Some(value.split_whitespace().map(String::from).collect());
+            }
             _ => {
                 // Unknown key, ignore
                 debug!("Unknown metadata key: {}", key);
@@ -135,11 +369,35 @@ impl GameInfo {
         }
     }
     
+    /// Remove files listed more than once, such as an installer reachable through a symlink or junction
+    ///
+    /// Files with the same name and size are the same file unless their checksums
+    /// differ. The first path is kept. Returns the number of files removed.
+    pub fn dedup_files(&mut self) -> usize {
+        let before = self.files.len();
+        let mut seen: HashMap<(String, u64), Option<String>> = HashMap::new();
+        
+        self.files.retain(|file| {
+            match seen.get(&(file.name.clone(), file.size)) {
+                Some(sha256) => match (sha256, &file.sha256) {
+                    (Some(kept), Some(other)) => !kept.eq_ignore_ascii_case(other),
+                    _ => false,
+                },
+                None => {
+                    seen.insert((file.name.clone(), file.size), file.sha256.clone());
+                    true
+                }
+            }
+        });
+        
+        before - self.files.len()
+    }
+    
     /// Parse available versions from files
     pub fn parse_versions(&mut self) {
-        // Extract installer files
+        // Extract installer files (archives are installed the same way as installers)
         let installer_files: Vec<&GameFile> = self.files.iter()
-            .filter(|f| f.file_type == FileType::Installer)
+            .filter(|f| matches!(f.file_type, FileType::Installer | FileType::Archive))
             .collect();
             
         // Extract patch files
@@ -155,9 +413,9 @@ impl GameInfo {
             // Common GOG pattern: build_1234
             Regex::new(r"build_(\d+[a-z]?)_?\(?(\d+)?\)?").unwrap(),
             // Common version pattern: v1.2.3
-            Regex::new(r"v(\d+\.\d+(\.\d+)?)").unwrap(),
-            // Numeric pattern: 1.0, 2.1, etc.
-            Regex::new(r"(\d+\.\d+(\.\d+)?)").unwrap(),
+            Regex::new(r"v(\d+(?:\.\d+)+)").unwrap(),
+            // Numeric pattern: 1.0, 2.1, 1.2.3.4, etc.
+            Regex::new(r"(\d+(?:\.\d+)+)").unwrap(),
         ];
         
         // Map to track which files belong to which version
@@ -172,27 +430,6 @@ impl GameInfo {
                 if let Some(captures) = pattern.captures(&file_name) {
                     let version_str = captures.get(1).map_or("Unknown", |m| m.as_str());
                     
-                    // Try to extract a build number
-                    let build = if version_str.contains('.') {
-                        // For version like 1.2.3, convert to a number like 10203
-                        let parts: Vec<&str> = version_str.split('.').collect();
-                        let mut build_num = 0;
-                        
-                        for (i, part) in parts.iter().enumerate() {
-                            if let Ok(num) = part.parse::<u32>() {
-                                build_num += num * 10u32.pow(6 - (i as u32 * 2));
-                            }
-                        }
-                        
-                        build_num
-                    } else {
-                        // Try to parse as a simple number
-                        captures.get(2)
-                            .and_then(|m| m.as_str().parse::<u32>().ok())
-                            .or_else(|| version_str.parse::<u32>().ok())
-                            .unwrap_or(0)
-                    };
-                    
                     // Create a version name
                     let version_name = if version_str.contains('.') {
                         format!("Version {}", version_str)
@@ -232,9 +469,16 @@ impl GameInfo {
                 }
             };
             
+            // "Version 1.2.3" orders by its dotted number, "Build 2241" by the build
+            let version_key = match name.strip_prefix("Version ") {
+                Some(version_str) => parse_version_key(version_str),
+                None => (build, 0, 0, 0),
+            };
+            
             let version = GameVersion {
                 name,
                 build,
+                version_key,
                 files,
                 required_patches: Vec::new(),
             };
@@ -244,31 +488,19 @@ impl GameInfo {
         
         // Second pass: assign patches to versions
         if !patch_files.is_empty() && !versions.is_empty() {
-            for patch in &patch_files {
-                let patch_name = patch.name.to_lowercase();
-                
-                // Try to match patch with version based on build numbers
-                let from_to_regex = Regex::new(r"(?:patch|update).*?(?:build|v)_?(\d+[a-z]?)(?:_|\s|-).*?(?:to|-).*?(?:build|v)_?(\d+[a-z]?)").unwrap();
+            // Patches with build numbers are applied along the upgrade chain of each version
+            let (versioned_patches, unversioned_patches): (Vec<&GameFile>, Vec<&GameFile>) = patch_files
+                .iter()
+                .copied()
+                .partition(|patch| patch.patch_builds().is_some());
+            
+            for version in &mut versions {
+                // Patches the chain cannot reach are left out, `patch_chain_issues` warns about them
+                let (chain, _) = follow_patch_chain(version.build, &versioned_patches);
+                version.required_patches.extend(chain.into_iter().cloned());
                 
-                if let Some(captures) = from_to_regex.captures(&patch_name) {
-                    let from_str = captures.get(1).map_or("", |m| m.as_str());
-                    let to_str = captures.get(2).map_or("", |m| m.as_str());
-                    
-                    let from_build = from_str.parse::<u32>().unwrap_or(0);
-                    let _to_build = to_str.parse::<u32>().unwrap_or(0);
-                    
-                    // Find the matching version
-                    for version in &mut versions {
-                        if version.build == from_build {
-                            version.required_patches.push((**patch).clone());
-                        }
-                    }
-                } else {
-                    // If we can't match the patch to a specific version, add it to all versions
-                    for version in &mut versions {
-                        version.required_patches.push((**patch).clone());
-                    }
-                }
+                // If we can't match the patch to a specific version, add it to all versions
+                version.required_patches.extend(unversioned_patches.iter().map(|&patch| patch.clone()));
             }
         }
         
@@ -279,6 +511,7 @@ impl GameInfo {
             let version = GameVersion {
                 name: "Default Version".to_string(),
                 build: 1,
+                version_key: (1, 0, 0, 0),
                 files: default_files,
                 required_patches: Vec::new(),
             };
@@ -286,8 +519,8 @@ impl GameInfo {
             versions.push(version);
         }
         
-        // Sort versions by build number (descending)
-        versions.sort_by(|a, b| b.build.cmp(&a.build));
+        // Sort versions newest first
+        versions.sort_by(|a, b| b.version_key.cmp(&a.version_key).then(b.build.cmp(&a.build)));
         
         self.versions = versions;
     }
@@ -301,4 +534,177 @@ impl GameInfo {
     pub fn get_version_by_build(&self, build: u32) -> Option<&GameVersion> {
         self.versions.iter().find(|v| v.build == build)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn installer(name: &str) -> GameFile {
+        GameFile {
+            name: name.to_string(),
+            remote_path: format!("game/{}", name),
+            size: 0,
+            file_type: FileType::Installer,
+            sha256: None,
+        }
+    }
+    
+    fn patch(name: &str) -> GameFile {
+        GameFile {
+            file_type: FileType::Patch,
+            ..installer(name)
+        }
+    }
+    
+    /// Parse the versions of a game with the given installers and patches
+    fn game_with(installers: &[&str], patches: &[&str]) -> GameInfo {
+        let mut game = GameInfo {
+            files: installers.iter().map(|name| installer(name))
+                .chain(patches.iter().map(|name| patch(name)))
+                .collect(),
+            ..Default::default()
+        };
+        game.parse_versions();
+        game
+    }
+    
+    #[test]
+    fn parses_version_keys() {
+        assert_eq!(parse_version_key("1.12.0"), (1, 12, 0, 0));
+        assert_eq!(parse_version_key("2.0"), (2, 0, 0, 0));
+        assert_eq!(parse_version_key("1.2.3.4"), (1, 2, 3, 4));
+    }
+    
+    #[test]
+    fn orders_versions_by_components() {
+        assert!(parse_version_key("2.0") > parse_version_key("1.12.0"));
+        assert!(parse_version_key("1.12.0") > parse_version_key("1.2.3.4"));
+        assert!(parse_version_key("1.2.3.4") > parse_version_key("1.2.3"));
+    }
+    
+    #[test]
+    fn sorts_parsed_versions_newest_first() {
+        let mut game = GameInfo {
+            files: vec![
+                installer("setup_game_1.2.3.4.exe"),
+                installer("setup_game_2.0.exe"),
+                installer("setup_game_1.12.0.exe"),
+            ],
+            ..Default::default()
+        };
+        
+        game.parse_versions();
+        
+        let names: Vec<&str> = game.versions.iter().map(|version| version.name.as_str()).collect();
+        assert_eq!(names, ["Version 2.0", "Version 1.12.0", "Version 1.2.3.4"]);
+    }
+    
+    #[test]
+    fn drops_duplicate_files_before_parsing_versions() {
+        let linked = |name: &str, dir: &str| GameFile {
+            remote_path: format!("game/{}/{}", dir, name),
+            size: 1024,
+            ..installer(name)
+        };
+        
+        let mut game = GameInfo {
+            files: vec![
+                linked("setup_game_1.0.exe", "current"),
+                linked("setup_game_1.0.exe", "mirror"),
+                linked("setup_game_2.0.exe", "current"),
+                GameFile { size: 2048, ..linked("setup_game_2.0.exe", "mirror") },
+            ],
+            ..Default::default()
+        };
+        
+        assert_eq!(game.dedup_files(), 1);
+        game.parse_versions();
+        
+        let remote_paths: Vec<&str> = game.files.iter().map(|file| file.remote_path.as_str()).collect();
+        assert_eq!(remote_paths, [
+            "game/current/setup_game_1.0.exe",
+            "game/current/setup_game_2.0.exe",
+            "game/mirror/setup_game_2.0.exe",
+        ]);
+        
+        let versions: Vec<(&str, usize)> = game.versions.iter()
+            .map(|version| (version.name.as_str(), version.files.len()))
+            .collect();
+        assert_eq!(versions, [("Version 2.0", 2), ("Version 1.0", 1)]);
+    }
+    
+    #[test]
+    fn follows_patch_chain_until_a_gap() {
+        let patches = [
+            patch("patch_game_build_1500_to_build_1600.exe"),
+            patch("patch_game_build_1000_to_build_1500.exe"),
+            patch("patch_game_build_1700_to_build_1800.exe"),
+        ];
+        let refs: Vec<&GameFile> = patches.iter().collect();
+        
+        let (chain, end_build) = follow_patch_chain(1000, &refs);
+        
+        let names: Vec<&str> = chain.iter().map(|patch| patch.name.as_str()).collect();
+        assert_eq!(names, ["patch_game_build_1000_to_build_1500.exe", "patch_game_build_1500_to_build_1600.exe"]);
+        assert_eq!(end_build, 1600);
+        
+        let (chain, end_build) = follow_patch_chain(1, &refs);
+        assert!(chain.is_empty());
+        assert_eq!(end_build, 1);
+    }
+    
+    #[test]
+    fn leaves_unreachable_patches_out_of_a_version() {
+        let game = game_with(
+            &["setup_game_1.0.exe", "setup_game_build_1000.exe"],
+            &["patch_game_build_1000_to_build_1500.exe", "patch_game_build_2000_to_build_2100.exe"],
+        );
+        
+        let patches_of = |name: &str| -> Vec<String> {
+            game.versions.iter()
+                .find(|version| version.name == name)
+                .unwrap()
+                .required_patches.iter()
+                .map(|patch| patch.name.clone())
+                .collect()
+        };
+        assert_eq!(patches_of("Version 1.0"), Vec::<String>::new());
+        assert_eq!(patches_of("Build 1000"), ["patch_game_build_1000_to_build_1500.exe"]);
+    }
+    
+    #[test]
+    fn reports_gaps_after_the_chain() {
+        let game = game_with(
+            &["setup_game_build_1000.exe"],
+            &["patch_game_build_1000_to_build_1500.exe", "patch_game_build_1600_to_build_1700.exe"],
+        );
+        
+        assert_eq!(game.versions[0].patch_chain_issues(&game), [
+            "patch_game_build_1600_to_build_1700.exe expects build 1600, which no installer or patch provides",
+        ]);
+    }
+    
+    #[test]
+    fn accepts_separate_release_lines() {
+        let game = game_with(
+            &["setup_game_build_1000.exe", "setup_game_build_2000.exe"],
+            &["patch_game_build_1000_to_build_1500.exe", "patch_game_build_2000_to_build_2100.exe"],
+        );
+        
+        for version in &game.versions {
+            assert!(version.patch_chain_issues(&game).is_empty(), "{}", version.name);
+        }
+    }
+    
+    #[test]
+    fn reports_overlapping_patches() {
+        let game = game_with(
+            &["setup_game_build_1000.exe"],
+            &["patch_game_build_1000_to_build_1500.exe", "patch_game_build_1000_to_build_1600.exe"],
+        );
+        
+        assert_eq!(game.versions[0].patch_chain_issues(&game), [
+            "2 patches start from build 1000: patch_game_build_1000_to_build_1500.exe, patch_game_build_1000_to_build_1600.exe",
+        ]);
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::game_info::GameInfo;
+
+/// Cached repository listing for fast startup
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListingCache {
+    /// Time the listing was scanned (seconds since the Unix epoch)
+    pub scanned_at: u64,
+    /// Games found in the repository
+    pub games: Vec<GameInfo>,
+}
+
+impl ListingCache {
+    /// Create a new listing from freshly scanned games
+    pub fn new(games: Vec<GameInfo>) -> Self {
+        Self {
+            scanned_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            games,
+        }
+    }
+    
+    /// Get the path of the listing cache file
+    pub fn path(cache_dir: &Path) -> PathBuf {
+        cache_dir.join("repository.json")
+    }
+    
+    /// Load the cached listing, if one exists
+    pub fn load(cache_dir: &Path) -> Option<Self> {
+        let path = Self::path(cache_dir);
+        
+        if !path.exists() {
+            return None;
+        }
+        
+        let result = fs::read_to_string(&path)
+            .context("Failed to read repository listing cache")
+            .and_then(|json_str| {
+                serde_json::from_str::<Self>(&json_str)
+                    .context("Failed to parse repository listing cache")
+            });
+        
+        match result {
+            Ok(cache) => {
+                info!("Loaded {} cached games from {}", cache.games.len(), path.display());
+                Some(cache)
+            }
+            Err(e) => {
+                warn!("{}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+    
+    /// Save the listing to the cache directory
+    pub fn save(&self, cache_dir: &Path) -> Result<()> {
+        if !cache_dir.exists() {
+            fs::create_dir_all(cache_dir)?;
+        }
+        
+        let path = Self::path(cache_dir);
+        let json_str = serde_json::to_string(self)
+            .context("Failed to serialize repository listing")?;
+        
+        fs::write(&path, json_str)
+            .with_context(|| format!("Failed to write repository listing cache: {}", path.display()))?;
+        
+        info!("Saved repository listing to {}", path.display());
+        Ok(())
+    }
+    
+    /// Check if the listing is older than the given number of hours
+    pub fn is_stale(&self, hours: u64) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        
+        now.saturating_sub(self.scanned_at) > hours * 3600
+    }
+}
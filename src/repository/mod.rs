@@ -1,5 +1,9 @@
 pub mod smb;
 pub mod game_info;
+pub mod listing_cache;
+pub mod first_seen;
 
 pub use smb::SmbConnection;
-pub use game_info::{GameInfo, GameVersion, GameFile, FileType};
\ No newline at end of file
+pub use game_info::{GameInfo, GameVersion, GameFile, FileType, follow_patch_chain};
+pub use listing_cache::ListingCache;
+pub use first_seen::FirstSeen;
\ No newline at end of file
@@ -2,9 +2,15 @@ use crate::config::RepositoryConfig;
 use crate::repository::game_info::{GameInfo, GameFile, FileType};
 use anyhow::{Context, Result};
 use log::{info, warn, error};
+use pavao::{SmbClient, SmbCredentials, SmbDirentType, SmbOpenOptions, SmbOptions};
+use std::io::{Read, Seek, SeekFrom};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
 use regex::Regex;
+use tokio_util::sync::CancellationToken;
 use walkdir::WalkDir;
 
 /// SMB Connection to game repository
@@ -15,20 +21,83 @@ pub struct SmbConnection {
     using_local_fallback: bool,
     /// Local path for fallback mode
     local_path: Option<PathBuf>,
+    /// Open SMB session, shared with the blocking threads that use it
+    client: Option<Arc<SmbClient>>,
+    /// Pattern for installer file names
+    installer_regex: Regex,
+    /// Pattern for patch file names
+    patch_regex: Regex,
 }
 
+/// Default pattern for installer file names
+const DEFAULT_INSTALLER_PATTERN: &str = r"(?i)(setup|install|launcher).*\.(exe|msi|pkg|dmg)$";
+
+/// Default pattern for patch file names
+const DEFAULT_PATCH_PATTERN: &str = r"(?i)(patch|update).*\.(exe|msi|pkg|dmg|zip|rar|7z)$";
+
 impl SmbConnection {
     /// Create a new SMB connection from configuration
     pub fn new(config: RepositoryConfig) -> Self {
+        let installer_regex = Self::compile_pattern(&config.installer_pattern, DEFAULT_INSTALLER_PATTERN, "installer");
+        let patch_regex = Self::compile_pattern(&config.patch_pattern, DEFAULT_PATCH_PATTERN, "patch");
+        
         Self {
             config,
             using_local_fallback: false,
             local_path: None,
+            client: None,
+            installer_regex,
+            patch_regex,
+        }
+    }
+    
+    /// Compile a user-supplied file pattern, falling back to the default if it is empty or invalid
+    fn compile_pattern(pattern: &str, default: &str, kind: &str) -> Regex {
+        if !pattern.is_empty() {
+            match Regex::new(pattern) {
+                Ok(regex) => return regex,
+                Err(e) => warn!("Invalid {} pattern '{}', using default: {}", kind, pattern, e),
+            }
         }
+        
+        Regex::new(default).expect("default file pattern is valid")
     }
     
-    /// Connect to the SMB repository
+    /// Connect to the SMB repository, retrying up to 3 times
     pub async fn connect(&mut self) -> Result<()> {
+        self.connect_with_retry(3, Duration::from_secs(1)).await
+    }
+    
+    /// Connect to the SMB repository, retrying with exponential backoff
+    pub async fn connect_with_retry(&mut self, attempts: u32, base_delay: Duration) -> Result<()> {
+        let attempts = attempts.max(1);
+        let mut attempt = 1;
+        
+        loop {
+            let timeout = Duration::from_secs(self.config.timeout_secs);
+            let result = match tokio::time::timeout(timeout, self.try_connect()).await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow::anyhow!("Connection timed out after {} seconds", self.config.timeout_secs)),
+            };
+            
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < attempts => {
+                    let delay = base_delay * 2u32.saturating_pow(attempt - 1);
+                    warn!("Connection attempt {}/{} failed: {}. Retrying in {:?}", attempt, attempts, e, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    error!("Connection failed after {} attempts: {}", attempts, e);
+                    return Err(e);
+                }
+            }
+        }
+    }
+    
+    /// Make a single connection attempt
+    async fn try_connect(&mut self) -> Result<()> {
         let server = &self.config.server;
         let share = &self.config.share;
         
@@ -55,19 +124,59 @@ impl SmbConnection {
             }
         }
         
+        if server.is_empty() {
+            return Err(anyhow::anyhow!("SMB server is not configured"));
+        }
+        
         // Try SMB connection for non-local paths
         info!("Attempting to connect to SMB repository: {}\\{}", server, share);
         
-        // In a real implementation, this would use actual SMB connection code
-        // For now, we'll simulate a successful connection for demo purposes
-        info!("Successfully connected to SMB repository (simulated)");
+        let mut credentials = SmbCredentials::default()
+            .server(format!("smb://{}", server.trim_start_matches("smb://")))
+            .share(format!("/{}", share.trim_matches('/')))
+            .username(&self.config.username)
+            .password(&self.config.password);
+        
+        if !self.config.domain.is_empty() {
+            credentials = credentials.workgroup(&self.config.domain);
+        }
+        
+        let server = server.clone();
+        let share = share.clone();
+        let base_path = self.get_smb_path("");
+        
+        // libsmbclient blocks, so run it off the async executor to let the timeout fire
+        let client = tokio::task::spawn_blocking(move || -> Result<SmbClient> {
+            let client = SmbClient::new(credentials, SmbOptions::default().one_share_per_server(true))
+                .with_context(|| format!("Failed to connect to SMB share \\\\{}\\{}", server, share))?;
+            
+            // Listing the base directory verifies both the session and the configured path
+            client.list_dir(&base_path)
+                .with_context(|| format!("Failed to open {} on SMB share {}", base_path, share))?;
+            
+            Ok(client)
+        }).await??;
+        
+        self.client = Some(Arc::new(client));
+        info!("Successfully connected to SMB repository");
         
         Ok(())
     }
     
+    /// Test the repository configuration without touching this connection
+    ///
+    /// Returns the number of game directories found.
+    pub async fn test(&self) -> Result<usize> {
+        let mut connection = SmbConnection::new(self.config.clone());
+        connection.connect_with_retry(1, Duration::ZERO).await?;
+        
+        let directories = connection.list_directories().await?;
+        Ok(directories.len())
+    }
+    
     /// Check if connected to the SMB repository
     pub fn is_connected(&self) -> bool {
-        self.using_local_fallback || !self.config.server.is_empty()
+        self.using_local_fallback || self.client.is_some()
     }
     
     /// Get the full path for a relative path in the repository
@@ -79,6 +188,18 @@ impl SmbConnection {
         }
     }
     
+    /// Get the absolute path within the SMB share for a relative repository path
+    fn get_smb_path(&self, path: &str) -> String {
+        let full_path = self.get_full_path(path);
+        format!("/{}", full_path.trim_matches('/'))
+    }
+    
+    /// Get the open SMB session
+    fn smb_client(&self) -> Result<&Arc<SmbClient>> {
+        self.client.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Not connected to SMB repository"))
+    }
+    
     /// List directories in the repository
     pub async fn list_directories(&self) -> Result<Vec<String>> {
         if self.using_local_fallback {
@@ -118,10 +239,26 @@ impl SmbConnection {
                 Ok(self.get_demo_directories())
             }
         } else {
-            // In a real implementation, this would use SMB APIs
-            // For now, return demo directories
-            info!("Using demo directories (SMB implementation not complete)");
-            Ok(self.get_demo_directories())
+            let client = self.smb_client()?.clone();
+            let base_path = self.get_smb_path("");
+            
+            info!("Listing directories in SMB repository: {}", base_path);
+            
+            // libsmbclient blocks, so list on a blocking thread
+            let entries = tokio::task::spawn_blocking(move || {
+                client.list_dir(&base_path)
+                    .with_context(|| format!("Failed to list SMB directory {}", base_path))
+            }).await??;
+            
+            let dirs: Vec<String> = entries.iter()
+                .filter(|entry| entry.get_type() == SmbDirentType::Dir)
+                .map(|entry| entry.name().to_string())
+                // Skip directories starting with . or _
+                .filter(|name| !name.starts_with('.') && !name.starts_with('_'))
+                .collect();
+            
+            info!("Found {} game directories", dirs.len());
+            Ok(dirs)
         }
     }
     
@@ -135,70 +272,166 @@ impl SmbConnection {
         ]
     }
     
-    /// Download a file from the repository
-    pub async fn download_file(&self, remote_path: &str, local_path: &Path) -> Result<()> {
+    /// Open a repository file for reading, starting at `offset` bytes
+    pub fn open_file(&self, remote_path: &str, offset: u64) -> Result<Box<dyn Read + Send + '_>> {
         if self.using_local_fallback {
-            // Construct source path
-            let source_path = if let Some(base_path) = &self.local_path {
-                base_path.join(remote_path.replace('/', "\\"))
-            } else {
-                return Err(anyhow::anyhow!("Local path not set"));
+            let source_path = match &self.local_path {
+                Some(base_path) => base_path.join(remote_path),
+                None => return Err(anyhow::anyhow!("Local path not set")),
             };
             
-            info!("Copying file: {} -> {}", source_path.display(), local_path.display());
+            let mut file = fs::File::open(&source_path)
+                .with_context(|| format!("Failed to open file: {}", source_path.display()))?;
             
-            // Create parent directory if it doesn't exist
-            if let Some(parent) = local_path.parent() {
-                if !parent.exists() {
-                    fs::create_dir_all(parent)
-                        .context("Failed to create parent directory")?;
-                }
+            if offset > 0 {
+                file.seek(SeekFrom::Start(offset))
+                    .with_context(|| format!("Failed to seek in file: {}", source_path.display()))?;
             }
             
-            // Copy the file
-            match fs::copy(&source_path, local_path) {
-                Ok(_) => {
-                    info!("File copied successfully");
-                    Ok(())
-                },
-                Err(e) => {
-                    // If file doesn't exist, create a dummy file for demonstration
-                    warn!("Failed to copy file: {}. Creating dummy file instead.", e);
-                    fs::write(local_path, b"Simulated file content")
-                        .context(format!("Failed to create local file: {}", local_path.display()))?;
-                    Ok(())
-                }
-            }
+            Ok(Box::new(file))
         } else {
-            // Simulate SMB download
-            info!("Simulating download from SMB: {} -> {}", remote_path, local_path.display());
-            
-            // Create parent directory if it doesn't exist
-            if let Some(parent) = local_path.parent() {
-                if !parent.exists() {
-                    fs::create_dir_all(parent)
-                        .context("Failed to create parent directory")?;
-                }
+            let client = self.smb_client()?;
+            let source_path = self.get_smb_path(remote_path);
+            
+            let mut file = client.open_with(&source_path, SmbOpenOptions::default().read(true))
+                .with_context(|| format!("Failed to open remote file: {}", source_path))?;
+            
+            if offset > 0 {
+                file.seek(SeekFrom::Start(offset))
+                    .with_context(|| format!("Failed to seek in remote file: {}", source_path))?;
             }
             
-            // Create a dummy file
-            fs::write(local_path, b"Simulated file content")
-                .context(format!("Failed to create local file: {}", local_path.display()))?;
-                
-            info!("Downloaded file: {} -> {}", remote_path, local_path.display());
+            Ok(Box::new(file))
+        }
+    }
+    
+    /// Download a file from the repository
+    pub async fn download_file(&self, remote_path: &str, local_path: &Path) -> Result<()> {
+        info!("Downloading file: {} -> {}", remote_path, local_path.display());
+        
+        // Create parent directory if it doesn't exist
+        if let Some(parent) = local_path.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent)
+                    .context("Failed to create parent directory")?;
+            }
+        }
+        
+        let mut source = self.open_file(remote_path, 0)?;
+        let mut local_file = crate::fs_util::temp_file_for(local_path)?;
+        
+        std::io::copy(&mut source, &mut local_file)
+            .with_context(|| format!("Failed to download file: {}", remote_path))?;
+        crate::fs_util::persist(local_file, local_path)?;
+        
+        info!("Downloaded file: {} -> {}", remote_path, local_path.display());
+        Ok(())
+    }
+    
+    /// Read a text file from the SMB repository on a blocking thread
+    async fn read_smb_text_file(&self, remote_path: &str) -> Result<String> {
+        let client = self.smb_client()?.clone();
+        let path = self.get_smb_path(remote_path);
+        
+        tokio::task::spawn_blocking(move || {
+            let mut file = client.open_with(&path, SmbOpenOptions::default().read(true))
+                .with_context(|| format!("Failed to open remote file: {}", path))?;
+            
+            let mut content = String::new();
+            file.read_to_string(&mut content)
+                .with_context(|| format!("Failed to read remote file: {}", path))?;
+            
+            Ok(content)
+        }).await?
+    }
+    
+    /// Recursively collect files under a directory of the SMB repository on a blocking thread
+    ///
+    /// Returns `(path relative to the game directory, size)` pairs.
+    async fn walk_smb_files(&self, game_dir: &str, max_depth: usize, cancel: &CancellationToken) -> Result<Vec<(String, u64)>> {
+        let client = self.smb_client()?.clone();
+        let game_path = self.get_smb_path(game_dir);
+        let cancel = cancel.clone();
+        
+        tokio::task::spawn_blocking(move || Self::walk_smb_files_blocking(&client, &game_path, max_depth, &cancel))
+            .await?
+    }
+    
+    /// Recursively collect files under `game_path` on the current thread
+    fn walk_smb_files_blocking(client: &SmbClient, game_path: &str, max_depth: usize, cancel: &CancellationToken) -> Result<Vec<(String, u64)>> {
+        let mut files = Vec::new();
+        let mut pending = vec![(String::new(), 1usize)];
+        
+        while let Some((rel_dir, depth)) = pending.pop() {
+            if cancel.is_cancelled() {
+                return Err(anyhow::anyhow!("Repository scan cancelled"));
+            }
+            
+            let dir_path = if rel_dir.is_empty() {
+                game_path.to_string()
+            } else {
+                format!("{}/{}", game_path, rel_dir)
+            };
             
-            Ok(())
+            let entries = match client.list_dir(&dir_path) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    warn!("Failed to list SMB directory {}: {}", dir_path, e);
+                    continue;
+                }
+            };
+            
+            for entry in entries {
+                let name = entry.name();
+                if name == "." || name == ".." {
+                    continue;
+                }
+                
+                let rel_path = if rel_dir.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{}/{}", rel_dir, name)
+                };
+                
+                match entry.get_type() {
+                    SmbDirentType::Dir => {
+                        if depth < max_depth {
+                            pending.push((rel_path, depth + 1));
+                        }
+                    }
+                    SmbDirentType::File => {
+                        let size = client.stat(&format!("{}/{}", dir_path, name))
+                            .map(|stat| stat.size)
+                            .unwrap_or(0);
+                        files.push((rel_path, size));
+                    }
+                    _ => {}
+                }
+            }
         }
+        
+        Ok(files)
     }
     
     /// List all game directories and parse their info
-    pub async fn list_games(&self) -> Result<Vec<GameInfo>> {
+    ///
+    /// The scan stops early with an error once `cancel` is triggered.
+    pub async fn list_games(&self, cancel: &CancellationToken) -> Result<Vec<GameInfo>> {
         let directories = self.list_directories().await?;
+        let scan_depth = self.config.scan_depth.max(1);
         let mut games = Vec::new();
         
         for dir in directories {
-            match self.get_game_info(&dir).await {
+            if cancel.is_cancelled() {
+                info!("Repository scan cancelled");
+                return Err(anyhow::anyhow!("Repository scan cancelled"));
+            }
+            
+            match self.get_game_info(&dir, scan_depth, cancel).await {
                 Ok(info) => games.push(info),
+                Err(_) if cancel.is_cancelled() => {
+                    return Err(anyhow::anyhow!("Repository scan cancelled"));
+                }
                 Err(e) => {
                     warn!("Failed to parse game info for {}: {}", dir, e);
                     continue;
@@ -210,8 +443,8 @@ impl SmbConnection {
         Ok(games)
     }
     
-    /// Get game info from a directory
-    async fn get_game_info(&self, dir_name: &str) -> Result<GameInfo> {
+    /// Get game info from a directory, scanning up to `scan_depth` levels of files
+    async fn get_game_info(&self, dir_name: &str, scan_depth: usize, cancel: &CancellationToken) -> Result<GameInfo> {
         info!("Getting game info for: {}", dir_name);
         
         // Initialize game info with default values
@@ -225,102 +458,217 @@ impl SmbConnection {
             igdb_id: None,
             files: Vec::new(),
             versions: Vec::new(),
+            dlc: Vec::new(),
             cover_image: None,
+            install_args: None,
+            gog_id: None,
+            gog_version: None,
+            source: self.config.name.clone(),
         };
         
-        // Try to read real files in local mode
-        if self.using_local_fallback {
+        // Metadata files, in order of preference
+        let info_files = ["info.txt", "!info.txt", "game.info", "game.txt"];
+        
+        // Collect the metadata file content and (relative path, size) of every file
+        let (info_content, entries) = if self.using_local_fallback {
             if let Some(base_path) = &self.local_path {
                 let game_dir = base_path.join(dir_name);
                 
                 // Try to read info.txt or !info.txt for metadata
-                let info_files = ["info.txt", "!info.txt", "game.info", "game.txt"];
+                let mut info_content = None;
                 for info_file in &info_files {
                     let info_path = game_dir.join(info_file);
                     if info_path.exists() && info_path.is_file() {
                         if let Ok(content) = fs::read_to_string(&info_path) {
-                            game_info.parse_metadata(&content);
+                            info_content = Some(content);
                             break;
                         }
                     }
                 }
                 
-                // Apply title from directory name if not found in metadata
-                if game_info.title.is_empty() {
-                    game_info.title = dir_name.replace('_', " ")
-                        .split(' ')
-                        .map(|s| {
-                            let mut chars = s.chars();
-                            match chars.next() {
-                                None => String::new(),
-                                Some(first) => first.to_uppercase().chain(chars).collect(),
-                            }
-                        })
-                        .collect::<Vec<String>>()
-                        .join(" ");
-                }
-                
-                // Scan for game files (executables, installers)
-                let mut game_files = Vec::new();
-                
-                // Define patterns for installer and patch files
-                let installer_regex = Regex::new(r"(?i)(setup|install|launcher).*\.(exe|msi|pkg|dmg)$").unwrap();
-                let patch_regex = Regex::new(r"(?i)(patch|update).*\.(exe|msi|pkg|dmg|zip)$").unwrap();
-                
                 // Walk directory to find files
-                let walker = WalkDir::new(&game_dir).max_depth(2).into_iter();
+                let mut entries = Vec::new();
+                let walker = WalkDir::new(&game_dir)
+                    .min_depth(1)
+                    .max_depth(scan_depth)
+                    .into_iter();
                 for entry in walker.filter_map(|e| e.ok()) {
+                    if cancel.is_cancelled() {
+                        return Err(anyhow::anyhow!("Repository scan cancelled"));
+                    }
+                    
                     let file_path = entry.path();
                     
                     // Skip directories
-                    if file_path.is_dir() {
+                    if entry.file_type().is_dir() {
                         continue;
                     }
                     
-                    // Get file name and extension
-                    if let Some(file_name) = file_path.file_name().and_then(|n| n.to_str()) {
-                        let file_size = fs::metadata(file_path)
-                            .map(|m| m.len())
-                            .unwrap_or(0);
-                        
-                        // Determine file type
-                        let file_type = if installer_regex.is_match(file_name) {
-                            FileType::Installer
-                        } else if patch_regex.is_match(file_name) {
-                            FileType::Patch
-                        } else if file_name.to_lowercase().ends_with(".exe") {
-                            FileType::Installer
-                        } else {
-                            FileType::Other
-                        };
-                        
-                        // Get relative path from base directory
-                        let rel_path = file_path.strip_prefix(&game_dir)
-                            .unwrap_or_else(|_| Path::new(file_name))
-                            .to_string_lossy()
-                            .replace('\\', "/");
-                        
-                        // Add to files list
-                        game_files.push(GameFile {
-                            name: file_name.to_string(),
-                            remote_path: format!("{}/{}", dir_name, rel_path),
-                            size: file_size,
-                            file_type,
-                        });
-                    }
+                    let file_size = fs::metadata(file_path)
+                        .map(|m| m.len())
+                        .unwrap_or(0);
+                    
+                    // Get relative path from the game directory, keeping nested folders
+                    let rel_path = match file_path.strip_prefix(&game_dir) {
+                        Ok(rel_path) => rel_path.to_string_lossy().replace('\\', "/"),
+                        Err(_) => continue,
+                    };
+                    
+                    entries.push((rel_path, file_size));
                 }
                 
-                // Add found files to game info
-                game_info.files = game_files;
+                (info_content, entries)
             } else {
                 // If no local path, use demo data
                 self.add_demo_files(&mut game_info);
+                (None, Vec::new())
             }
         } else {
-            // If not using local fallback, use demo data
-            self.add_demo_files(&mut game_info);
+            let entries = self.walk_smb_files(dir_name, scan_depth, cancel).await?;
+            
+            // Try to read info.txt or !info.txt for metadata
+            let mut info_content = None;
+            for info_file in info_files.iter().filter(|info_file| entries.iter().any(|(path, _)| path == *info_file)) {
+                if let Ok(content) = self.read_smb_text_file(&format!("{}/{}", dir_name, info_file)).await {
+                    info_content = Some(content);
+                    break;
+                }
+            }
+            
+            (info_content, entries)
+        };
+        
+        // GOG installers leave a goggame-<id>.info file, prefer the top-level one
+        let gog_info_regex = Regex::new(r"(?i)(^|/)goggame-\d+\.info$").unwrap();
+        let gog_info_path = entries.iter()
+            .map(|(rel_path, _)| rel_path)
+            .filter(|rel_path| gog_info_regex.is_match(rel_path))
+            .min_by_key(|rel_path| rel_path.matches('/').count());
+        
+        if let Some(rel_path) = gog_info_path {
+            match self.read_text_file(&format!("{}/{}", dir_name, rel_path)).await {
+                Ok(content) => {
+                    if let Err(e) = game_info.parse_gog_info(&content) {
+                        warn!("Failed to parse {} for {}: {}", rel_path, dir_name, e);
+                    }
+                }
+                Err(e) => warn!("Failed to read {} for {}: {}", rel_path, dir_name, e),
+            }
         }
         
+        // Structured metadata files take precedence over the text formats
+        let structured_files = entries.iter()
+            .map(|(path, _)| path)
+            .filter(|path| ["info.yaml", "info.yml", "info.json"].iter().any(|name| path.eq_ignore_ascii_case(name)));
+        
+        let mut structured = false;
+        for file_name in structured_files {
+            let content = match self.read_text_file(&format!("{}/{}", dir_name, file_name)).await {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to read {} for {}: {}", file_name, dir_name, e);
+                    continue;
+                }
+            };
+            
+            let result = if file_name.to_lowercase().ends_with(".json") {
+                game_info.parse_metadata_json(&content).map_err(anyhow::Error::from)
+            } else {
+                game_info.parse_metadata_yaml(&content).map_err(anyhow::Error::from)
+            };
+            
+            match result {
+                Ok(()) => {
+                    structured = true;
+                    break;
+                }
+                Err(e) => warn!("Failed to parse {} for {}: {}", file_name, dir_name, e),
+            }
+        }
+        
+        if !structured {
+            if let Some(content) = &info_content {
+                game_info.parse_metadata(content);
+            }
+        }
+        
+        // Apply title from directory name if not found in metadata
+        if game_info.title.is_empty() {
+            game_info.title = dir_name.replace('_', " ")
+                .split(' ')
+                .map(|s| {
+                    let mut chars = s.chars();
+                    match chars.next() {
+                        None => String::new(),
+                        Some(first) => first.to_uppercase().chain(chars).collect(),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+        }
+        
+        // Pick up cover art shipped at the top level of the game directory
+        let cover_regex = Regex::new(r"(?i)^(cover|folder|poster)\.(jpe?g|png)$").unwrap();
+        game_info.cover_image = entries.iter()
+            .find(|(rel_path, _)| cover_regex.is_match(rel_path))
+            .map(|(rel_path, _)| PathBuf::from(format!("{}/{}", dir_name, rel_path)));
+        
+        // Load checksums published alongside the game files
+        let checksums = self.load_checksums(dir_name, &entries).await;
+        
+        // Define pattern for archive files
+        let archive_regex = Regex::new(r"(?i)\.(zip|rar|7z)$").unwrap();
+        
+        // DLC installers, checked first so they are never taken for base installers
+        let dlc_regex = Regex::new(r"(?i)^dlc_.*\.exe$").unwrap();
+        
+        // Classify game files (executables, installers)
+        for (rel_path, file_size) in entries {
+            let file_name = match rel_path.rsplit('/').next() {
+                Some(name) if !name.is_empty() => name.to_string(),
+                _ => continue,
+            };
+            
+            // Determine file type
+            let file_type = if dlc_regex.is_match(&file_name) {
+                FileType::Dlc
+            } else if self.installer_regex.is_match(&file_name) {
+                FileType::Installer
+            } else if self.patch_regex.is_match(&file_name) {
+                FileType::Patch
+            } else if archive_regex.is_match(&file_name) {
+                FileType::Archive
+            } else if file_name.to_lowercase().ends_with(".exe") {
+                FileType::Installer
+            } else {
+                FileType::Other
+            };
+            
+            // Add to files list
+            let sha256 = checksums.get(&rel_path)
+                .or_else(|| checksums.get(&file_name))
+                .cloned();
+            
+            game_info.files.push(GameFile {
+                name: file_name,
+                remote_path: format!("{}/{}", dir_name, rel_path),
+                size: file_size,
+                file_type,
+                sha256,
+            });
+        }
+        
+        // The same installer can be reachable through a symlink or junction
+        let duplicates = game_info.dedup_files();
+        if duplicates > 0 {
+            info!("Ignored {} duplicate files in {}", duplicates, dir_name);
+        }
+        
+        game_info.dlc = game_info.files.iter()
+            .filter(|file| file.file_type == FileType::Dlc)
+            .cloned()
+            .collect();
+        
         // Parse versions from files
         game_info.parse_versions();
         
@@ -330,16 +678,87 @@ impl SmbConnection {
             let version = crate::repository::game_info::GameVersion {
                 name: "Default Version".to_string(),
                 build: 1,
-                files: game_info.files.clone(),
+                version_key: (1, 0, 0, 0),
+                files: game_info.files.iter()
+                    .filter(|file| file.file_type != FileType::Dlc)
+                    .cloned()
+                    .collect(),
                 required_patches: Vec::new(),
             };
             
             game_info.versions.push(version);
         }
         
+        // Name a version without one in its file names after the GOG version
+        if let Some(gog_version) = &game_info.gog_version {
+            for version in &mut game_info.versions {
+                if version.name == "Default Version" {
+                    version.name = format!("Version {}", gog_version);
+                }
+            }
+        }
+        
         Ok(game_info)
     }
     
+    /// Read a text file from the repository
+    async fn read_text_file(&self, remote_path: &str) -> Result<String> {
+        if self.using_local_fallback {
+            let path = match &self.local_path {
+                Some(base_path) => base_path.join(remote_path),
+                None => return Err(anyhow::anyhow!("Local path not set")),
+            };
+            
+            fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read file: {}", path.display()))
+        } else {
+            self.read_smb_text_file(remote_path).await
+        }
+    }
+    
+    /// Load SHA-256 checksums from a `checksums.txt` or `SHA256SUMS` file in the game directory
+    ///
+    /// Lines use the `sha256sum` format (`<hash>  <file>`). Keys are the listed paths.
+    async fn load_checksums(&self, dir_name: &str, entries: &[(String, u64)]) -> HashMap<String, String> {
+        let mut checksums = HashMap::new();
+        
+        let checksum_files = entries.iter()
+            .map(|(path, _)| path)
+            .filter(|path| {
+                let lower = path.to_lowercase();
+                lower == "checksums.txt" || lower == "sha256sums" || lower == "sha256sums.txt"
+            });
+        
+        for checksum_file in checksum_files {
+            let content = match self.read_text_file(&format!("{}/{}", dir_name, checksum_file)).await {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to read checksum file {}: {}", checksum_file, e);
+                    continue;
+                }
+            };
+            
+            for line in content.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                
+                if let Some((hash, path)) = line.split_once(char::is_whitespace) {
+                    // Binary mode entries prefix the file name with '*'
+                    let path = path.trim().trim_start_matches('*').replace('\\', "/");
+                    let hash = hash.trim().to_lowercase();
+                    
+                    if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                        checksums.insert(path.trim_start_matches("./").to_string(), hash);
+                    }
+                }
+            }
+        }
+        
+        checksums
+    }
+    
     /// Add demo files to a game
     fn add_demo_files(&self, game_info: &mut GameInfo) {
         let dir_name = &game_info.id;
@@ -350,6 +769,7 @@ impl SmbConnection {
             remote_path: format!("{}/setup_{}_gog_build_2241b_(64bit)_(51706).exe", dir_name, dir_name),
             size: 15_000_000,
             file_type: FileType::Installer,
+            sha256: None,
         });
         
         // Add patch files
@@ -358,6 +778,7 @@ impl SmbConnection {
             remote_path: format!("{}/patch_{}_GOG_Build_2055a_(37083)_to_GOG_Build_2172_(47150).exe", dir_name, dir_name),
             size: 2_000_000,
             file_type: FileType::Patch,
+            sha256: None,
         });
         
         // Set demo metadata
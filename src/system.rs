@@ -0,0 +1,14 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Free space in bytes on the drive holding `path`
+///
+/// A directory that doesn't exist yet is measured on its nearest existing ancestor.
+pub fn available_space(path: &Path) -> Result<u64> {
+    let existing = path.ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(path);
+    
+    fs2::available_space(existing)
+        .with_context(|| format!("Failed to check free space on {}", existing.display()))
+}
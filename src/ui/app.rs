@@ -1,16 +1,30 @@
 use eframe::egui;
-use log::{info, error};
+use log::{info, warn, error};
 use std::sync::{Arc, Mutex as StdMutex};
 use tokio::sync::Mutex;
 use tokio::runtime::Runtime;
-use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
-use std::collections::HashMap;
+use tokio::sync::mpsc::{self, unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio_util::sync::CancellationToken;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
-use crate::config::Config;
-use crate::repository::{GameInfo, SmbConnection};
+use crate::config::{Config, IgdbConfig, RepositoryConfig, Theme};
+use crate::library_export;
+use crate::logging;
+use crate::system;
+use crate::installer::{queue, Downloader, InstallScan, Installer, InstalledVersion, JobStatus, QueuedJob};
+use crate::installer::download::{DownloadProgress, DownloadStatus};
+use crate::installer::install::InstallStatus;
+use crate::repository::{FirstSeen, GameInfo, GameVersion, ListingCache, SmbConnection};
+use crate::metadata::{CacheStats, IgdbClient};
+use crate::metadata::igdb::build_http_client;
 use crate::metadata::handler::{MetadataHandler, MetadataStatus};
 use crate::ui::game_detail::{GameDetailView, GameAction};
 use crate::ui::library_view::{LibraryView, LibraryAction};
+use crate::ui::settings::{PathSetting, SettingsView, SettingsAction};
+use crate::ui::image_loader::ImageLoader;
+use crate::ui::helpers;
+use crate::ui::install_panel::{InstallPanel, InstallPanelAction};
 
 /// Application view
 pub enum AppView {
@@ -29,12 +43,119 @@ pub struct RefreshState {
     pub error: Option<String>,
 }
 
+/// How often the free space on the install drive is measured again
+const FREE_SPACE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Minimum time between two download speed measurements
+const SPEED_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Install state for tracking an installation in progress
+#[derive(Debug, Clone, Default)]
+pub struct InstallState {
+    pub game_id: String,
+    /// Title of the game, shown in the progress panel
+    pub game_title: String,
+    pub is_running: bool,
+    pub message: String,
+    /// Progress of the current download (0.0 - 1.0), if known
+    pub progress: Option<f32>,
+    pub error: Option<String>,
+    /// Files of the installation in the order their downloads started
+    pub files: Vec<FileProgress>,
+    /// Total size in bytes of the files to download, once known
+    pub total_bytes: u64,
+    /// Smoothed download speed in bytes per second
+    pub bytes_per_second: f64,
+    /// Time and downloaded bytes of the last speed measurement
+    speed_sample: Option<(Instant, u64)>,
+    /// When the installation succeeded or failed
+    pub finished_at: Option<Instant>,
+}
+
+/// Download progress of one file of an installation
+#[derive(Debug, Clone)]
+pub struct FileProgress {
+    pub name: String,
+    pub downloaded: u64,
+    pub size: u64,
+    pub is_done: bool,
+}
+
+impl InstallState {
+    /// Bytes downloaded so far over every file
+    pub fn downloaded_bytes(&self) -> u64 {
+        self.files.iter().map(|file| file.downloaded).sum()
+    }
+    
+    /// Progress of all downloads (0.0 - 1.0), once the total size is known
+    pub fn overall_progress(&self) -> Option<f32> {
+        if self.total_bytes == 0 {
+            return None;
+        }
+        Some((self.downloaded_bytes() as f64 / self.total_bytes as f64).min(1.0) as f32)
+    }
+    
+    /// Estimated time until all downloads are done, at the current speed
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.total_bytes.saturating_sub(self.downloaded_bytes());
+        if self.bytes_per_second < 1.0 || remaining == 0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(remaining as f64 / self.bytes_per_second))
+    }
+    
+    /// Record the progress of a file and update the download speed
+    fn record_progress(&mut self, progress: &DownloadProgress) {
+        match self.files.iter_mut().find(|file| file.name == progress.file) {
+            Some(file) => {
+                file.downloaded = progress.downloaded;
+                file.size = progress.total;
+            }
+            None => self.files.push(FileProgress {
+                name: progress.file.clone(),
+                downloaded: progress.downloaded,
+                size: progress.total,
+                is_done: false,
+            }),
+        }
+        
+        let now = Instant::now();
+        let downloaded = self.downloaded_bytes();
+        match self.speed_sample {
+            Some((at, bytes)) if now.duration_since(at) >= SPEED_SAMPLE_INTERVAL => {
+                let speed = downloaded.saturating_sub(bytes) as f64 / now.duration_since(at).as_secs_f64();
+                self.bytes_per_second = if self.bytes_per_second > 0.0 {
+                    self.bytes_per_second * 0.7 + speed * 0.3
+                } else {
+                    speed
+                };
+                self.speed_sample = Some((now, downloaded));
+            }
+            Some(_) => {}
+            // Start measuring after the first update, so resumed bytes don't count as speed
+            None => self.speed_sample = Some((now, downloaded)),
+        }
+    }
+}
+
+/// Games listed by a repository scan
+struct ScanResult {
+    /// Games of every repository that could be listed
+    games: Vec<GameInfo>,
+    /// Name and error of every repository that could not be listed
+    failed: Vec<(String, String)>,
+}
+
 /// Game Library App
 pub struct GameLibraryApp {
     /// Current view
     view: AppView,
     /// Configuration
     config: Config,
+    /// Theme and OS preference the visuals were last set for
+    applied_theme: Option<(Theme, Option<eframe::Theme>)>,
+    /// Whether the window has keyboard focus, which suppresses notifications
+    window_focused: bool,
     /// SMB connection
     smb_connection: Option<SmbConnection>,
     /// Game list
@@ -43,6 +164,10 @@ pub struct GameLibraryApp {
     library_view: LibraryView,
     /// Game detail view
     game_detail_view: Option<GameDetailView>,
+    /// Progress of the running installation, shown below every view
+    install_panel: InstallPanel,
+    /// Background image decoder shared by the views
+    image_loader: ImageLoader,
     /// Selected game ID
     selected_game_id: Option<String>,
     
@@ -57,9 +182,31 @@ pub struct GameLibraryApp {
     
     // Connection state
     is_connecting: bool,
+    scan_cancel_token: Option<CancellationToken>,
+    connection_error: Option<String>,
+    
+    // Result of the last repository connection test (directory count or error)
+    connection_test_result: Arc<StdMutex<Option<Result<usize, String>>>>,
+    is_testing_connection: Arc<StdMutex<bool>>,
+    
+    // Result of the last IGDB credential check
+    igdb_validation_result: Arc<StdMutex<Option<Result<(), String>>>>,
+    is_validating_igdb: Arc<StdMutex<bool>>,
+    
+    // Folder chosen in the settings folder picker, and whether the picker is open
+    picked_folder: Arc<StdMutex<Option<(PathSetting, std::path::PathBuf)>>>,
+    is_picking_folder: Arc<StdMutex<bool>>,
+    
+    // Result of the last library export, and whether its save dialog or write is running
+    library_export_result: Arc<StdMutex<Option<Result<String, String>>>>,
+    is_exporting_library: Arc<StdMutex<bool>>,
+    
+    // Problems found by the last file verification as (game ID, problems), and whether one is running
+    verify_result: Arc<StdMutex<Option<(String, Vec<String>)>>>,
+    is_verifying: Arc<StdMutex<bool>>,
     
     // Channel for receiving games from repository (still using std channel here)
-    games_receiver: Option<std::sync::mpsc::Receiver<Vec<GameInfo>>>,
+    games_receiver: Option<std::sync::mpsc::Receiver<ScanResult>>,
     
     // Channel for metadata operations using a Tokio unbounded channel
     metadata_status_sender: Option<UnboundedSender<MetadataStatus>>,
@@ -68,17 +215,62 @@ pub struct GameLibraryApp {
     // Batch operation state
     is_batch_refreshing: bool,
     batch_progress: Option<(usize, usize)>, // (completed, total)
+    batch_cancel_token: Option<CancellationToken>,
+    batch_failed_games: Vec<(String, String)>, // (game_id, game_name)
+    
+    // Settings view, while it is open
+    settings_view: Option<SettingsView>,
+    // Disk usage of the metadata cache, measured when the settings view opens
+    cache_stats: Option<CacheStats>,
+    
+    // Result of the last metadata export or import
+    metadata_transfer_result: Option<Result<String, String>>,
+    
+    // Installation state
+    install_state: Option<InstallState>,
+    install_status_receiver: Option<mpsc::Receiver<InstallStatus>>,
+    install_cancel_token: Option<CancellationToken>,
+    // Installations run one after another, persisted across restarts
+    install_jobs: Vec<QueuedJob>,
+    // Results of the installations since the queue was last empty, for the notification
+    install_results: Vec<String>,
+    installed_games: HashSet<String>,
+    installed_versions: HashMap<String, InstalledVersion>,
+    installer: Installer,
+    // Free space on the install drive, and when it was measured
+    install_free_space: Option<u64>,
+    free_space_checked_at: Option<Instant>,
+    // Result of the last scan of the install directory for games installed elsewhere
+    install_scan_result: Option<Result<InstallScan, String>>,
+    
+    // Game awaiting uninstall confirmation
+    pending_uninstall: Option<GameInfo>,
+    
+    // Games currently running, and a channel notified when a play session ends
+    running_games: Arc<StdMutex<HashSet<String>>>,
+    play_session_sender: std::sync::mpsc::Sender<String>,
+    play_session_receiver: std::sync::mpsc::Receiver<String>,
 }
 
 impl GameLibraryApp {
     /// Create a new game library app
-    pub fn new(_cc: &eframe::CreationContext<'_>, config: Config) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, config: Config) -> Self {
         // Create tokio runtime
         let rt = Runtime::new().expect("Failed to create runtime");
         
-        let library_view = LibraryView::new();
+        let image_loader = ImageLoader::new(cc.egui_ctx.clone());
+        let mut library_view = LibraryView::new(image_loader.clone());
+        library_view.set_favorites(config.library.favorites.clone());
+        library_view.set_cover_width(config.library.cover_width);
+        library_view.set_max_cover_textures(config.library.max_cover_textures);
+        library_view.set_group_by(config.library.group_by);
+        library_view.set_show_sources(config.enabled_repositories().len() > 1);
+        
+        let smb_connection = Some(SmbConnection::new(config.repository().clone()));
         
-        let smb_connection = Some(SmbConnection::new(config.repository.clone()));
+        let installer = Self::create_installer(&config);
+        let install_jobs = queue::load(&config.paths.cache_dir);
+        let (play_session_tx, play_session_rx) = std::sync::mpsc::channel();
         
         // Create channel for metadata status updates using Tokio unbounded channel
         let (metadata_tx, metadata_rx) = unbounded_channel();
@@ -86,88 +278,261 @@ impl GameLibraryApp {
         let mut app = Self {
             view: AppView::Library,
             config,
+            applied_theme: None,
+            window_focused: true,
             smb_connection,
             games: Vec::new(),
             library_view,
             game_detail_view: None,
+            install_panel: InstallPanel::default(),
+            image_loader,
             selected_game_id: None,
             metadata_handler: None,
             rt,
             refresh_states: HashMap::new(),
             is_connecting: false,
+            scan_cancel_token: None,
+            connection_error: None,
+            connection_test_result: Arc::new(StdMutex::new(None)),
+            is_testing_connection: Arc::new(StdMutex::new(false)),
+            igdb_validation_result: Arc::new(StdMutex::new(None)),
+            is_validating_igdb: Arc::new(StdMutex::new(false)),
+            picked_folder: Arc::new(StdMutex::new(None)),
+            is_picking_folder: Arc::new(StdMutex::new(false)),
+            library_export_result: Arc::new(StdMutex::new(None)),
+            is_exporting_library: Arc::new(StdMutex::new(false)),
+            verify_result: Arc::new(StdMutex::new(None)),
+            is_verifying: Arc::new(StdMutex::new(false)),
             games_receiver: None,
             metadata_status_sender: Some(metadata_tx),
             metadata_status_receiver: Some(metadata_rx),
             is_batch_refreshing: false,
             batch_progress: None,
+            batch_cancel_token: None,
+            batch_failed_games: Vec::new(),
+            settings_view: None,
+            cache_stats: None,
+            metadata_transfer_result: None,
+            install_state: None,
+            install_status_receiver: None,
+            install_cancel_token: None,
+            install_jobs,
+            install_results: Vec::new(),
+            installed_games: HashSet::new(),
+            installed_versions: HashMap::new(),
+            installer,
+            install_free_space: None,
+            free_space_checked_at: None,
+            install_scan_result: None,
+            pending_uninstall: None,
+            running_games: Arc::new(StdMutex::new(HashSet::new())),
+            play_session_sender: play_session_tx,
+            play_session_receiver: play_session_rx,
         };
         
-        // Initial connection to repository
-        app.connect_to_repository();
+        app.load_first_seen();
+        
+        // Show the cached listing immediately and only rescan when it is missing or stale
+        match ListingCache::load(&app.config.paths.cache_dir) {
+            Some(cache) => {
+                let is_stale = cache.is_stale(app.config.repository().listing_stale_hours);
+                app.games = cache.games;
+                app.refresh_installed_games();
+                
+                if is_stale && !app.config.offline {
+                    info!("Cached repository listing is stale, rescanning");
+                    app.connect_to_repository();
+                }
+            }
+            None => app.connect_to_repository(),
+        }
+        
+        // Carry on with installations queued in the last session
+        app.start_next_queued_install();
         
         app
     }
     
+    /// Pass the recorded first-seen times of the games to the library view
+    fn load_first_seen(&mut self) {
+        if let Some(first_seen) = FirstSeen::load(&self.config.paths.cache_dir) {
+            self.library_view.set_first_seen(first_seen.games);
+        }
+    }
+    
     /// Connect to repository
+    ///
+    /// In offline mode the persisted listing is loaded instead.
     fn connect_to_repository(&mut self) {
-        if self.is_connecting {
+        // Stop any scan that is still running before starting a new one
+        if let Some(token) = self.scan_cancel_token.take() {
+            info!("Cancelling in-flight repository scan");
+            token.cancel();
+        }
+        
+        if self.config.offline {
+            self.load_cached_listing();
             return;
         }
         
+        let cancel_token = CancellationToken::new();
+        self.scan_cancel_token = Some(cancel_token.clone());
+        
         self.is_connecting = true;
+        self.connection_error = None;
         
         // Create a channel to receive games (using std channel for now)
         let (tx, rx) = std::sync::mpsc::channel();
         self.games_receiver = Some(rx);
         
-        // Create a new connection for the async task
-        let config_clone = self.config.repository.clone();
+        let repositories = self.config.enabled_repositories();
+        let has_repositories = !repositories.is_empty();
+        let cache_dir = self.config.paths.cache_dir.clone();
+        let prefer_local_covers = self.config.metadata.prefer_local_covers;
         
-        // Spawn a background task to connect and list games
+        // Spawn a background task to list the games of every enabled repository
         self.rt.spawn(async move {
-            // Create a new connection in the async task
-            let mut connection = SmbConnection::new(config_clone);
+            let mut games: Vec<GameInfo> = Vec::new();
+            let mut failed = Vec::new();
             
-            // Connect to repository
-            match connection.connect().await {
-                Ok(_) => {
-                    info!("Connected to repository");
-                    
-                    // List games
-                    match connection.list_games().await {
-                        Ok(games) => {
-                            info!("Found {} games in repository", games.len());
-                            
-                            // Send games back to main thread
-                            if let Err(e) = tx.send(games) {
-                                error!("Failed to send games to main thread: {}", e);
+            for repository in repositories {
+                let name = repository.name.clone();
+                
+                match Self::scan_repository(repository, &cache_dir, prefer_local_covers, &cancel_token).await {
+                    Ok(found) => {
+                        for game in found {
+                            if games.iter().any(|listed| listed.id == game.id) {
+                                warn!("Skipping {} from {}, it is already listed from another repository", game.id, name);
+                            } else {
+                                games.push(game);
                             }
                         }
-                        Err(e) => {
-                            error!("Failed to list games: {}", e);
-                        }
                     }
+                    Err(error) => failed.push((name, error)),
                 }
-                Err(e) => {
-                    error!("Failed to connect to repository: {}", e);
+            }
+            
+            // A newer scan has replaced this one, so nobody is listening for the result
+            if cancel_token.is_cancelled() {
+                return;
+            }
+            
+            // Only a complete listing may prune games or replace the cached listing; with no
+            // repository enabled the empty listing says nothing about the games
+            let result = if failed.is_empty() && has_repositories {
+                if let Err(e) = FirstSeen::record_scan(&cache_dir, &games) {
+                    error!("Failed to record new games: {}", e);
+                }
+                
+                let cache = ListingCache::new(games);
+                if let Err(e) = cache.save(&cache_dir) {
+                    error!("Failed to save repository listing: {}", e);
                 }
+                
+                ScanResult { games: cache.games, failed }
+            } else {
+                ScanResult { games, failed }
+            };
+            
+            // Send result back to main thread
+            if let Err(e) = tx.send(result) {
+                error!("Failed to send games to main thread: {}", e);
             }
         });
     }
     
+    /// Show the listing saved by the last complete scan, without contacting the repository
+    fn load_cached_listing(&mut self) {
+        self.is_connecting = false;
+        self.games_receiver = None;
+        
+        match ListingCache::load(&self.config.paths.cache_dir) {
+            Some(cache) => {
+                info!("Offline mode, showing {} games from the cached listing", cache.games.len());
+                self.connection_error = None;
+                self.games = cache.games;
+                self.refresh_installed_games();
+                self.register_local_covers();
+                self.load_first_seen();
+            }
+            None => {
+                self.connection_error = Some("Offline mode is enabled and no repository listing is cached".to_string());
+            }
+        }
+    }
+    
+    /// Connect to a repository and list its games, labeled with the repository name
+    async fn scan_repository(
+        repository: RepositoryConfig,
+        cache_dir: &std::path::Path,
+        prefer_local_covers: bool,
+        cancel_token: &CancellationToken,
+    ) -> Result<Vec<GameInfo>, String> {
+        let name = repository.name.clone();
+        let mut connection = SmbConnection::new(repository);
+        
+        if let Err(e) = connection.connect().await {
+            error!("Failed to connect to repository {}: {}", name, e);
+            return Err(format!("Failed to connect to repository: {}", e));
+        }
+        info!("Connected to repository {}", name);
+        
+        match connection.list_games(cancel_token).await {
+            Ok(mut games) => {
+                info!("Found {} games in repository {}", games.len(), name);
+                
+                for game in &mut games {
+                    game.source = name.clone();
+                }
+                
+                if prefer_local_covers {
+                    Self::copy_local_covers(&connection, &games, cache_dir).await;
+                }
+                
+                Ok(games)
+            }
+            Err(e) => {
+                error!("Failed to list games in repository {}: {}", name, e);
+                Err(format!("Failed to list games: {}", e))
+            }
+        }
+    }
+    
     /// Check for repository connection results
     fn check_repository_results(&mut self) {
         if let Some(receiver) = &self.games_receiver {
             // Check if we have received games from the repository
             match receiver.try_recv() {
-                Ok(games) => {
-                    info!("Received {} games from repository", games.len());
+                Ok(ScanResult { mut games, failed }) => {
+                    info!("Received {} games from the repositories", games.len());
+                    
+                    // Keep showing the games of repositories that could not be reached
+                    for game in &self.games {
+                        let source_failed = failed.iter().any(|(name, _)| *name == game.source);
+                        if source_failed && !games.iter().any(|listed| listed.id == game.id) {
+                            games.push(game.clone());
+                        }
+                    }
+                    
+                    self.connection_error = if failed.is_empty() {
+                        None
+                    } else {
+                        Some(failed.iter()
+                            .map(|(name, error)| format!("{}: {}", name, error))
+                            .collect::<Vec<_>>()
+                            .join("\n"))
+                    };
+                    
                     self.games = games;
+                    self.refresh_installed_games();
+                    self.register_local_covers();
+                    self.load_first_seen();
                     self.is_connecting = false;
                     self.games_receiver = None; // Done receiving
                 }
                 Err(std::sync::mpsc::TryRecvError::Empty) => {}
                 Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.connection_error = Some("Repository connection ended unexpectedly".to_string());
                     self.is_connecting = false;
                     self.games_receiver = None;
                 }
@@ -175,6 +540,50 @@ impl GameLibraryApp {
         }
     }
     
+    /// Copy cover images shipped with games into the metadata cache
+    ///
+    /// Games that already have a cached cover keep it.
+    async fn copy_local_covers(connection: &SmbConnection, games: &[GameInfo], cache_dir: &std::path::Path) {
+        for game in games {
+            let Some(cover_image) = &game.cover_image else {
+                continue;
+            };
+            
+            let cover_path = cache_dir.join("images").join(format!("{}_cover.jpg", game.id));
+            if cover_path.exists() {
+                continue;
+            }
+            
+            let remote_path = cover_image.to_string_lossy().replace('\\', "/");
+            if let Err(e) = connection.download_file(&remote_path, &cover_path).await {
+                warn!("Failed to copy local cover for {}: {}", game.title, e);
+            }
+        }
+    }
+    
+    /// Record covers copied from game directories in the metadata cache
+    fn register_local_covers(&mut self) {
+        if !self.config.metadata.prefer_local_covers {
+            return;
+        }
+        
+        self.ensure_metadata_handler();
+        
+        let Some(handler) = &mut self.metadata_handler else {
+            return;
+        };
+        
+        for game in self.games.iter().filter(|game| game.cover_image.is_some()) {
+            if handler.has_cover(&game.id) {
+                if let Err(e) = handler.register_cover(&game.id) {
+                    warn!("Failed to record local cover for {}: {}", game.title, e);
+                }
+            }
+        }
+        
+        self.library_view.clear_texture_cache();
+    }
+    
     /// Check for metadata status updates using the Tokio unbounded channel
     fn check_metadata_status(&mut self) {
         let mut need_recreate_channel = false;
@@ -230,6 +639,14 @@ impl GameLibraryApp {
                 }
                 MetadataStatus::Success { game_id, game_name } => {
                     info!("Successfully fetched metadata for {}", game_name);
+                    
+                    // The refresh ran on a copy of the handler, so pick up what it wrote
+                    if let Some(handler) = &mut self.metadata_handler {
+                        if let Err(e) = handler.reload_metadata(&game_id) {
+                            warn!("Failed to reload metadata for {}: {}", game_id, e);
+                        }
+                    }
+                    self.library_view.invalidate_filter();
                     if let Some(state) = self.refresh_states.get(&game_id) {
                         let mut state = state.lock().unwrap();
                         state.is_refreshing = false;
@@ -260,295 +677,1456 @@ impl GameLibraryApp {
                 MetadataStatus::Progress { completed, total } => {
                     self.batch_progress = Some((completed, total));
                 }
-                MetadataStatus::Completed { successful, failed, total } => {
+                MetadataStatus::Completed { successful, failed, total, failed_games } => {
                     info!("Completed metadata update: {}/{} successful, {} failed", successful, total, failed);
                     self.is_batch_refreshing = false;
                     self.batch_progress = None;
+                    self.batch_cancel_token = None;
+                    self.library_view.set_failed_refreshes(failed_games.len());
+                    self.batch_failed_games = failed_games;
+                    
+                    let mut summary = format!("Updated {} of {} games", successful, total);
+                    if failed > 0 {
+                        summary.push_str(&format!(", {} failed", failed));
+                    }
+                    self.notify("Metadata refresh finished", summary);
+                    
+                    if let Some(handler) = &mut self.metadata_handler {
+                        let library: HashSet<String> = self.games.iter().map(|game| game.id.clone()).collect();
+                        if let Err(e) = handler.enforce_cover_budget(&library) {
+                            warn!("Failed to enforce cover cache budget: {}", e);
+                        }
+                    }
                     self.library_view.clear_texture_cache();
                 }
             }
         }
     }
     
-    /// Ensure metadata handler is initialized
-    fn ensure_metadata_handler(&mut self) {
-        if self.metadata_handler.is_none() {
-            let handler = MetadataHandler::new(
-                self.config.igdb.clone(),
-                self.config.paths.cache_dir.clone(),
-            ).expect("Failed to create metadata handler");
-            
-            self.metadata_handler = Some(handler);
-            
-            if let Some(handler) = &mut self.metadata_handler {
-                if let Some(tx) = &self.metadata_status_sender {
-                    handler.set_progress_channel(tx.clone());
+    /// Check for installation status updates
+    fn check_install_status(&mut self) {
+        let mut collected_statuses = Vec::new();
+        let mut disconnected = false;
+        
+        if let Some(receiver) = &mut self.install_status_receiver {
+            loop {
+                match receiver.try_recv() {
+                    Ok(status) => collected_statuses.push(status),
+                    Err(mpsc::error::TryRecvError::Empty) => break,
+                    Err(mpsc::error::TryRecvError::Disconnected) => {
+                        disconnected = true;
+                        break;
+                    }
                 }
             }
-            
-            let handler_copy = self.metadata_handler.as_ref().unwrap().clone();
-            let handler_mutex = Arc::new(Mutex::new(handler_copy));
-            
-            self.rt.spawn(async move {
-                let mut handler = handler_mutex.lock().await;
-                if let Err(e) = handler.initialize().await {
-                    eprintln!("Failed to initialize metadata handler: {}", e);
+        }
+        
+        let Some(state) = &mut self.install_state else {
+            return;
+        };
+        let mut install_finished = false;
+        let mut job_result = None;
+        
+        for status in collected_statuses {
+            match status {
+                InstallStatus::Downloading(DownloadStatus::Queued { size, .. }) => {
+                    state.total_bytes = size;
                 }
-            });
+                InstallStatus::Downloading(DownloadStatus::Started { file, .. }) => {
+                    state.message = format!("Downloading {}", file);
+                    state.progress = Some(0.0);
+                }
+                InstallStatus::Downloading(DownloadStatus::Progress(progress)) => {
+                    state.progress = Some(progress.percentage / 100.0);
+                    state.record_progress(&progress);
+                }
+                InstallStatus::Downloading(DownloadStatus::Completed { file, .. }) => {
+                    if let Some(file) = state.files.iter_mut().find(|progress| progress.name == file) {
+                        file.is_done = true;
+                    }
+                }
+                InstallStatus::Downloading(DownloadStatus::Failed { error }) => {
+                    state.message = format!("Download failed: {}", error);
+                }
+                InstallStatus::Installing { game, version } => {
+                    state.message = format!("Installing {} ({})", game, version);
+                    state.progress = None;
+                }
+                InstallStatus::Completed { game, install_dir } => {
+                    info!("Installed {} to {}", game, install_dir.display());
+                    state.is_running = false;
+                    state.message = format!("Installed to {}", install_dir.display());
+                    state.progress = None;
+                    state.finished_at = Some(Instant::now());
+                    install_finished = true;
+                    job_result = Some((JobStatus::Done, state.message.clone()));
+                    self.install_results.push(format!("{} installed", game));
+                }
+                InstallStatus::Failed { error } => {
+                    error!("Installation failed: {}", error);
+                    state.is_running = false;
+                    state.progress = None;
+                    state.error = Some(format!("Installation failed: {}", error));
+                    state.finished_at = Some(Instant::now());
+                    job_result = Some((JobStatus::Failed, error.clone()));
+                    self.install_results.push(format!("{} failed: {}", state.game_id, error));
+                }
+            }
+        }
+        
+        if install_finished {
+            self.refresh_installed_games();
+            self.free_space_checked_at = None;
+        }
+        
+        if disconnected {
+            let Some(state) = &mut self.install_state else {
+                return;
+            };
+            if state.is_running {
+                state.is_running = false;
+                state.error = Some("Installation failed: installation ended unexpectedly".to_string());
+                state.finished_at = Some(Instant::now());
+                job_result = Some((JobStatus::Failed, "Installation ended unexpectedly".to_string()));
+            }
+            self.install_status_receiver = None;
+        }
+        
+        if let Some((status, message)) = job_result {
+            self.finish_active_job(status, message);
         }
+        
+        self.start_next_queued_install();
+        self.notify_install_results();
     }
     
-    /// Handle game selection from library
-    fn handle_game_selection(&mut self, idx: usize) {
-        if let Some(game) = self.games.get(idx) {
-            self.selected_game_id = Some(game.id.clone());
-            self.view = AppView::GameDetail(game.id.clone());
+    /// Send one notification for all installations once the queue has drained
+    fn notify_install_results(&mut self) {
+        let is_running = self.install_state.as_ref().map_or(false, |state| state.is_running);
+        let is_queued = self.install_jobs.iter().any(|job| job.status == JobStatus::Queued);
+        if is_running || is_queued || self.install_results.is_empty() {
+            return;
         }
+        
+        let results = std::mem::take(&mut self.install_results);
+        let summary = if results.len() == 1 { "Installation finished" } else { "Installations finished" };
+        self.notify(summary, results.join("\n"));
     }
     
-    /// Handle library action
-    fn handle_library_action(&mut self, action: LibraryAction) {
-        match action {
-            LibraryAction::SelectGame(idx) => self.handle_game_selection(idx),
-            LibraryAction::RefreshAll => self.refresh_all_metadata(),
+    /// Show a desktop notification, if enabled and the window is in the background
+    fn notify(&self, summary: &str, body: String) {
+        if !self.config.notifications || self.window_focused {
+            return;
         }
+        
+        let summary = summary.to_string();
+        self.rt.spawn_blocking(move || {
+            let result = notify_rust::Notification::new()
+                .appname("Game Library Manager")
+                .summary(&summary)
+                .body(&body)
+                .show();
+            
+            if let Err(e) = result {
+                warn!("Failed to show notification: {}", e);
+            }
+        });
     }
     
-    /// Refresh metadata for all games
-    fn refresh_all_metadata(&mut self) {
-        if self.is_batch_refreshing {
+    /// Start the next queued installation once nothing else is installing
+    ///
+    /// Queued installations wait while offline mode is enabled.
+    fn start_next_queued_install(&mut self) {
+        if self.config.offline || self.install_state.as_ref().map_or(false, |state| state.is_running) {
             return;
         }
         
-        self.ensure_metadata_handler();
-        self.is_batch_refreshing = true;
+        let Some(job) = self.install_jobs.iter_mut().find(|job| job.status == JobStatus::Queued) else {
+            return;
+        };
+        job.status = JobStatus::Active;
+        let (game, version) = (job.game.clone(), job.version.clone());
         
-        let game_pairs: Vec<(String, String)> = self.games
-            .iter()
-            .map(|game| (game.id.clone(), game.title.clone()))
-            .collect();
+        self.save_install_jobs();
+        self.start_install(&game, version);
+    }
+    
+    /// Add an installation to the end of the queue, starting it if nothing else is installing
+    fn enqueue_install(&mut self, game: &GameInfo, version: GameVersion) {
+        if self.install_jobs.iter().any(|job| job.is_pending() && job.game.id == game.id) {
+            info!("{} is already queued for installation", game.title);
+            return;
+        }
         
-        let game_pairs_clone = game_pairs.clone();
+        info!("Queued {} (version: {}) for installation", game.title, version.name);
+        let id = self.install_jobs.iter().map(|job| job.id + 1).max().unwrap_or(0);
+        self.install_jobs.push(QueuedJob {
+            id,
+            game: game.clone(),
+            version,
+            status: JobStatus::Queued,
+            message: None,
+        });
         
-        if let Some(handler) = &self.metadata_handler {
-            let handler_copy = handler.clone();
-            let handler_mutex = Arc::new(Mutex::new(handler_copy));
-            
-            self.rt.spawn(async move {
-                let mut handler = handler_mutex.lock().await;
-                if let Err(e) = handler.update_library_metadata(&game_pairs_clone).await {
-                    error!("Error in batch metadata update: {}", e);
-                }
-            });
+        self.save_install_jobs();
+        self.start_next_queued_install();
+    }
+    
+    /// Record the outcome of the running job
+    fn finish_active_job(&mut self, status: JobStatus, message: String) {
+        if let Some(job) = self.install_jobs.iter_mut().find(|job| job.status == JobStatus::Active) {
+            job.status = status;
+            job.message = Some(message);
+            self.save_install_jobs();
         }
     }
     
-    /// Handle game action
-    fn handle_game_action(&mut self, action: GameAction, game_id: &str, game: &GameInfo) {
-        match action {
-            GameAction::Back => self.view = AppView::Library,
-            GameAction::Install(version_idx) => {
-                info!("Installing game: {} (version: {})", game.title, version_idx);
-                // TODO: Implement installation
-            }
-            GameAction::Uninstall => {
-                info!("Uninstalling game: {}", game.title);
-                // TODO: Implement uninstallation
-            }
-            GameAction::FetchMetadata => {
-                self.ensure_metadata_handler();
-                
-                let game_id = game_id.to_string();
-                let game_name = game.title.clone();
-                
-                let state = Arc::new(StdMutex::new(RefreshState {
-                    game_id: game_id.clone(),
-                    is_refreshing: true,
-                    error: None,
-                }));
-                
-                self.refresh_states.insert(game_id.clone(), state.clone());
-                
-                if let Some(detail_view) = &mut self.game_detail_view {
-                    detail_view.set_refresh_pending(true);
-                    detail_view.set_error(None);
-                }
-                
-                if let Some(handler) = &self.metadata_handler {
-                    let handler_copy = handler.clone();
-                    let handler_mutex = Arc::new(Mutex::new(handler_copy));
-                    
-                    let game_id_clone = game_id.clone();
-                    let game_name_clone = game_name.clone();
-                    let state_clone = state.clone();
-                    
-                    self.rt.spawn(async move {
-                        let mut handler = handler_mutex.lock().await;
-                        let result = handler.refresh_metadata(&game_id_clone, &game_name_clone).await;
-                        
-                        let mut state = state_clone.lock().unwrap();
-                        state.is_refreshing = false;
-                        
-                        if let Err(e) = result {
-                            state.error = Some(e.to_string());
-                        }
-                    });
-                }
-            }
+    /// Persist the installation queue so it survives a restart
+    fn save_install_jobs(&self) {
+        if let Err(e) = queue::save(&self.config.paths.cache_dir, &self.install_jobs) {
+            error!("Failed to save installation queue: {}", e);
         }
     }
     
-    /// Render the settings view
-    fn render_settings(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Settings");
-        
-        if ui.button("Back").clicked() {
-            self.view = AppView::Library;
+    /// Measure the free space on the install drive if it is due
+    ///
+    /// Clearing `free_space_checked_at` forces a new measurement.
+    fn refresh_free_space(&mut self) {
+        let is_due = self.free_space_checked_at
+            .map_or(true, |checked_at| checked_at.elapsed() >= FREE_SPACE_INTERVAL);
+        if !is_due {
+            return;
         }
         
-        ui.separator();
+        self.free_space_checked_at = Some(Instant::now());
+        self.install_free_space = match system::available_space(&self.config.paths.install_dir) {
+            Ok(available) => Some(available),
+            Err(e) => {
+                warn!("{:#}", e);
+                None
+            }
+        };
+    }
+    
+    /// Create an installer for local install state and uninstalls
+    ///
+    /// The repository connection is never opened, so this must not be used to download.
+    fn create_installer(config: &Config) -> Installer {
+        let connection = SmbConnection::new(config.repository().clone());
+        let downloader = Downloader::new(config, Arc::new(connection));
+        Installer::new(config.clone(), Arc::new(downloader))
+    }
+    
+    /// Recompute which games are installed from their install markers
+    fn refresh_installed_games(&mut self) {
+        self.installed_games = self.games.iter()
+            .filter(|game| self.installer.is_installed(game))
+            .map(|game| game.id.clone())
+            .collect();
         
-        ui.heading("Repository Settings");
+        self.installed_versions = self.games.iter()
+            .filter(|game| self.installed_games.contains(&game.id))
+            .filter_map(|game| self.installer.installed_version(game))
+            .map(|installed| (installed.game_id.clone(), installed))
+            .collect();
+    }
+    
+    /// Wait for a launched game to exit in the background and record its playtime
+    fn track_play_session(&mut self, game: &GameInfo, mut child: std::process::Child) {
+        self.running_games.lock().unwrap().insert(game.id.clone());
         
-        let mut server = self.config.repository.server.clone();
-        let mut share = self.config.repository.share.clone();
-        let mut username = self.config.repository.username.clone();
-        let mut password = self.config.repository.password.clone();
-        let mut base_dir = self.config.repository.base_dir.clone();
+        let installer = self.installer.clone();
+        let running_games = self.running_games.clone();
+        let session_tx = self.play_session_sender.clone();
+        let game = game.clone();
         
-        ui.horizontal(|ui| {
-            ui.label("Server:");
-            ui.text_edit_singleline(&mut server);
+        self.rt.spawn(async move {
+            let started = std::time::Instant::now();
+            
+            match tokio::task::spawn_blocking(move || child.wait()).await {
+                Ok(Ok(status)) => info!("{} exited with {}", game.title, status),
+                Ok(Err(e)) => error!("Failed to wait for {}: {}", game.title, e),
+                Err(e) => error!("Failed to wait for {}: {}", game.title, e),
+            }
+            
+            if let Err(e) = installer.record_playtime(&game, started.elapsed()) {
+                error!("Failed to record playtime for {}: {}", game.title, e);
+            }
+            
+            running_games.lock().unwrap().remove(&game.id);
+            let _ = session_tx.send(game.id);
         });
-        
-        ui.horizontal(|ui| {
-            ui.label("Share:");
-            ui.text_edit_singleline(&mut share);
+    }
+    
+    /// Pick up finished play sessions so the recorded playtime is shown
+    fn check_play_sessions(&mut self) {
+        let mut finished = false;
+        while self.play_session_receiver.try_recv().is_ok() {
+            finished = true;
+        }
+        
+        if finished {
+            self.refresh_installed_games();
+        }
+    }
+    
+    /// Uninstall a game after the user confirmed it
+    fn uninstall_game(&mut self, game: &GameInfo) {
+        info!("Uninstalling game: {}", game.title);
+        
+        match self.installer.uninstall_game(game) {
+            Ok(()) => {
+                if self.config.install.folders.remove(&game.id).is_some() {
+                    if let Err(e) = self.config.save() {
+                        error!("Failed to save configuration: {}", e);
+                    }
+                    self.installer = Self::create_installer(&self.config);
+                }
+                self.refresh_installed_games();
+                self.free_space_checked_at = None;
+                if self.install_state.as_ref().map_or(false, |state| state.game_id == game.id) {
+                    self.install_state = None;
+                }
+            }
+            Err(e) => {
+                error!("Failed to uninstall {}: {}", game.title, e);
+                self.install_state = Some(InstallState {
+                    game_id: game.id.clone(),
+                    is_running: false,
+                    message: String::new(),
+                    progress: None,
+                    error: Some(format!("Uninstall failed: {}", e)),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    
+    /// Other library games sharing a franchise or collection with a game, as (game ID, title)
+    fn series_games(&self, game: &GameInfo) -> Vec<(String, String)> {
+        let Some(handler) = &self.metadata_handler else {
+            return Vec::new();
+        };
+        
+        let series_of = |game: &GameInfo| {
+            handler.get_metadata(&game.id)
+                .and_then(|metadata| metadata.igdb_data.as_ref())
+                .map(|igdb_data| igdb_data.series())
+                .unwrap_or_default()
+        };
+        
+        let series = series_of(game);
+        if series.is_empty() {
+            return Vec::new();
+        }
+        
+        self.games.iter()
+            .filter(|other| other.id != game.id)
+            .filter(|other| series_of(*other).iter().any(|name| series.contains(name)))
+            .map(|other| (other.id.clone(), other.title.clone()))
+            .collect()
+    }
+    
+    /// Games IGDB lists as similar to a game, as (title, library game ID if owned)
+    ///
+    /// A similar game is owned if a library game has its IGDB ID or, lacking metadata, its title.
+    fn similar_games(&self, game: &GameInfo) -> Vec<(String, Option<String>)> {
+        let Some(handler) = &self.metadata_handler else {
+            return Vec::new();
+        };
+        let igdb_data_of = |game: &GameInfo| {
+            handler.get_metadata(&game.id).and_then(|metadata| metadata.igdb_data.as_ref())
+        };
+        
+        let Some(similar_games) = igdb_data_of(game).and_then(|igdb_data| igdb_data.similar_games.as_ref()) else {
+            return Vec::new();
+        };
+        
+        similar_games.iter()
+            .map(|similar| {
+                let owned = self.games.iter().find(|other| match igdb_data_of(*other) {
+                    Some(igdb_data) => igdb_data.id == similar.id,
+                    None => other.title.eq_ignore_ascii_case(&similar.name),
+                });
+                (similar.name.clone(), owned.map(|other| other.id.clone()))
+            })
+            .collect()
+    }
+    
+    /// Find the repository version a game was installed from
+    fn installed_game_version<'a>(&self, game: &'a GameInfo) -> Result<&'a GameVersion, String> {
+        let installed = self.installed_versions.get(&game.id)
+            .ok_or_else(|| "Installation marker is missing".to_string())?;
+        
+        game.versions.iter()
+            .find(|version| version.name == installed.version_name)
+            .ok_or_else(|| format!("Installed version {} is not in the repository", installed.version_name))
+    }
+    
+    /// Check the files of an installed game in the background, as stating them all can take a while
+    fn verify_files(&mut self, game: &GameInfo) {
+        let is_verifying_flag = self.is_verifying.clone();
+        if *is_verifying_flag.lock().unwrap() {
+            return;
+        }
+        
+        let version = match self.installed_game_version(game) {
+            Ok(version) => version.clone(),
+            Err(e) => {
+                *self.verify_result.lock().unwrap() = Some((game.id.clone(), vec![e]));
+                return;
+            }
+        };
+        
+        *is_verifying_flag.lock().unwrap() = true;
+        
+        let installer = Self::create_installer(&self.config);
+        let result_slot = self.verify_result.clone();
+        let game = game.clone();
+        
+        self.rt.spawn_blocking(move || {
+            let problems = installer.verify(&game, &version);
+            info!("Verified {}: {} problems", game.title, problems.len());
+            
+            *result_slot.lock().unwrap() = Some((game.id, problems));
+            *is_verifying_flag.lock().unwrap() = false;
         });
+    }
+    
+    /// Register games in the install directory that were installed without this application
+    fn scan_installed_games(&mut self) {
+        let scan = match self.installer.scan_install_dir(&self.games) {
+            Ok(scan) => scan,
+            Err(e) => {
+                error!("Failed to scan for installed games: {:#}", e);
+                self.install_scan_result = Some(Err(format!("Scan failed: {:#}", e)));
+                return;
+            }
+        };
+        
+        info!("Scan found {} installed games, {} folders unmatched", scan.matched.len(), scan.unmatched.len());
+        
+        // Games matched by title keep their folder, which the installer has to be told about
+        let elsewhere: Vec<&(String, String)> = scan.matched.iter()
+            .filter(|(game_id, folder)| game_id != folder)
+            .collect();
+        if !elsewhere.is_empty() {
+            for (game_id, folder) in elsewhere {
+                self.config.install.folders.insert(game_id.clone(), folder.clone());
+            }
+            if let Err(e) = self.config.save() {
+                error!("Failed to save configuration: {}", e);
+            }
+            self.installer = Self::create_installer(&self.config);
+        }
+        
+        self.refresh_installed_games();
+        self.install_scan_result = Some(Ok(scan));
+    }
+    
+    /// Cancel the running installation, leaving the rest of the queue to carry on
+    fn cancel_install(&mut self) {
+        if let Some(token) = &self.install_cancel_token {
+            if let Some(state) = &self.install_state {
+                info!("Cancelling installation of {}", state.game_title);
+            }
+            token.cancel();
+        }
+        if let Some(state) = &mut self.install_state {
+            state.message = "Cancelling...".to_string();
+        }
+    }
+    
+    /// Handle an action from the install panel
+    fn handle_install_panel_action(&mut self, action: InstallPanelAction) {
+        match action {
+            InstallPanelAction::Cancel => self.cancel_install(),
+            InstallPanelAction::ShowGame(game_id) => {
+                self.selected_game_id = Some(game_id.clone());
+                self.view = AppView::GameDetail(game_id);
+            }
+            InstallPanelAction::MoveUp(job_id) => {
+                if let Some(index) = self.install_jobs.iter().position(|job| job.id == job_id) {
+                    if index > 0 {
+                        self.install_jobs.swap(index, index - 1);
+                        self.save_install_jobs();
+                    }
+                }
+            }
+            InstallPanelAction::MoveDown(job_id) => {
+                if let Some(index) = self.install_jobs.iter().position(|job| job.id == job_id) {
+                    if index + 1 < self.install_jobs.len() {
+                        self.install_jobs.swap(index, index + 1);
+                        self.save_install_jobs();
+                    }
+                }
+            }
+            InstallPanelAction::RemoveJob(job_id) => {
+                self.install_jobs.retain(|job| job.id != job_id || job.status == JobStatus::Active);
+                self.save_install_jobs();
+            }
+            InstallPanelAction::ClearFinished => {
+                self.install_jobs.retain(|job| job.is_pending());
+                self.save_install_jobs();
+            }
+        }
+    }
+    
+    /// Render the uninstall confirmation dialog
+    fn render_uninstall_dialog(&mut self, ctx: &egui::Context) {
+        let Some(game) = self.pending_uninstall.clone() else {
+            return;
+        };
+        
+        let mut confirmed = false;
+        let mut cancelled = false;
+        
+        egui::Window::new("Confirm Uninstall")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!("Uninstall {}?", game.title));
+                ui.label(format!(
+                    "This will delete {}",
+                    self.installer.game_dir(&game).display()
+                ));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Yes").clicked() {
+                        confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+        
+        if confirmed {
+            self.pending_uninstall = None;
+            self.uninstall_game(&game);
+        } else if cancelled {
+            self.pending_uninstall = None;
+        }
+    }
+    
+    /// Start installing a game version in the background
+    fn start_install(&mut self, game: &GameInfo, version: GameVersion) {
+        if self.install_state.as_ref().map_or(false, |state| state.is_running) {
+            error!("An installation is already in progress");
+            return;
+        }
+        
+        info!("Installing game: {} (version: {})", game.title, version.name);
+        
+        let (tx, rx) = mpsc::channel(100);
+        self.install_status_receiver = Some(rx);
+        self.install_state = Some(InstallState {
+            game_id: game.id.clone(),
+            game_title: game.title.clone(),
+            is_running: true,
+            message: "Connecting to repository...".to_string(),
+            progress: None,
+            error: None,
+            ..Default::default()
+        });
+        
+        let cancel_token = CancellationToken::new();
+        self.install_cancel_token = Some(cancel_token.clone());
+        
+        let config = self.config.clone();
+        let game = game.clone();
+        
+        self.rt.spawn(async move {
+            if let Err(e) = Self::run_install(config, &game, &version, tx.clone(), cancel_token).await {
+                let _ = tx.send(InstallStatus::Failed { error: e.to_string() }).await;
+            }
+        });
+    }
+    
+    /// Connect to the repository and run the installer, reporting progress on `tx`
+    async fn run_install(
+        config: Config,
+        game: &GameInfo,
+        version: &GameVersion,
+        tx: mpsc::Sender<InstallStatus>,
+        cancel: CancellationToken,
+    ) -> anyhow::Result<()> {
+        let mut connection = SmbConnection::new(config.repository_for(&game.source).clone());
+        connection.connect().await?;
+        
+        // Forward download progress into the installation status channel
+        let (download_tx, mut download_rx) = mpsc::channel(100);
+        let forward_tx = tx.clone();
+        tokio::spawn(async move {
+            while let Some(status) = download_rx.recv().await {
+                if forward_tx.send(InstallStatus::Downloading(status)).await.is_err() {
+                    break;
+                }
+            }
+        });
+        
+        let mut downloader = Downloader::new(&config, Arc::new(connection));
+        downloader.set_progress_channel(download_tx);
+        
+        let mut installer = Installer::new(config, Arc::new(downloader));
+        installer.set_progress_channel(tx);
+        
+        installer.install_version(game, version, &cancel).await
+    }
+    
+    /// Ensure metadata handler is initialized
+    fn ensure_metadata_handler(&mut self) {
+        if self.metadata_handler.is_none() {
+            let mut handler = MetadataHandler::new(
+                self.config.igdb.clone(),
+                self.config.metadata.clone(),
+                self.config.paths.cache_dir.clone(),
+            ).expect("Failed to create metadata handler");
+            handler.set_offline(self.config.offline);
+            
+            self.metadata_handler = Some(handler);
+            
+            if let Some(handler) = &mut self.metadata_handler {
+                if let Some(tx) = &self.metadata_status_sender {
+                    handler.set_progress_channel(tx.clone());
+                }
+            }
+            
+            let handler_copy = self.metadata_handler.as_ref().unwrap().clone();
+            let handler_mutex = Arc::new(Mutex::new(handler_copy));
+            
+            self.rt.spawn(async move {
+                let mut handler = handler_mutex.lock().await;
+                if let Err(e) = handler.initialize().await {
+                    eprintln!("Failed to initialize metadata handler: {}", e);
+                }
+            });
+        }
+    }
+    
+    /// Handle game selection from library
+    fn handle_game_selection(&mut self, idx: usize) {
+        if let Some(game) = self.games.get(idx) {
+            self.selected_game_id = Some(game.id.clone());
+            self.view = AppView::GameDetail(game.id.clone());
+        }
+    }
+    
+    /// Handle library action
+    fn handle_library_action(&mut self, action: LibraryAction) {
+        match action {
+            LibraryAction::SelectGame(idx) => self.handle_game_selection(idx),
+            LibraryAction::RefreshAll => self.refresh_all_metadata(),
+            LibraryAction::RetryFailed => self.retry_failed_metadata(),
+            LibraryAction::ToggleFavorite(game_id) => self.toggle_favorite(&game_id),
+            LibraryAction::SetCoverWidth(width) => {
+                self.config.library.cover_width = width;
+                if let Err(e) = self.config.save() {
+                    error!("Failed to save cover size: {}", e);
+                }
+            }
+            LibraryAction::SetGroupBy(group_by) => {
+                self.config.library.group_by = group_by;
+                if let Err(e) = self.config.save() {
+                    error!("Failed to save library grouping: {}", e);
+                }
+            }
+            LibraryAction::RefreshGame(idx) => self.handle_library_game_action(idx, GameAction::RefreshMetadata),
+            LibraryAction::ClearMetadata(idx) => self.handle_library_game_action(idx, GameAction::ClearMetadata),
+            LibraryAction::InstallLatest(idx) => {
+                let Some(game) = self.games.get(idx).cloned() else {
+                    return;
+                };
+                
+                match game.latest_version() {
+                    Some(version) => self.enqueue_install(&game, version.clone()),
+                    None => error!("No versions available for {}", game.title),
+                }
+            }
+            LibraryAction::RefreshSelected(game_ids) => {
+                let game_pairs: Vec<(String, String, Option<u32>)> = self.games
+                    .iter()
+                    .filter(|game| game_ids.contains(&game.id))
+                    .map(|game| (game.id.clone(), game.title.clone(), game.igdb_id))
+                    .collect();
+                
+                self.start_batch_refresh(game_pairs);
+            }
+            LibraryAction::InstallSelected(game_ids) => {
+                let selected: Vec<GameInfo> = self.games
+                    .iter()
+                    .filter(|game| game_ids.contains(&game.id) && !self.installed_games.contains(&game.id))
+                    .cloned()
+                    .collect();
+                
+                for game in selected {
+                    match game.latest_version() {
+                        Some(version) => self.enqueue_install(&game, version.clone()),
+                        None => error!("No versions available for {}", game.title),
+                    }
+                }
+            }
+            LibraryAction::CancelRefresh => {
+                if let Some(token) = &self.batch_cancel_token {
+                    info!("Cancelling batch metadata refresh");
+                    token.cancel();
+                }
+            }
+        }
+    }
+    
+    /// Run a game action for the game at `idx` without opening its detail view
+    fn handle_library_game_action(&mut self, idx: usize, action: GameAction) {
+        if let Some(game) = self.games.get(idx).cloned() {
+            self.handle_game_action(action, &game.id, &game);
+        }
+    }
+    
+    /// Add a game to the favorites, or remove it if it already is one
+    fn toggle_favorite(&mut self, game_id: &str) {
+        let favorites = &mut self.config.library.favorites;
+        if !favorites.remove(game_id) {
+            favorites.insert(game_id.to_string());
+        }
+        
+        if let Err(e) = self.config.save() {
+            error!("Failed to save favorites: {}", e);
+        }
+        
+        self.library_view.set_favorites(self.config.library.favorites.clone());
+    }
+    
+    /// Refresh metadata for all games
+    fn refresh_all_metadata(&mut self) {
+        let game_pairs: Vec<(String, String, Option<u32>)> = self.games
+            .iter()
+            .map(|game| (game.id.clone(), game.title.clone(), game.igdb_id))
+            .collect();
+        
+        self.start_batch_refresh(game_pairs);
+    }
+    
+    /// Refresh metadata again for the games that failed in the last batch
+    fn retry_failed_metadata(&mut self) {
+        let game_pairs: Vec<(String, String, Option<u32>)> = self.batch_failed_games
+            .iter()
+            .map(|(game_id, game_name)| {
+                let igdb_id = self.games.iter().find(|game| game.id == *game_id).and_then(|game| game.igdb_id);
+                (game_id.clone(), game_name.clone(), igdb_id)
+            })
+            .collect();
+        
+        self.start_batch_refresh(game_pairs);
+    }
+    
+    /// Refresh metadata for a set of games in the background
+    fn start_batch_refresh(&mut self, game_pairs: Vec<(String, String, Option<u32>)>) {
+        if self.is_batch_refreshing {
+            return;
+        }
+        
+        self.ensure_metadata_handler();
+        self.is_batch_refreshing = true;
+        self.library_view.set_failed_refreshes(0);
+        
+        let cancel_token = CancellationToken::new();
+        self.batch_cancel_token = Some(cancel_token.clone());
+        
+        if let Some(handler) = &self.metadata_handler {
+            let handler_copy = handler.clone();
+            let handler_mutex = Arc::new(Mutex::new(handler_copy));
+            
+            self.rt.spawn(async move {
+                let mut handler = handler_mutex.lock().await;
+                if let Err(e) = handler.update_library_metadata(&game_pairs, &cancel_token).await {
+                    error!("Error in batch metadata update: {}", e);
+                }
+            });
+        }
+    }
+    
+    /// Handle game action
+    fn handle_game_action(&mut self, action: GameAction, game_id: &str, game: &GameInfo) {
+        match action {
+            GameAction::Back => self.view = AppView::Library,
+            GameAction::Install(version_idx, dlc) => {
+                match game.versions.get(version_idx) {
+                    Some(version) => {
+                        // DLC installers run after the version's installers and patches
+                        let mut version = version.clone();
+                        version.files.extend(dlc);
+                        self.enqueue_install(game, version);
+                    }
+                    None => error!("Version {} not found for {}", version_idx, game.title),
+                }
+            }
+            GameAction::CancelInstall => self.cancel_install(),
+            GameAction::SaveOverrides(overrides) => {
+                self.ensure_metadata_handler();
+                
+                if let Some(handler) = &mut self.metadata_handler {
+                    if let Err(e) = handler.set_user_overrides(game_id, overrides) {
+                        error!("Failed to save metadata for {}: {:#}", game.title, e);
+                        if let Some(detail_view) = &mut self.game_detail_view {
+                            detail_view.set_error(Some(format!("Failed to save metadata: {:#}", e)));
+                        }
+                    }
+                }
+            }
+            GameAction::ShowGame(other_id) => {
+                self.selected_game_id = Some(other_id.clone());
+                self.view = AppView::GameDetail(other_id);
+            }
+            GameAction::VerifyFiles => self.verify_files(game),
+            GameAction::Repair => match self.installed_game_version(game) {
+                Ok(version) => {
+                    let repair = self.installer.repair_version(game, version);
+                    self.enqueue_install(game, repair);
+                }
+                Err(e) => error!("Cannot repair {}: {}", game.title, e),
+            },
+            GameAction::Launch => {
+                match self.installer.launch(game) {
+                    Ok(child) => {
+                        self.refresh_installed_games();
+                        self.track_play_session(game, child);
+                    }
+                    Err(e) => {
+                        error!("Failed to launch {}: {}", game.title, e);
+                        self.install_state = Some(InstallState {
+                            game_id: game.id.clone(),
+                            is_running: false,
+                            message: String::new(),
+                            progress: None,
+                            error: Some(format!("Launch failed: {}", e)),
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+            GameAction::Uninstall => {
+                self.pending_uninstall = Some(game.clone());
+            }
+            GameAction::OverrideIgdbId(igdb_id) => {
+                self.ensure_metadata_handler();
+                
+                if let Some(handler) = &mut self.metadata_handler {
+                    if let Err(e) = handler.set_igdb_id_override(game_id, Some(igdb_id)) {
+                        error!("Failed to pin IGDB ID for {}: {}", game.title, e);
+                        if let Some(detail_view) = &mut self.game_detail_view {
+                            detail_view.set_error(Some(format!("Failed to pin IGDB ID: {}", e)));
+                        }
+                        return;
+                    }
+                }
+                
+                self.handle_game_action(GameAction::FetchMetadata, game_id, game);
+            }
+            GameAction::SetCover(path) => {
+                self.ensure_metadata_handler();
+                
+                let Some(handler) = &mut self.metadata_handler else {
+                    return;
+                };
+                
+                match handler.import_cover(game_id, &path) {
+                    Ok(()) => {
+                        if let Some(detail_view) = &mut self.game_detail_view {
+                            detail_view.reset_cover_texture();
+                            detail_view.set_error(None);
+                        }
+                        self.library_view.clear_texture_cache();
+                    }
+                    Err(e) => {
+                        error!("Failed to set cover for {}: {}", game.title, e);
+                        if let Some(detail_view) = &mut self.game_detail_view {
+                            detail_view.set_error(Some(format!("Failed to set cover: {:#}", e)));
+                        }
+                    }
+                }
+            }
+            GameAction::ClearMetadata => {
+                self.ensure_metadata_handler();
+                
+                let Some(handler) = &mut self.metadata_handler else {
+                    return;
+                };
+                
+                let error = match handler.clear_game_metadata(game_id) {
+                    Ok(()) => None,
+                    Err(e) => {
+                        error!("Failed to clear metadata for {}: {}", game.title, e);
+                        Some(format!("Failed to clear metadata: {}", e))
+                    }
+                };
+                
+                self.refresh_states.remove(game_id);
+                self.library_view.clear_texture_cache();
+                let detail_view = self.game_detail_view.as_mut().filter(|view| view.get_game_id() == game_id);
+                if let Some(detail_view) = detail_view {
+                    detail_view.reset_textures();
+                    detail_view.set_error(error);
+                }
+            }
+            GameAction::ToggleFavorite => self.toggle_favorite(game_id),
+            GameAction::ConfigureIgdb => self.open_igdb_settings(),
+            GameAction::FetchMetadata => self.refresh_game_metadata(game_id, game, false),
+            GameAction::RefreshMetadata => self.refresh_game_metadata(game_id, game, true),
+        }
+    }
+    
+    /// Fetch metadata for a single game in the background
+    fn refresh_game_metadata(&mut self, game_id: &str, game: &GameInfo, force: bool) {
+        self.ensure_metadata_handler();
+        
+        let game_id = game_id.to_string();
+        let game_name = game.title.clone();
+        let igdb_id = game.igdb_id;
         
-        ui.horizontal(|ui| {
-            ui.label("Username:");
-            ui.text_edit_singleline(&mut username);
-        });
+        let state = Arc::new(StdMutex::new(RefreshState {
+            game_id: game_id.clone(),
+            is_refreshing: true,
+            error: None,
+        }));
         
-        ui.horizontal(|ui| {
-            ui.label("Password:");
-            ui.text_edit_singleline(&mut password);
-        });
+        self.refresh_states.insert(game_id.clone(), state.clone());
         
-        ui.horizontal(|ui| {
-            ui.label("Base directory:");
-            ui.text_edit_singleline(&mut base_dir);
-        });
+        // The refresh may have been started from the library for another game
+        let detail_view = self.game_detail_view.as_mut().filter(|view| view.get_game_id() == game_id);
+        if let Some(detail_view) = detail_view {
+            detail_view.set_refresh_pending(true);
+            detail_view.set_error(None);
+        }
         
-        if ui.button("Save Repository Settings").clicked() {
-            self.config.repository.server = server;
-            self.config.repository.share = share;
-            self.config.repository.username = username;
-            self.config.repository.password = password;
-            self.config.repository.base_dir = base_dir;
+        if let Some(handler) = &self.metadata_handler {
+            let handler_copy = handler.clone();
+            let handler_mutex = Arc::new(Mutex::new(handler_copy));
             
-            if let Err(e) = self.config.save() {
-                error!("Failed to save configuration: {}", e);
-            }
+            let game_id_clone = game_id.clone();
+            let game_name_clone = game_name.clone();
+            let state_clone = state.clone();
             
-            self.smb_connection = Some(SmbConnection::new(self.config.repository.clone()));
+            self.rt.spawn(async move {
+                let mut handler = handler_mutex.lock().await;
+                let result = handler.refresh_metadata(&game_id_clone, &game_name_clone, igdb_id, force).await;
+                
+                let mut state = state_clone.lock().unwrap();
+                state.is_refreshing = false;
+                
+                if let Err(e) = result {
+                    state.error = Some(format!("{:#}", e));
+                }
+            });
+        }
+    }
+    
+    /// Delete all cached metadata and images
+    fn clear_metadata_cache(&mut self) {
+        self.ensure_metadata_handler();
+        
+        let Some(handler) = &mut self.metadata_handler else {
+            return;
+        };
+        
+        if let Err(e) = handler.clear_cache() {
+            error!("Failed to clear metadata cache: {}", e);
+        }
+        
+        self.refresh_states.clear();
+        self.library_view.clear_texture_cache();
+        if let Some(detail_view) = &mut self.game_detail_view {
+            detail_view.reset_textures();
+        }
+        
+        self.refresh_cache_stats();
+    }
+    
+    /// Measure the disk usage of the metadata cache
+    fn refresh_cache_stats(&mut self) {
+        self.ensure_metadata_handler();
+        
+        self.cache_stats = match self.metadata_handler.as_ref().map(|handler| handler.cache_stats()) {
+            Some(Ok(stats)) => Some(stats),
+            Some(Err(e)) => {
+                warn!("Failed to measure metadata cache: {}", e);
+                None
+            }
+            None => None,
+        };
+    }
+    
+    /// Check if any background operation is still reporting back to the UI
+    fn has_pending_work(&self) -> bool {
+        self.games_receiver.is_some()
+            || self.install_status_receiver.is_some()
+            || !self.running_games.lock().unwrap().is_empty()
+            || self.is_batch_refreshing
+            || *self.is_testing_connection.lock().unwrap()
+            || *self.is_validating_igdb.lock().unwrap()
+            || *self.is_picking_folder.lock().unwrap()
+            || *self.is_exporting_library.lock().unwrap()
+            || *self.is_verifying.lock().unwrap()
+            || self.install_panel.is_showing_install()
+            || self.refresh_states.values().any(|state| state.lock().unwrap().is_refreshing)
+    }
+    
+    /// Show the repository profile selector, which also picks the profiles that are listed
+    fn render_repository_selector(&mut self, ui: &mut egui::Ui) {
+        let mut active = self.config.active_repository;
+        let mut enabled: Vec<bool> = self.config.repositories.iter()
+            .map(|repository| repository.enabled)
+            .collect();
+        
+        egui::ComboBox::from_id_source("repository_profile")
+            .selected_text(&self.config.repository().name)
+            .show_ui(ui, |ui| {
+                for (i, repository) in self.config.repositories.iter().enumerate() {
+                    ui.selectable_value(&mut active, i, &repository.name);
+                }
+                
+                ui.separator();
+                
+                for (i, repository) in self.config.repositories.iter().enumerate() {
+                    ui.checkbox(&mut enabled[i], format!("List {}", repository.name));
+                }
+            });
+        ui.label("Repository:");
+        
+        let enabled_changed = self.config.repositories.iter()
+            .zip(&enabled)
+            .any(|(repository, enabled)| repository.enabled != *enabled);
+        
+        if active == self.config.active_repository && !enabled_changed {
+            return;
+        }
+        
+        self.config.active_repository = active;
+        for (repository, enabled) in self.config.repositories.iter_mut().zip(enabled) {
+            repository.enabled = enabled;
+        }
+        
+        if let Err(e) = self.config.save() {
+            error!("Failed to save repository selection: {}", e);
+        }
+        
+        self.smb_connection = Some(SmbConnection::new(self.config.repository().clone()));
+        if enabled_changed {
+            self.library_view.set_show_sources(self.config.enabled_repositories().len() > 1);
             self.connect_to_repository();
         }
+    }
+    
+    /// Handle an action from the settings view
+    fn handle_settings_action(&mut self, action: SettingsAction) {
+        match action {
+            SettingsAction::Save(config) => match self.apply_settings(config) {
+                Ok(()) => self.close_settings(),
+                Err(e) => {
+                    if let Some(settings_view) = &mut self.settings_view {
+                        if let Some((setting, path)) = self.picked_folder.lock().unwrap().take() {
+                            settings_view.set_picked_folder(setting, path);
+                        }
+                        settings_view.set_save_error(Some(e));
+                    }
+                }
+            },
+            SettingsAction::Cancel => self.close_settings(),
+            SettingsAction::PickFolder(setting, current) => self.pick_folder(setting, current),
+            SettingsAction::TestConnection(repository) => self.test_repository_connection(repository),
+            SettingsAction::TestIgdb => self.test_igdb_connection(),
+            SettingsAction::ValidateIgdb(igdb) => self.validate_igdb_credentials(igdb),
+            SettingsAction::ClearCache => self.clear_metadata_cache(),
+            SettingsAction::ExportMetadata(path, include_covers) => self.export_metadata(&path, include_covers),
+            SettingsAction::ImportMetadata(path) => self.import_metadata(&path),
+            SettingsAction::OpenLogFolder => {
+                let log_dir = logging::log_dir(&self.config.paths.cache_dir);
+                if let Err(e) = open::that(&log_dir) {
+                    error!("Failed to open log folder {}: {}", log_dir.display(), e);
+                }
+            }
+        }
+    }
+    
+    /// Open the settings view on a copy of the current configuration
+    fn open_settings(&mut self) {
+        self.settings_view = Some(SettingsView::new(self.config.clone()));
+        *self.connection_test_result.lock().unwrap() = None;
+        *self.igdb_validation_result.lock().unwrap() = None;
+        *self.picked_folder.lock().unwrap() = None;
+        self.metadata_transfer_result = None;
+        self.view = AppView::Settings;
+        self.refresh_cache_stats();
+    }
+    
+    /// Open the settings view on the IGDB credentials
+    fn open_igdb_settings(&mut self) {
+        self.open_settings();
+        if let Some(settings_view) = &mut self.settings_view {
+            settings_view.show_igdb_tab();
+        }
+    }
+    
+    /// Leave the settings view
+    fn close_settings(&mut self) {
+        self.settings_view = None;
+        self.view = AppView::Library;
+    }
+    
+    /// Persist an edited configuration and restart whatever depends on the changed parts
+    ///
+    /// Nothing is applied if one of the configured directories cannot be written to. If a
+    /// password could not go to the keyring, everything is applied and the error tells the
+    /// user it was written to the configuration file instead.
+    fn apply_settings(&mut self, mut config: Config) -> Result<(), String> {
+        // The library settings and window may have changed while the settings view was open
+        config.library = self.config.library.clone();
+        config.window = self.config.window.clone();
         
-        ui.separator();
+        let repository_changed = config.repositories != self.config.repositories
+            || config.offline != self.config.offline;
+        let logging_changed = config.logging != self.config.logging;
+        let paths_changed = config.paths != self.config.paths;
+        let metadata_changed = paths_changed
+            || config.igdb != self.config.igdb
+            || config.metadata != self.config.metadata
+            || config.offline != self.config.offline;
         
-        ui.heading("Path Settings");
+        if paths_changed {
+            if let Err(e) = config.validate_directories() {
+                error!("Invalid path settings: {:#}", e);
+                return Err(format!("{:#}", e));
+            }
+        }
         
-        let install_dir = self.config.paths.install_dir.clone();
-        let cache_dir = self.config.paths.cache_dir.clone();
-        let temp_dir = self.config.paths.temp_dir.clone();
+        if config.igdb != self.config.igdb {
+            if let Err(e) = build_http_client(&config.igdb) {
+                error!("Invalid IGDB network settings: {:#}", e);
+                return Err(format!("{:#}", e));
+            }
+        }
         
-        ui.horizontal(|ui| {
-            ui.label("Install directory:");
-            ui.text_edit_singleline(&mut install_dir.to_string_lossy().to_string());
-        });
+        self.config = config;
         
-        ui.horizontal(|ui| {
-            ui.label("Cache directory:");
-            ui.text_edit_singleline(&mut cache_dir.to_string_lossy().to_string());
-        });
+        let keyring_failures = match self.config.save() {
+            Ok(failures) => failures,
+            Err(e) => {
+                error!("Failed to save configuration: {}", e);
+                Vec::new()
+            }
+        };
         
-        ui.horizontal(|ui| {
-            ui.label("Temp directory:");
-            ui.text_edit_singleline(&mut temp_dir.to_string_lossy().to_string());
-        });
+        if paths_changed || repository_changed {
+            self.installer = Self::create_installer(&self.config);
+            self.refresh_installed_games();
+            self.free_space_checked_at = None;
+        }
+        
+        if paths_changed || logging_changed {
+            let log_dir = logging::log_dir(&self.config.paths.cache_dir);
+            if let Err(e) = logging::start_file_logging(&self.config.logging, &log_dir) {
+                warn!("Failed to restart file logging: {:#}", e);
+            }
+        }
         
-        if ui.button("Save Path Settings").clicked() {
-            // TODO: Update path settings
+        if paths_changed {
+            // Cached covers and first-seen times belong to the old cache directory
+            self.library_view.clear_texture_cache();
+            if let Some(detail_view) = &mut self.game_detail_view {
+                detail_view.reset_textures();
+            }
+            self.load_first_seen();
         }
         
-        ui.separator();
+        if metadata_changed {
+            self.metadata_handler = None;
+            self.ensure_metadata_handler();
+        }
         
-        ui.heading("IGDB API Settings");
-        ui.separator();
+        if repository_changed {
+            self.library_view.set_show_sources(self.config.enabled_repositories().len() > 1);
+            self.smb_connection = Some(SmbConnection::new(self.config.repository().clone()));
+            self.connect_to_repository();
+        }
         
-        let mut client_id = self.config.igdb.client_id.clone();
-        let mut client_secret = self.config.igdb.client_secret.clone();
+        if !keyring_failures.is_empty() {
+            return Err(format!(
+                "Settings saved, but the keyring did not accept the password, so it was written to the configuration file: {}",
+                keyring_failures.join("; ")
+            ));
+        }
         
-        ui.horizontal(|ui| {
-            ui.label("Client ID:");
-            ui.text_edit_singleline(&mut client_id);
-        });
+        Ok(())
+    }
+    
+    /// Switch offline mode on or off and reload the library accordingly
+    fn set_offline(&mut self, offline: bool) {
+        info!("Offline mode {}", if offline { "enabled" } else { "disabled" });
+        self.config.offline = offline;
+        
+        if let Err(e) = self.config.save() {
+            error!("Failed to save configuration: {}", e);
+        }
+        
+        if let Some(handler) = &mut self.metadata_handler {
+            handler.set_offline(offline);
+        }
+        
+        self.connect_to_repository();
+        self.start_next_queued_install();
+    }
+    
+    /// Open the native folder picker without blocking the frame
+    fn pick_folder(&mut self, setting: PathSetting, current: std::path::PathBuf) {
+        let is_picking_flag = self.is_picking_folder.clone();
+        if *is_picking_flag.lock().unwrap() {
+            return;
+        }
+        *is_picking_flag.lock().unwrap() = true;
+        
+        let result_slot = self.picked_folder.clone();
         
-        ui.horizontal(|ui| {
-            ui.label("Client Secret:");
-            ui.text_edit_singleline(&mut client_secret);
+        self.rt.spawn(async move {
+            let mut dialog = rfd::AsyncFileDialog::new();
+            if current.is_dir() {
+                dialog = dialog.set_directory(&current);
+            }
+            
+            if let Some(folder) = dialog.pick_folder().await {
+                *result_slot.lock().unwrap() = Some((setting, folder.path().to_path_buf()));
+            }
+            *is_picking_flag.lock().unwrap() = false;
         });
+    }
+    
+    /// Ask for a file and write the library to it as CSV or JSON, depending on the extension
+    fn export_library(&mut self) {
+        let is_exporting_flag = self.is_exporting_library.clone();
+        if *is_exporting_flag.lock().unwrap() {
+            return;
+        }
+        *is_exporting_flag.lock().unwrap() = true;
         
-        if ui.button("Save IGDB Settings").clicked() {
-            self.config.igdb.client_id = client_id;
-            self.config.igdb.client_secret = client_secret;
+        // Snapshot the library now so the export matches what is on screen
+        let rows = library_export::library_rows(&self.games, &self.installed_games, self.metadata_handler.as_ref());
+        let result_slot = self.library_export_result.clone();
+        
+        self.rt.spawn(async move {
+            let file = rfd::AsyncFileDialog::new()
+                .set_file_name("library.csv")
+                .add_filter("CSV", &["csv"])
+                .add_filter("JSON", &["json"])
+                .save_file()
+                .await;
             
-            if let Err(e) = self.config.save() {
-                error!("Failed to save configuration: {}", e);
+            if let Some(file) = file {
+                let path = file.path().to_path_buf();
+                let result = tokio::task::spawn_blocking(move || {
+                    library_export::write_library(&path, &rows).map(|_| (rows.len(), path))
+                }).await;
+                
+                *result_slot.lock().unwrap() = Some(match result {
+                    Ok(Ok((count, path))) => {
+                        info!("Exported {} games to {}", count, path.display());
+                        Ok(format!("Exported {} games to {}", count, path.display()))
+                    }
+                    Ok(Err(e)) => {
+                        error!("Failed to export library: {:#}", e);
+                        Err(format!("Library export failed: {:#}", e))
+                    }
+                    Err(e) => Err(format!("Library export failed: {}", e)),
+                });
             }
+            *is_exporting_flag.lock().unwrap() = false;
+        });
+    }
+    
+    /// List the repository with the given settings and report the result to the settings view
+    fn test_repository_connection(&mut self, repository: RepositoryConfig) {
+        let connection = SmbConnection::new(repository);
+        
+        let result_slot = self.connection_test_result.clone();
+        let is_testing_flag = self.is_testing_connection.clone();
+        *is_testing_flag.lock().unwrap() = true;
+        
+        self.rt.spawn(async move {
+            let result = match connection.test().await {
+                Ok(count) => {
+                    info!("Repository test successful: found {} directories", count);
+                    Ok(count)
+                }
+                Err(e) => {
+                    error!("Repository test failed: {}", e);
+                    Err(e.to_string())
+                }
+            };
             
-            self.metadata_handler = None;
+            *result_slot.lock().unwrap() = Some(result);
+            *is_testing_flag.lock().unwrap() = false;
+        });
+    }
+    
+    /// Authenticate with the given IGDB credentials and report the result to the settings view
+    fn validate_igdb_credentials(&mut self, igdb: IgdbConfig) {
+        // Report a bad proxy here, the client itself would fall back to a direct connection
+        if let Err(e) = build_http_client(&igdb) {
+            *self.igdb_validation_result.lock().unwrap() = Some(Err(format!("{:#}", e)));
+            return;
         }
         
-        ui.separator();
+        // A fresh client has no cached token, so this always performs the handshake
+        let mut client = IgdbClient::new(igdb).with_offline(self.config.offline);
         
-        if ui.button("Test IGDB Connection").clicked() {
-            self.ensure_metadata_handler();
+        let result_slot = self.igdb_validation_result.clone();
+        let is_validating_flag = self.is_validating_igdb.clone();
+        *is_validating_flag.lock().unwrap() = true;
+        
+        self.rt.spawn(async move {
+            let result = match client.authenticate().await {
+                Ok(()) => {
+                    info!("IGDB credentials are valid");
+                    Ok(())
+                }
+                Err(e) => {
+                    error!("IGDB credential check failed: {:#}", e);
+                    Err(format!("{:#}", e))
+                }
+            };
             
-            if let Some(handler) = &self.metadata_handler {
-                let handler_copy = handler.clone();
-                let handler_mutex = Arc::new(Mutex::new(handler_copy));
-                
-                self.rt.spawn(async move {
-                    let mut handler = handler_mutex.lock().await;
-                    match handler.search_game("The Witcher 3").await {
-                        Ok(games) => {
-                            info!("IGDB test successful: found {} games", games.len());
-                            for game in games {
-                                info!("  - {} (ID: {})", game.name, game.id);
-                            }
-                        }
-                        Err(e) => {
-                            error!("IGDB test failed: {}", e);
+            *result_slot.lock().unwrap() = Some(result);
+            *is_validating_flag.lock().unwrap() = false;
+        });
+    }
+    
+    /// Run a sample IGDB search with the saved credentials and log the result
+    fn test_igdb_connection(&mut self) {
+        self.ensure_metadata_handler();
+        
+        if let Some(handler) = &self.metadata_handler {
+            let handler_copy = handler.clone();
+            let handler_mutex = Arc::new(Mutex::new(handler_copy));
+            
+            self.rt.spawn(async move {
+                let mut handler = handler_mutex.lock().await;
+                match handler.search_game("The Witcher 3").await {
+                    Ok(games) => {
+                        info!("IGDB test successful: found {} games", games.len());
+                        for game in games {
+                            info!("  - {} (ID: {})", game.name, game.id);
                         }
                     }
-                });
+                    Err(e) => {
+                        error!("IGDB test failed: {}", e);
+                    }
+                }
+            });
+        }
+    }
+    
+    /// Set the visuals for the configured theme whenever it or the OS preference changes
+    fn apply_theme(&mut self, ctx: &egui::Context, system_theme: Option<eframe::Theme>) {
+        let theme = (self.config.theme, system_theme);
+        if self.applied_theme == Some(theme) {
+            return;
+        }
+        
+        let visuals = match self.config.theme {
+            Theme::System => system_theme.unwrap_or(eframe::Theme::Dark).egui_visuals(),
+            Theme::Light => egui::Visuals::light(),
+            Theme::Dark => egui::Visuals::dark(),
+        };
+        
+        ctx.set_visuals(visuals);
+        self.applied_theme = Some(theme);
+    }
+    
+    /// Remember the window geometry so it can be saved on exit
+    fn track_window_geometry(&mut self, window_info: &eframe::WindowInfo) {
+        self.window_focused = window_info.focused;
+        
+        let window = &mut self.config.window;
+        window.maximized = window_info.maximized;
+        
+        // Keep the restored size and position while maximized or minimized
+        if window_info.maximized || window_info.minimized || window_info.fullscreen {
+            return;
+        }
+        
+        window.width = window_info.size.x;
+        window.height = window_info.size.y;
+        if let Some(position) = window_info.position {
+            window.position = Some((position.x, position.y));
+        }
+    }
+    
+    /// Write all cached metadata to the export file
+    fn export_metadata(&mut self, path: &std::path::Path, include_covers: bool) {
+        self.ensure_metadata_handler();
+        
+        let Some(handler) = &self.metadata_handler else {
+            return;
+        };
+        
+        self.metadata_transfer_result = Some(match handler.export_metadata(path, include_covers) {
+            Ok(count) => Ok(format!("Exported metadata for {} games to {}", count, path.display())),
+            Err(e) => {
+                error!("Failed to export metadata: {:#}", e);
+                Err(format!("Export failed: {:#}", e))
+            }
+        });
+    }
+    
+    /// Restore cached metadata from the export file
+    fn import_metadata(&mut self, path: &std::path::Path) {
+        self.ensure_metadata_handler();
+        
+        let Some(handler) = &mut self.metadata_handler else {
+            return;
+        };
+        
+        self.metadata_transfer_result = Some(match handler.import_metadata(path) {
+            Ok(count) => Ok(format!("Imported metadata for {} games", count)),
+            Err(e) => {
+                error!("Failed to import metadata: {:#}", e);
+                Err(format!("Import failed: {:#}", e))
             }
+        });
+        
+        self.library_view.clear_texture_cache();
+        if let Some(detail_view) = &mut self.game_detail_view {
+            detail_view.reset_textures();
         }
+        self.refresh_cache_stats();
     }
 }
 
 impl eframe::App for GameLibraryApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.apply_theme(ctx, frame.info().system_theme);
+        self.track_window_geometry(&frame.info().window_info);
         self.check_repository_results();
         self.check_metadata_status();
+        self.check_install_status();
+        self.check_play_sessions();
+        self.refresh_free_space();
         
         let mut game_action = None;
         let mut action_game_id = None;
         let mut action_game = None;
         let mut library_action = None;
+        let mut settings_action = None;
+        let mut install_panel_action = None;
+        
+        self.install_panel.set_state(self.install_state.clone());
+        self.install_panel.set_jobs(self.install_jobs.clone());
+        self.install_panel.set_paused(self.config.offline);
+        if self.install_panel.is_visible() {
+            egui::TopBottomPanel::bottom("install_panel").show(ctx, |ui| {
+                self.install_panel.show(ui, |action| {
+                    install_panel_action = Some(action);
+                });
+            });
+        }
 
         egui::CentralPanel::default().show(ctx, |ui| {
             match self.view {
@@ -563,17 +2141,66 @@ impl eframe::App for GameLibraryApp {
                         
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                             if ui.button("Settings").clicked() {
-                                self.view = AppView::Settings;
+                                self.open_settings();
                             }
                             
                             if ui.button("Refresh").clicked() {
                                 self.connect_to_repository();
                             }
+                            
+                            if let Some(available) = self.install_free_space {
+                                ui.label(egui::RichText::new(format!("{} free", helpers::format_bytes(available))).weak())
+                                    .on_hover_text(format!("Free space on {}", self.config.paths.install_dir.display()));
+                            }
+                            
+                            let mut offline = self.config.offline;
+                            if ui.toggle_value(&mut offline, "Offline")
+                                .on_hover_text("Browse the cached library without contacting the repository or IGDB")
+                                .changed()
+                            {
+                                self.set_offline(offline);
+                            }
+                            
+                            if ui.button("Scan installed games")
+                                .on_hover_text(format!("Register games already installed in {}", self.config.paths.install_dir.display()))
+                                .clicked()
+                            {
+                                self.scan_installed_games();
+                            }
+                            
+                            let exporting = *self.is_exporting_library.lock().unwrap();
+                            if ui.add_enabled(!exporting, egui::Button::new("Export library")).clicked() {
+                                self.export_library();
+                            }
+                            
+                            if self.config.repositories.len() > 1 {
+                                self.render_repository_selector(ui);
+                            }
                         });
                     });
                     
                     ui.separator();
                     
+                    if self.config.offline {
+                        ui.label(egui::RichText::new("Offline mode: showing the cached library, nothing is fetched or installed")
+                            .color(egui::Color32::YELLOW));
+                        ui.separator();
+                    }
+                    
+                    let igdb_configured = self.metadata_handler
+                        .as_ref()
+                        .map_or(true, |handler| handler.is_igdb_configured());
+                    if !igdb_configured && !self.config.offline {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("IGDB is not configured, so no covers or metadata can be fetched.")
+                                .color(egui::Color32::YELLOW));
+                            if ui.button("Configure IGDB").clicked() {
+                                self.open_igdb_settings();
+                            }
+                        });
+                        ui.separator();
+                    }
+                    
                     if self.is_connecting {
                         ui.horizontal(|ui| {
                             ui.spinner();
@@ -582,6 +2209,55 @@ impl eframe::App for GameLibraryApp {
                         ui.separator();
                     }
                     
+                    if let Some(error) = &self.connection_error {
+                        ui.label(egui::RichText::new(error).color(egui::Color32::RED));
+                        ui.separator();
+                    }
+                    
+                    let export_result = self.library_export_result.lock().unwrap().clone();
+                    if let Some(result) = export_result {
+                        ui.horizontal(|ui| {
+                            match result {
+                                Ok(message) => ui.label(egui::RichText::new(message).color(egui::Color32::GREEN)),
+                                Err(error) => ui.label(egui::RichText::new(error).color(egui::Color32::RED)),
+                            };
+                            if ui.small_button("Dismiss").clicked() {
+                                *self.library_export_result.lock().unwrap() = None;
+                            }
+                        });
+                        ui.separator();
+                    }
+                    
+                    if let Some(result) = &self.install_scan_result {
+                        let mut dismissed = false;
+                        ui.horizontal(|ui| {
+                            match result {
+                                Ok(scan) => ui.label(egui::RichText::new(format!(
+                                    "Found {} installed games, {} folders unmatched",
+                                    scan.matched.len(),
+                                    scan.unmatched.len()
+                                )).color(egui::Color32::GREEN)),
+                                Err(error) => ui.label(egui::RichText::new(error).color(egui::Color32::RED)),
+                            };
+                            dismissed = ui.small_button("Dismiss").clicked();
+                        });
+                        if let Ok(scan) = result {
+                            if !scan.unmatched.is_empty() {
+                                egui::CollapsingHeader::new("Unmatched folders")
+                                    .id_source("unmatched_folders")
+                                    .show(ui, |ui| {
+                                        for folder in &scan.unmatched {
+                                            ui.label(folder);
+                                        }
+                                    });
+                            }
+                        }
+                        if dismissed {
+                            self.install_scan_result = None;
+                        }
+                        ui.separator();
+                    }
+                    
                     if self.is_batch_refreshing {
                         ui.horizontal(|ui| {
                             ui.spinner();
@@ -590,16 +2266,32 @@ impl eframe::App for GameLibraryApp {
                             } else {
                                 ui.label("Refreshing all metadata...");
                             }
+                            
+                            let cancelling = self.batch_cancel_token
+                                .as_ref()
+                                .map_or(false, |token| token.is_cancelled());
+                            if cancelling {
+                                ui.label("Cancelling...");
+                            } else if ui.button("Cancel").clicked() {
+                                library_action = Some(LibraryAction::CancelRefresh);
+                            }
                         });
                         ui.separator();
                     }
                     
-                    ui.label(format!("Found {} games", self.games.len()));
+                    let shown = self.library_view
+                        .filtered_games(&self.games, &self.installed_games, self.metadata_handler.as_ref())
+                        .len();
+                    if shown == self.games.len() {
+                        ui.label(format!("Found {} games", self.games.len()));
+                    } else {
+                        ui.label(format!("Showing {} of {} games", shown, self.games.len()));
+                    }
                     ui.separator();
                     
                     let lib_action = {
                         let mut action = None;
-                        self.library_view.show(ui, &self.games, self.metadata_handler.as_ref(), |a| {
+                        self.library_view.show(ui, &self.games, &self.installed_games, self.metadata_handler.as_ref(), |a| {
                             action = Some(a);
                         });
                         action
@@ -613,10 +2305,12 @@ impl eframe::App for GameLibraryApp {
                     let game = self.games.iter().find(|g| g.id == *game_id).cloned();
                     
                     if let Some(game) = game {
-                        let is_installed = false; // TODO: Check if installed
+                        let is_installed = self.installed_games.contains(game_id);
+                        let series_games = self.series_games(&game);
+                        let similar_games = self.similar_games(&game);
                         
                         if self.game_detail_view.is_none() {
-                            self.game_detail_view = Some(GameDetailView::new(game_id.to_string()));
+                            self.game_detail_view = Some(GameDetailView::new(game_id.to_string(), self.image_loader.clone()));
                         }
                         
                         if let Some(detail_view) = &mut self.game_detail_view {
@@ -629,6 +2323,26 @@ impl eframe::App for GameLibraryApp {
                                 detail_view.set_refresh_pending(state.is_refreshing);
                                 detail_view.set_error(state.error.clone());
                             }
+                            
+                            let install_state = self.install_state.as_ref()
+                                .filter(|state| state.game_id == *game_id)
+                                .cloned();
+                            detail_view.set_install_state(install_state);
+                            detail_view.set_installed_version(self.installed_versions.get(game_id).cloned());
+                            detail_view.set_running(self.running_games.lock().unwrap().contains(game_id));
+                            detail_view.set_favorite(self.config.library.favorites.contains(game_id));
+                            detail_view.set_free_space(self.install_free_space);
+                            detail_view.set_series_games(series_games);
+                            detail_view.set_similar_games(similar_games);
+                            detail_view.set_verifying(*self.is_verifying.lock().unwrap());
+                            
+                            // A result for another game waits until that game is shown again
+                            let mut verify_result = self.verify_result.lock().unwrap();
+                            if verify_result.as_ref().map_or(false, |(verified_id, _)| verified_id == game_id) {
+                                if let Some((_, problems)) = verify_result.take() {
+                                    detail_view.set_verify_result(Some(problems));
+                                }
+                            }
                         }
                         
                         if let Some(detail_view) = &mut self.game_detail_view {
@@ -656,7 +2370,23 @@ impl eframe::App for GameLibraryApp {
                     }
                 }
                 AppView::Settings => {
-                    self.render_settings(ui);
+                    if let Some(settings_view) = &mut self.settings_view {
+                        settings_view.set_connection_test(
+                            *self.is_testing_connection.lock().unwrap(),
+                            self.connection_test_result.lock().unwrap().clone(),
+                        );
+                        settings_view.set_igdb_validation(
+                            *self.is_validating_igdb.lock().unwrap(),
+                            self.igdb_validation_result.lock().unwrap().clone(),
+                        );
+                        settings_view.set_cache_stats(self.cache_stats);
+                        settings_view.set_refreshing(self.is_batch_refreshing);
+                        settings_view.set_transfer_result(self.metadata_transfer_result.clone());
+                        
+                        settings_view.show(ui, |action| {
+                            settings_action = Some(action);
+                        });
+                    }
                 }
             }
         });
@@ -665,10 +2395,29 @@ impl eframe::App for GameLibraryApp {
             self.handle_library_action(action);
         }
         
+        if let Some(action) = settings_action {
+            self.handle_settings_action(action);
+        }
+        
+        if let Some(action) = install_panel_action {
+            self.handle_install_panel_action(action);
+        }
+        
         if let (Some(action), Some(game_id), Some(game)) = (game_action, action_game_id, action_game) {
             self.handle_game_action(action, &game_id, &game);
         }
         
-        ctx.request_repaint();
+        self.render_uninstall_dialog(ctx);
+        
+        // Keep polling background work; otherwise egui only redraws on input
+        if self.has_pending_work() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+    }
+    
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Err(e) = self.config.save() {
+            error!("Failed to save window geometry: {}", e);
+        }
     }
 }
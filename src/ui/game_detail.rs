@@ -1,22 +1,57 @@
 use eframe::egui;
 use egui::{Align, Layout, RichText, Ui};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::fs;
 
-use crate::repository::GameInfo;
+use crate::repository::{GameFile, GameInfo};
 use crate::metadata::handler::MetadataHandler;
+use crate::metadata::UserOverrides;
+use crate::installer::InstalledVersion;
+use crate::ui::app::InstallState;
 use crate::ui::helpers; // Using our shared image-loading helper
+use crate::ui::image_loader::ImageLoader;
+
+/// Size of a screenshot thumbnail in the gallery
+const SCREENSHOT_SIZE: egui::Vec2 = egui::vec2(256.0, 144.0);
+
+/// Height of the title banner
+const BANNER_HEIGHT: f32 = 180.0;
 
 /// Game action
 pub enum GameAction {
     /// Go back to library
     Back,
-    /// Install game with version index
-    Install(usize),
+    /// Install game with version index, along with the chosen DLC
+    Install(usize, Vec<GameFile>),
+    /// Cancel the running installation
+    CancelInstall,
+    /// Launch the installed game
+    Launch,
     /// Uninstall game
     Uninstall,
-    /// Fetch or refresh metadata
+    /// Fetch missing or stale metadata
     FetchMetadata,
+    /// Re-fetch metadata and cover even if the cached copy is fresh
+    RefreshMetadata,
+    /// Pin an IGDB ID for this game and re-fetch its metadata
+    OverrideIgdbId(u32),
+    /// Replace the cover with an image file
+    SetCover(PathBuf),
+    /// Delete the cached metadata and images for this game
+    ClearMetadata,
+    /// Add or remove the game from the favorites
+    ToggleFavorite,
+    /// Open the settings to enter IGDB credentials
+    ConfigureIgdb,
+    /// Check the installed files against the installation marker
+    VerifyFiles,
+    /// Install again what verification found missing
+    Repair,
+    /// Open the detail view of another game, by ID
+    ShowGame(String),
+    /// Replace the metadata values entered by the user
+    SaveOverrides(UserOverrides),
 }
 
 /// Game detail view
@@ -29,19 +64,105 @@ pub struct GameDetailView {
     refresh_pending: bool,
     /// Error message
     error_message: Option<String>,
-    /// Cached cover texture
-    cover_texture: Option<egui::TextureHandle>,
+    /// Background decoder for cover, hero and screenshot images
+    images: ImageLoader,
+    /// Cached cover texture, `None` until it has been decoded
+    cover_texture: Option<Option<egui::TextureHandle>>,
+    /// Cached hero artwork texture, `None` until it has been decoded
+    hero_texture: Option<Option<egui::TextureHandle>>,
+    /// Screenshot textures by index, loaded once they scroll into view
+    screenshot_textures: HashMap<usize, Option<egui::TextureHandle>>,
+    /// Remote paths of the DLC chosen to install with the game
+    selected_dlc: HashSet<String>,
+    /// Text of the IGDB ID override box
+    igdb_id_input: String,
+    /// Installation in progress for this game
+    install_state: Option<InstallState>,
+    /// Installed version of this game, if any
+    installed_version: Option<InstalledVersion>,
+    /// Whether the game is currently running
+    is_running: bool,
+    /// Whether the game is a favorite
+    is_favorite: bool,
+    /// Free space on the install drive, if it could be measured
+    free_space: Option<u64>,
+    /// Problems found by the last file verification, empty if none
+    verify_result: Option<Vec<String>>,
+    /// Whether file verification is running
+    is_verifying: bool,
+    /// Other library games in the same series, as (game ID, title)
+    series_games: Vec<(String, String)>,
+    /// Games IGDB lists as similar, as (title, library game ID if owned)
+    similar_games: Vec<(String, Option<String>)>,
+    /// Metadata editor, while it is open
+    metadata_form: Option<MetadataForm>,
+}
+
+/// Text of the metadata editor fields, empty where the fetched value is used
+#[derive(Default)]
+struct MetadataForm {
+    title: String,
+    developer: String,
+    publisher: String,
+    release_date: String,
+    description: String,
+}
+
+impl MetadataForm {
+    /// Fill the fields with the values the user entered before
+    fn from_overrides(overrides: &UserOverrides) -> Self {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        
+        Self {
+            title: text(&overrides.title),
+            developer: text(&overrides.developer),
+            publisher: text(&overrides.publisher),
+            release_date: text(&overrides.release_date),
+            description: text(&overrides.description),
+        }
+    }
+    
+    /// Get the entered values, leaving out empty fields
+    fn to_overrides(&self) -> UserOverrides {
+        let value = |text: &str| {
+            let text = text.trim();
+            (!text.is_empty()).then(|| text.to_string())
+        };
+        
+        UserOverrides {
+            title: value(&self.title),
+            developer: value(&self.developer),
+            publisher: value(&self.publisher),
+            release_date: value(&self.release_date),
+            description: value(&self.description),
+        }
+    }
 }
 
 impl GameDetailView {
     /// Create a new game detail view
-    pub fn new(game_id: String) -> Self {
+    pub fn new(game_id: String, images: ImageLoader) -> Self {
         Self {
             selected_version: 0,
             game_id,
             refresh_pending: false,
             error_message: None,
+            images,
             cover_texture: None,
+            hero_texture: None,
+            screenshot_textures: HashMap::new(),
+            selected_dlc: HashSet::new(),
+            igdb_id_input: String::new(),
+            install_state: None,
+            installed_version: None,
+            is_running: false,
+            is_favorite: false,
+            free_space: None,
+            verify_result: None,
+            is_verifying: false,
+            series_games: Vec::new(),
+            similar_games: Vec::new(),
+            metadata_form: None,
         }
     }
     
@@ -50,6 +171,12 @@ impl GameDetailView {
         self.game_id = game_id;
         self.error_message = None;
         self.cover_texture = None; // Reset texture when game changes
+        self.hero_texture = None;
+        self.screenshot_textures.clear();
+        self.selected_dlc.clear();
+        self.igdb_id_input.clear();
+        self.verify_result = None;
+        self.metadata_form = None;
     }
 
     /// Get the current game ID
@@ -57,8 +184,24 @@ impl GameDetailView {
         &self.game_id
     }
 
+    /// Drop the cached cover texture so it is reloaded from disk
+    pub fn reset_cover_texture(&mut self) {
+        self.cover_texture = None;
+    }
+    
+    /// Drop all cached image textures so they are reloaded from disk
+    pub fn reset_textures(&mut self) {
+        self.cover_texture = None;
+        self.hero_texture = None;
+        self.screenshot_textures.clear();
+    }
+    
     /// Set refresh pending state
     pub fn set_refresh_pending(&mut self, pending: bool) {
+        // A finished refresh may have replaced the cover on disk
+        if self.refresh_pending && !pending {
+            self.cover_texture = None;
+        }
         self.refresh_pending = pending;
     }
     
@@ -67,6 +210,51 @@ impl GameDetailView {
         self.error_message = error;
     }
     
+    /// Set installation state
+    pub fn set_install_state(&mut self, state: Option<InstallState>) {
+        self.install_state = state;
+    }
+    
+    /// Set installed version
+    pub fn set_installed_version(&mut self, installed: Option<InstalledVersion>) {
+        self.installed_version = installed;
+    }
+    
+    /// Set whether the game is currently running
+    pub fn set_running(&mut self, running: bool) {
+        self.is_running = running;
+    }
+    
+    /// Set the free space on the install drive
+    pub fn set_free_space(&mut self, free_space: Option<u64>) {
+        self.free_space = free_space;
+    }
+    
+    /// Set the other library games in the same series
+    pub fn set_series_games(&mut self, games: Vec<(String, String)>) {
+        self.series_games = games;
+    }
+    
+    /// Set the games IGDB lists as similar, with the library game ID of the ones in the library
+    pub fn set_similar_games(&mut self, games: Vec<(String, Option<String>)>) {
+        self.similar_games = games;
+    }
+    
+    /// Set whether file verification is running
+    pub fn set_verifying(&mut self, is_verifying: bool) {
+        self.is_verifying = is_verifying;
+    }
+    
+    /// Set the problems found by file verification
+    pub fn set_verify_result(&mut self, problems: Option<Vec<String>>) {
+        self.verify_result = problems;
+    }
+    
+    /// Set whether the game is a favorite
+    pub fn set_favorite(&mut self, favorite: bool) {
+        self.is_favorite = favorite;
+    }
+    
     /// Show the game detail view
     pub fn show<F>(&mut self, ui: &mut egui::Ui, game: &GameInfo, is_installed: bool, metadata_handler: &MetadataHandler, mut on_action: F)
     where
@@ -80,7 +268,18 @@ impl GameDetailView {
             
             ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                 if ui.button("Refresh Metadata").clicked() {
-                    on_action(GameAction::FetchMetadata);
+                    on_action(GameAction::RefreshMetadata);
+                }
+                if ui.button("Clear cached metadata").clicked() {
+                    on_action(GameAction::ClearMetadata);
+                }
+                if self.metadata_form.is_none() && ui.button("Edit metadata").clicked() {
+                    let overrides = metadata_handler.user_overrides(&self.game_id).cloned().unwrap_or_default();
+                    self.metadata_form = Some(MetadataForm::from_overrides(&overrides));
+                }
+                let star = if self.is_favorite { "★ Favorite" } else { "☆ Favorite" };
+                if ui.button(star).clicked() {
+                    on_action(GameAction::ToggleFavorite);
                 }
             });
         });
@@ -93,6 +292,18 @@ impl GameDetailView {
             ui.separator();
         }
         
+        // Without credentials, fetching metadata can only fail
+        if !metadata_handler.is_igdb_configured() && !metadata_handler.has_igdb_metadata(&self.game_id) {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("IGDB is not configured, so metadata for this game cannot be fetched.")
+                    .color(egui::Color32::YELLOW));
+                if ui.button("Configure IGDB").clicked() {
+                    on_action(GameAction::ConfigureIgdb);
+                }
+            });
+            ui.separator();
+        }
+        
         // Show spinner if refresh is pending
         if self.refresh_pending {
             ui.horizontal(|ui| {
@@ -102,105 +313,190 @@ impl GameDetailView {
             ui.separator();
         }
         
-        // Game title
-        ui.heading(&game.title);
+        // Game title, over the hero artwork when we have one
+        self.render_title_banner(ui, game, metadata_handler);
         
         // Metadata display from IGDB or fallback to game info
         let has_igdb = metadata_handler.has_igdb_metadata(&self.game_id);
         let metadata = metadata_handler.get_metadata(&self.game_id);
+        let overrides = metadata.map(|metadata| &metadata.user_overrides);
+        let igdb_data = metadata
+            .filter(|_| has_igdb)
+            .and_then(|metadata| metadata.igdb_data.as_ref());
+        
+        // Values from IGDB when the game has IGDB metadata, otherwise from the repository
+        let companies = |developer: bool| {
+            let names: Vec<&str> = igdb_data
+                .and_then(|igdb_data| igdb_data.involved_companies.as_ref())
+                .into_iter()
+                .flatten()
+                .filter(|c| if developer { c.developer } else { c.publisher })
+                .map(|c| c.company.name.as_str())
+                .collect();
+            
+            (!names.is_empty()).then(|| names.join(", "))
+        };
+        let fetched = |igdb_value: Option<String>, repository_value: &Option<String>| {
+            if igdb_data.is_some() { igdb_value } else { repository_value.clone() }
+        };
+        let fetched_developer = fetched(companies(true), &game.developer);
+        let fetched_publisher = fetched(companies(false), &game.publisher);
+        let fetched_release_date = fetched(
+            igdb_data
+                .filter(|igdb_data| igdb_data.first_release_date.is_some())
+                .map(|igdb_data| {
+                    igdb_data.release_date()
+                        .map(|date| date.format("%B %d, %Y").to_string())
+                        .unwrap_or_else(|| "Unknown".to_string())
+                }),
+            &game.release_date,
+        );
+        
+        // The user's own values take precedence over both
+        let developer = overrides.and_then(|o| o.developer.clone()).or_else(|| fetched_developer.clone());
+        let publisher = overrides.and_then(|o| o.publisher.clone()).or_else(|| fetched_publisher.clone());
+        let release_date = overrides.and_then(|o| o.release_date.clone()).or_else(|| fetched_release_date.clone());
         
         ui.horizontal(|ui| {
-            if has_igdb {
-                if let Some(metadata) = metadata {
-                    if let Some(igdb_data) = &metadata.igdb_data {
-                        if let Some(companies) = &igdb_data.involved_companies {
-                            let developers = companies.iter()
-                                .filter(|c| c.developer)
-                                .map(|c| c.company.name.as_str())
-                                .collect::<Vec<_>>();
-                            let publishers = companies.iter()
-                                .filter(|c| c.publisher)
-                                .map(|c| c.company.name.as_str())
-                                .collect::<Vec<_>>();
-                            
-                            if !developers.is_empty() {
-                                ui.label(format!("Developer: {}", developers.join(", ")));
-                                ui.separator();
-                            }
-                            
-                            if !publishers.is_empty() {
-                                ui.label(format!("Publisher: {}", publishers.join(", ")));
-                                ui.separator();
-                            }
-                        }
-                        
-                        if let Some(release_date) = igdb_data.first_release_date {
-                            let date = chrono::NaiveDateTime::from_timestamp_opt(release_date as i64, 0)
-                                .map(|dt| dt.format("%B %d, %Y").to_string())
-                                .unwrap_or_else(|| "Unknown".to_string());
-                            
-                            ui.label(format!("Released: {}", date));
-                        }
-                    }
-                }
-            } else {
-                if let Some(developer) = &game.developer {
-                    ui.label(format!("Developer: {}", developer));
-                    ui.separator();
-                }
-                
-                if let Some(publisher) = &game.publisher {
-                    ui.label(format!("Publisher: {}", publisher));
-                    ui.separator();
-                }
-                
-                if let Some(release_date) = &game.release_date {
-                    ui.label(format!("Released: {}", release_date));
-                }
+            if let Some(developer) = &developer {
+                ui.label(format!("Developer: {}", developer));
+                ui.separator();
+            }
+            
+            if let Some(publisher) = &publisher {
+                ui.label(format!("Publisher: {}", publisher));
+                ui.separator();
+            }
+            
+            if let Some(release_date) = &release_date {
+                ui.label(format!("Released: {}", release_date));
+            }
+            
+            if !game.source.is_empty() {
+                ui.separator();
+                ui.label(format!("Repository: {}", game.source));
             }
         });
         
         ui.separator();
         
+        if let Some(form) = &mut self.metadata_form {
+            let mut save = false;
+            let mut cancel = false;
+            
+            egui::Frame::group(ui.style()).show(ui, |ui| {
+                ui.label(RichText::new("Edit metadata").strong());
+                ui.label(RichText::new("Empty fields show the fetched value").weak());
+                
+                egui::Grid::new("metadata_form")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Title:");
+                        ui.add(egui::TextEdit::singleline(&mut form.title).hint_text(&game.title));
+                        ui.end_row();
+                        
+                        ui.label("Developer:");
+                        ui.add(egui::TextEdit::singleline(&mut form.developer)
+                            .hint_text(fetched_developer.as_deref().unwrap_or_default()));
+                        ui.end_row();
+                        
+                        ui.label("Publisher:");
+                        ui.add(egui::TextEdit::singleline(&mut form.publisher)
+                            .hint_text(fetched_publisher.as_deref().unwrap_or_default()));
+                        ui.end_row();
+                        
+                        ui.label("Release date:");
+                        ui.add(egui::TextEdit::singleline(&mut form.release_date)
+                            .hint_text(fetched_release_date.as_deref().unwrap_or("e.g. 1993-12-10")));
+                        ui.end_row();
+                        
+                        ui.label("Description:");
+                        ui.add(egui::TextEdit::multiline(&mut form.description).desired_rows(4));
+                        ui.end_row();
+                    });
+                
+                ui.horizontal(|ui| {
+                    save = ui.button("Save").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                    if ui.button("Clear all").clicked() {
+                        *form = MetadataForm::default();
+                    }
+                });
+            });
+            
+            if save {
+                let overrides = form.to_overrides();
+                self.metadata_form = None;
+                on_action(GameAction::SaveOverrides(overrides));
+            } else if cancel {
+                self.metadata_form = None;
+            }
+            ui.separator();
+        }
+        
         // Split layout: details and version/installation
         ui.columns(2, |columns| {
             // Left column: details and cover image
             columns[0].vertical(|ui| {
-                if has_igdb && metadata_handler.has_cover(&self.game_id) {
-                    let cover_path = metadata_handler.get_cover_path(&self.game_id);
-                    self.render_cover_image(ui, &cover_path);
-                } else {
-                    if !has_igdb {
-                        ui.vertical_centered(|ui| {
-                            ui.label("No IGDB metadata available");
-                            if ui.button("Fetch Metadata").clicked() {
-                                on_action(GameAction::FetchMetadata);
-                            }
-                        });
-                    } else if let Some(metadata) = metadata {
-                        if let Some(igdb_data) = &metadata.igdb_data {
-                            if igdb_data.cover.is_some() {
-                                ui.vertical_centered(|ui| {
-                                    ui.label("Cover available but not downloaded");
-                                    if ui.button("Download Cover").clicked() {
-                                        on_action(GameAction::FetchMetadata);
-                                    }
-                                });
-                            } else {
-                                ui.label("No cover image available");
+                // The cover area doubles as a drop target for a custom cover image
+                let cover_rect = ui.scope(|ui| {
+                    if metadata_handler.has_cover(&self.game_id) {
+                        let cover_path = metadata_handler.get_cover_path(&self.game_id);
+                        self.render_cover_image(ui, &cover_path);
+                    } else {
+                        if !has_igdb {
+                            ui.vertical_centered(|ui| {
+                                ui.label("No IGDB metadata available");
+                                if ui.button("Fetch Metadata").clicked() {
+                                    on_action(GameAction::FetchMetadata);
+                                }
+                            });
+                        } else if let Some(metadata) = metadata {
+                            if let Some(igdb_data) = &metadata.igdb_data {
+                                if igdb_data.cover.is_some() {
+                                    ui.vertical_centered(|ui| {
+                                        ui.label("Cover available but not downloaded");
+                                        if ui.button("Download Cover").clicked() {
+                                            on_action(GameAction::FetchMetadata);
+                                        }
+                                    });
+                                } else {
+                                    ui.label("No cover image available");
+                                }
                             }
+                        } else {
+                            ui.label("Cover Image (Placeholder)");
                         }
-                    } else {
-                        ui.label("Cover Image (Placeholder)");
                     }
+                }).response.rect;
+                
+                if let Some(path) = Self::handle_cover_drop(ui, cover_rect) {
+                    on_action(GameAction::SetCover(path));
                 }
                 
                 ui.separator();
                 
+                let screenshots = metadata_handler.get_screenshot_paths(&self.game_id);
+                if !screenshots.is_empty() {
+                    ui.label(RichText::new("Screenshots:").strong());
+                    self.render_screenshot_gallery(ui, &screenshots);
+                    ui.separator();
+                }
+                
+                let user_description = overrides.and_then(|o| o.description.as_deref());
+                
                 if has_igdb {
                     if let Some(metadata) = metadata {
                         if let Some(igdb_data) = &metadata.igdb_data {
-                            if let Some(summary) = &igdb_data.summary {
+                            if let Some(description) = user_description {
+                                ui.label(RichText::new("Description:").strong());
+                                ui.separator();
+                                egui::ScrollArea::vertical()
+                                    .max_height(200.0)
+                                    .show(ui, |ui| {
+                                        ui.label(description);
+                                    });
+                            } else if let Some(summary) = &igdb_data.summary {
                                 ui.label(RichText::new("IGDB Summary:").strong());
                                 ui.separator();
                                 egui::ScrollArea::vertical()
@@ -210,31 +506,67 @@ impl GameDetailView {
                                     });
                             }
                             
-                            if let Some(genres) = &igdb_data.genres {
-                                if !genres.is_empty() {
-                                    ui.add_space(10.0);
-                                    ui.label(RichText::new("Genres:").strong());
-                                    let genre_list = genres.iter()
-                                        .map(|g| g.name.as_str())
-                                        .collect::<Vec<_>>()
-                                        .join(", ");
-                                    ui.label(genre_list);
-                                }
-                            }
+                            Self::render_chips(ui, "Genres:", igdb_data.genres.iter().flatten().map(|g| g.name.as_str()));
+                            Self::render_chips(ui, "Game modes:", igdb_data.game_modes.iter().flatten().map(|m| m.name.as_str()));
+                            Self::render_chips(ui, "Engines:", igdb_data.game_engines.iter().flatten().map(|e| e.name.as_str()));
                             
                             if let Some(rating) = igdb_data.total_rating {
                                 ui.add_space(10.0);
                                 ui.label(format!("Rating: {:.1}/100", rating));
                             }
                             
+                            let age_ratings: Vec<_> = igdb_data.age_ratings.iter()
+                                .flatten()
+                                .filter_map(|age_rating| Some((age_rating.board()?, age_rating.rating()?)))
+                                .collect();
+                            if !age_ratings.is_empty() {
+                                ui.add_space(10.0);
+                                ui.horizontal(|ui| {
+                                    ui.label(RichText::new("Age rating:").strong());
+                                    for (board, (label, description)) in age_ratings {
+                                        Self::render_age_rating_badge(ui, board, label)
+                                            .on_hover_text(format!("{} {}: {}", board, label, description));
+                                    }
+                                });
+                            }
+                            
                             if let Some(url) = &igdb_data.url {
                                 ui.add_space(5.0);
                                 ui.hyperlink_to("View on IGDB", url);
                             }
+                            
+                            if !self.series_games.is_empty() {
+                                ui.add_space(10.0);
+                                ui.label(RichText::new("More in this series:").strong());
+                                for (game_id, title) in &self.series_games {
+                                    if ui.link(title).clicked() {
+                                        on_action(GameAction::ShowGame(game_id.clone()));
+                                    }
+                                }
+                            }
+                            
+                            if !self.similar_games.is_empty() {
+                                ui.add_space(10.0);
+                                ui.label(RichText::new("Similar games:").strong());
+                                ui.horizontal_wrapped(|ui| {
+                                    for (title, game_id) in &self.similar_games {
+                                        match game_id {
+                                            Some(game_id) => {
+                                                if ui.link(title).on_hover_text("In your library").clicked() {
+                                                    on_action(GameAction::ShowGame(game_id.clone()));
+                                                }
+                                            }
+                                            None => {
+                                                ui.label(RichText::new(title).weak());
+                                            }
+                                        }
+                                    }
+                                });
+                            }
                         }
                     }
                 } else {
-                    if let Some(description) = &game.description {
+                    if let Some(description) = user_description.or(game.description.as_deref()) {
                         ui.label(RichText::new("Description:").strong());
                         ui.separator();
                         egui::ScrollArea::vertical()
@@ -246,6 +578,18 @@ impl GameDetailView {
                         ui.label("No description available.");
                     }
                 }
+                
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    ui.label("Override IGDB ID:");
+                    ui.add(egui::TextEdit::singleline(&mut self.igdb_id_input).desired_width(80.0));
+                    if ui.button("Apply").clicked() {
+                        match self.igdb_id_input.trim().parse::<u32>() {
+                            Ok(igdb_id) => on_action(GameAction::OverrideIgdbId(igdb_id)),
+                            Err(_) => self.error_message = Some(format!("Invalid IGDB ID: {}", self.igdb_id_input.trim())),
+                        }
+                    }
+                });
             });
             
             // Right column: versions and installation actions
@@ -257,6 +601,7 @@ impl GameDetailView {
                         ui.radio_value(&mut self.selected_version, i, &version.name);
                         ui.horizontal(|ui| {
                             ui.label(format!("Build: {}", version.build));
+                            ui.label(helpers::format_bytes(version.total_size()));
                             let installer_count = version.files.len();
                             ui.label(format!("{} files", installer_count));
                             let patch_count = version.required_patches.len();
@@ -266,16 +611,160 @@ impl GameDetailView {
                         });
                         ui.separator();
                     }
+                    
+                    if !game.dlc.is_empty() {
+                        ui.heading("DLC");
+                        for file in &game.dlc {
+                            let mut selected = self.selected_dlc.contains(&file.remote_path);
+                            let label = format!("{} ({})", file.name, helpers::format_bytes(file.size));
+                            if ui.checkbox(&mut selected, label).changed() {
+                                if selected {
+                                    self.selected_dlc.insert(file.remote_path.clone());
+                                } else {
+                                    self.selected_dlc.remove(&file.remote_path);
+                                }
+                            }
+                        }
+                    }
                 });
                 ui.separator();
+                
+                if let Some(installed) = &self.installed_version {
+                    ui.label(format!("Installed: {} (build {})", installed.version_name, installed.build));
+                    
+                    if let Some(last_played) = installed.last_played.as_deref()
+                        .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+                    {
+                        ui.label(format!("Last played: {}", last_played.with_timezone(&chrono::Local).format("%B %d, %Y %H:%M")));
+                    }
+                    
+                    if installed.total_playtime_secs > 0 {
+                        let hours = installed.total_playtime_secs / 3600;
+                        let minutes = (installed.total_playtime_secs % 3600) / 60;
+                        ui.label(format!("Playtime: {}h {}m", hours, minutes));
+                    }
+                    
+                    if let Some(latest) = game.latest_version() {
+                        if latest.build > installed.build {
+                            ui.label(RichText::new(format!("Update available: {}", latest.name))
+                                .color(egui::Color32::YELLOW));
+                        }
+                    }
+                    ui.separator();
+                }
+                
+                let is_installing = self.install_state.as_ref().map_or(false, |state| state.is_running);
+                
+                if let Some(state) = &self.install_state {
+                    if state.is_running {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(&state.message);
+                        });
+                        if let Some(progress) = state.progress {
+                            ui.add(egui::ProgressBar::new(progress).show_percentage());
+                        }
+                        if ui.button("Cancel").clicked() {
+                            on_action(GameAction::CancelInstall);
+                        }
+                    } else if let Some(error) = &state.error {
+                        ui.label(RichText::new(error).color(egui::Color32::RED));
+                    } else {
+                        ui.label(&state.message);
+                    }
+                    ui.separator();
+                }
+                
                 ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
-                    if is_installed {
+                    if is_installing {
+                        ui.label("Installation in progress...");
+                    } else if is_installed {
                         if ui.button("Uninstall").clicked() {
                             on_action(GameAction::Uninstall);
                         }
+                        
+                        if self.is_running {
+                            ui.label("Running...");
+                        } else if ui.button(RichText::new("▶ Play").strong()).clicked() {
+                            on_action(GameAction::Launch);
+                        }
+                        
+                        if let Some(installed) = &self.installed_version {
+                            if let Some(latest) = game.latest_version() {
+                                if latest.build > installed.build && ui.button("Update").clicked() {
+                                    on_action(GameAction::Install(0, Vec::new()));
+                                }
+                            }
+                        }
+                        
+                        if ui.add_enabled(!self.is_verifying, egui::Button::new("Verify files")).clicked() {
+                            self.verify_result = None;
+                            on_action(GameAction::VerifyFiles);
+                        }
+                        if self.is_verifying {
+                            ui.spinner();
+                        }
+                        
+                        if let Some(problems) = &self.verify_result {
+                            let mut repair = false;
+                            ui.vertical(|ui| {
+                                if problems.is_empty() {
+                                    ui.colored_label(egui::Color32::GREEN, "All files are present");
+                                    return;
+                                }
+                                
+                                ui.colored_label(egui::Color32::RED, format!("⚠ {} problems found:", problems.len()));
+                                egui::ScrollArea::vertical()
+                                    .id_source("verify_problems")
+                                    .max_height(120.0)
+                                    .show(ui, |ui| {
+                                        for problem in problems {
+                                            ui.colored_label(egui::Color32::RED, format!("• {}", problem));
+                                        }
+                                    });
+                                repair = ui.button("Repair").clicked();
+                            });
+                            
+                            if repair {
+                                self.verify_result = None;
+                                on_action(GameAction::Repair);
+                            }
+                        }
                     } else if !game.versions.is_empty() {
+                        let issues = game.versions.get(self.selected_version)
+                            .map(|version| version.patch_chain_issues(game))
+                            .unwrap_or_default();
+                        if !issues.is_empty() {
+                            ui.colored_label(egui::Color32::YELLOW, "⚠ The patch chain of this version looks broken:");
+                            for issue in &issues {
+                                ui.colored_label(egui::Color32::YELLOW, format!("• {}", issue));
+                            }
+                        }
+                        
+                        let dlc: Vec<GameFile> = game.dlc.iter()
+                            .filter(|file| self.selected_dlc.contains(&file.remote_path))
+                            .cloned()
+                            .collect();
+                        
+                        if let Some(free_space) = self.free_space {
+                            let required = game.versions.get(self.selected_version)
+                                .map_or(0, |version| version.total_size())
+                                + dlc.iter().map(|file| file.size).sum::<u64>();
+                            let text = format!(
+                                "{} free on the install drive, {} needed",
+                                helpers::format_bytes(free_space),
+                                helpers::format_bytes(required)
+                            );
+                            
+                            if free_space < required {
+                                ui.colored_label(egui::Color32::RED, format!("⚠ {}", text));
+                            } else {
+                                ui.label(text);
+                            }
+                        }
+                        
                         if ui.button("Install Selected Version").clicked() {
-                            on_action(GameAction::Install(self.selected_version));
+                            on_action(GameAction::Install(self.selected_version, dlc));
                         }
                     } else {
                         ui.label("No versions available to install");
@@ -285,10 +774,145 @@ impl GameDetailView {
         });
     }
     
+    /// Render a heading followed by one chip per name, or nothing if there are no names
+    fn render_chips<'a>(ui: &mut Ui, heading: &str, names: impl Iterator<Item = &'a str>) {
+        let names: Vec<&str> = names.collect();
+        if names.is_empty() {
+            return;
+        }
+        
+        ui.add_space(10.0);
+        ui.label(RichText::new(heading).strong());
+        ui.horizontal_wrapped(|ui| {
+            for name in names {
+                egui::Frame::none()
+                    .fill(ui.visuals().widgets.inactive.bg_fill)
+                    .rounding(8.0)
+                    .inner_margin(egui::Margin::symmetric(6.0, 2.0))
+                    .show(ui, |ui| {
+                        ui.label(name);
+                    });
+            }
+        });
+    }
+    
+    /// Render an ESRB or PEGI rating as an outlined badge, coloured like the PEGI labels
+    fn render_age_rating_badge(ui: &mut Ui, board: &str, label: &str) -> egui::Response {
+        let color = match (board, label) {
+            ("PEGI", "3" | "7") => egui::Color32::from_rgb(0x6c, 0xbe, 0x45),
+            ("PEGI", "12" | "16") => egui::Color32::from_rgb(0xf7, 0xa8, 0x00),
+            ("PEGI", _) => egui::Color32::from_rgb(0xe3, 0x00, 0x0f),
+            _ => ui.visuals().strong_text_color(),
+        };
+        
+        egui::Frame::none()
+            .stroke(egui::Stroke::new(1.5, color))
+            .rounding(3.0)
+            .inner_margin(egui::Margin::symmetric(4.0, 1.0))
+            .show(ui, |ui| {
+                ui.label(RichText::new(format!("{} {}", board, label)).strong().color(color));
+            })
+            .response
+    }
+    
+    /// Render the title over the hero artwork, falling back to the cover or a plain heading
+    fn render_title_banner(&mut self, ui: &mut Ui, game: &GameInfo, metadata_handler: &MetadataHandler) {
+        let texture = if metadata_handler.has_hero(&self.game_id) {
+            if self.hero_texture.is_none() {
+                let hero_path = metadata_handler.get_hero_path(&self.game_id);
+                self.hero_texture = self.images.texture(ui.ctx(), &hero_path, "game_hero");
+            }
+            self.hero_texture.clone().flatten()
+        } else if metadata_handler.has_cover(&self.game_id) {
+            if self.cover_texture.is_none() {
+                let cover_path = metadata_handler.get_cover_path(&self.game_id);
+                self.cover_texture = self.images.texture(ui.ctx(), &cover_path, "game_cover");
+            }
+            self.cover_texture.clone().flatten()
+        } else {
+            None
+        };
+        
+        let Some(texture) = texture else {
+            ui.heading(metadata_handler.display_title(game));
+            return;
+        };
+        
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), BANNER_HEIGHT), egui::Sense::hover());
+        helpers::paint_texture_fill(ui.painter(), &texture, rect);
+        
+        // Darken the bottom strip so the title stays readable
+        let shade = egui::Rect::from_min_max(egui::pos2(rect.left(), rect.bottom() - 48.0), rect.max);
+        ui.painter().rect_filled(shade, 0.0, egui::Color32::from_black_alpha(160));
+        ui.painter().text(
+            egui::pos2(rect.left() + 12.0, rect.bottom() - 24.0),
+            egui::Align2::LEFT_CENTER,
+            metadata_handler.display_title(game),
+            egui::TextStyle::Heading.resolve(ui.style()),
+            egui::Color32::WHITE,
+        );
+    }
+    
+    /// Render a horizontally scrollable screenshot gallery
+    fn render_screenshot_gallery(&mut self, ui: &mut Ui, paths: &[PathBuf]) {
+        egui::ScrollArea::horizontal()
+            .id_source("screenshot_gallery")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for (index, path) in paths.iter().enumerate() {
+                        let (rect, _) = ui.allocate_exact_size(SCREENSHOT_SIZE, egui::Sense::hover());
+                        
+                        // Only decode screenshots that are actually on screen
+                        if !ui.is_rect_visible(rect) {
+                            continue;
+                        }
+                        
+                        if !self.screenshot_textures.contains_key(&index) {
+                            let name = format!("screenshot_{}_{}", self.game_id, index);
+                            if let Some(texture) = self.images.texture(ui.ctx(), path, &name) {
+                                self.screenshot_textures.insert(index, texture);
+                            }
+                        }
+                        
+                        match self.screenshot_textures.get(&index) {
+                            Some(Some(texture)) => egui::Image::new(texture, SCREENSHOT_SIZE).paint_at(ui, rect),
+                            _ => ui.painter().rect_filled(rect, 4.0, egui::Color32::from_gray(60)),
+                        }
+                    }
+                });
+            });
+    }
+    
+    /// Highlight the cover area while files are dragged over it and return a file dropped onto it
+    fn handle_cover_drop(ui: &mut Ui, cover_rect: egui::Rect) -> Option<PathBuf> {
+        let (hovering_files, dropped_files, pointer) = ui.ctx().input(|i| {
+            (!i.raw.hovered_files.is_empty(), i.raw.dropped_files.clone(), i.pointer.hover_pos())
+        });
+        
+        // Some platforms don't report the pointer during a file drag, so accept the drop then
+        let over_cover = pointer.map_or(true, |pos| cover_rect.contains(pos));
+        if !over_cover {
+            return None;
+        }
+        
+        if hovering_files {
+            ui.painter().rect_stroke(cover_rect, 4.0, egui::Stroke::new(2.0, egui::Color32::LIGHT_BLUE));
+            ui.painter().text(
+                cover_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop image to set cover",
+                egui::FontId::default(),
+                egui::Color32::WHITE,
+            );
+        }
+        
+        dropped_files.into_iter().find_map(|file| file.path)
+    }
+    
     /// Render cover image using the helper function
     fn render_cover_image(&mut self, ui: &mut Ui, path: &PathBuf) {
         if self.cover_texture.is_none() {
-            self.cover_texture = helpers::load_texture_from_path(ui.ctx(), path, "game_cover");
+            self.cover_texture = self.images.texture(ui.ctx(), path, "game_cover");
         }
         
         let cover_rect = egui::Rect::from_min_size(
@@ -296,7 +920,7 @@ impl GameDetailView {
             egui::vec2(200.0, 300.0)
         );
         
-        if let Some(texture) = &self.cover_texture {
+        if let Some(Some(texture)) = &self.cover_texture {
             ui.image(texture, egui::vec2(200.0, 300.0));
         } else {
             ui.allocate_ui_at_rect(cover_rect, |ui| {
@@ -306,7 +930,7 @@ impl GameDetailView {
                     egui::Color32::from_rgb(100, 100, 200)
                 );
                 ui.centered_and_justified(|ui| {
-                    ui.label("Cover Image");
+                    ui.label(if self.cover_texture.is_none() { "Loading..." } else { "Cover Image" });
                 });
             });
         }
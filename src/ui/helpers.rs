@@ -2,21 +2,83 @@ use eframe::egui;
 use std::path::Path;
 use std::fs;
 
-pub fn load_texture_from_path(ctx: &egui::Context, path: &Path, texture_id: &str) -> Option<egui::TextureHandle> {
+/// Decode the image file at `path` into pixels ready to upload as a texture
+pub fn decode_image(path: &Path) -> Option<egui::ColorImage> {
     if path.exists() {
         if let Ok(image_data) = fs::read(path) {
             if let Ok(image) = image::load_from_memory(&image_data) {
                 let size = [image.width() as _, image.height() as _];
                 let image_rgba = image.to_rgba8();
                 let pixels = image_rgba.as_flat_samples();
-                let texture = ctx.load_texture(
-                    texture_id,
-                    egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice()),
-                    egui::TextureOptions::default(),
-                );
-                return Some(texture);
+                return Some(egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice()));
             }
         }
     }
     None
 }
+
+/// Paint a texture so it fills `rect`, cropping the edges to keep its aspect ratio
+pub fn paint_texture_fill(painter: &egui::Painter, texture: &egui::TextureHandle, rect: egui::Rect) {
+    let [width, height] = texture.size();
+    let image_aspect = width as f32 / height.max(1) as f32;
+    let rect_aspect = rect.width() / rect.height().max(1.0);
+    
+    let uv = if image_aspect > rect_aspect {
+        // Image is wider than the rect, crop left and right
+        let visible = rect_aspect / image_aspect;
+        egui::Rect::from_min_max(egui::pos2((1.0 - visible) / 2.0, 0.0), egui::pos2((1.0 + visible) / 2.0, 1.0))
+    } else {
+        // Image is taller than the rect, crop top and bottom
+        let visible = image_aspect / rect_aspect;
+        egui::Rect::from_min_max(egui::pos2(0.0, (1.0 - visible) / 2.0), egui::pos2(1.0, (1.0 + visible) / 2.0))
+    };
+    
+    painter.image(texture.id(), rect, uv, egui::Color32::WHITE);
+}
+
+/// Truncate text to at most `max_chars` characters, appending "..." when shortened
+pub fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    
+    let keep = max_chars.saturating_sub(3);
+    let end = text.char_indices()
+        .nth(keep)
+        .map(|(idx, _)| idx)
+        .unwrap_or(text.len());
+    
+    format!("{}...", &text[..end])
+}
+
+/// Format a byte count with a binary unit, e.g. "1.5 MB"
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn truncates_multi_byte_titles_on_char_boundaries() {
+        let title = "ファイナルファンタジー";
+        
+        assert_eq!(truncate_chars(title, 8), "ファイナル...");
+        assert_eq!(truncate_chars(title, 10), "ファイナルファ...");
+        assert_eq!(truncate_chars(title, 11), title);
+    }
+}
@@ -0,0 +1,154 @@
+use eframe::egui;
+use log::error;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use crate::ui::helpers;
+
+/// State of an image handed to the decoder thread
+enum Decode {
+    /// Waiting for or being decoded
+    Queued,
+    /// Decoded, or `None` if the file could not be read
+    Done(Option<egui::ColorImage>),
+}
+
+/// Decodes images on a background thread so layout never waits for a file
+///
+/// Clones share the same queue, so the library and detail views never decode
+/// the same file twice at once.
+#[derive(Clone)]
+pub struct ImageLoader {
+    requests: Sender<PathBuf>,
+    decodes: Arc<Mutex<HashMap<PathBuf, Decode>>>,
+}
+
+impl ImageLoader {
+    /// Start the decoder thread, repainting `ctx` whenever an image is ready
+    pub fn new(ctx: egui::Context) -> Self {
+        let (requests, receiver) = mpsc::channel::<PathBuf>();
+        let decodes: Arc<Mutex<HashMap<PathBuf, Decode>>> = Arc::new(Mutex::new(HashMap::new()));
+        
+        let worker_decodes = decodes.clone();
+        let spawned = thread::Builder::new()
+            .name("image-decoder".to_string())
+            .spawn(move || {
+                for path in receiver {
+                    let image = helpers::decode_image(&path);
+                    
+                    // Skip results nobody is waiting for any more
+                    if let Some(decode) = worker_decodes.lock().unwrap().get_mut(&path) {
+                        *decode = Decode::Done(image);
+                    }
+                    ctx.request_repaint();
+                }
+            });
+        
+        if let Err(e) = spawned {
+            error!("Failed to start image decoder thread: {}", e);
+        }
+        
+        Self { requests, decodes }
+    }
+    
+    /// Get the texture for the image at `path`, queueing it for decoding on first use
+    ///
+    /// Returns `None` while the image is decoding and `Some(None)` if it could not
+    /// be read. The texture is only handed out once, so callers should cache it.
+    pub fn texture(&self, ctx: &egui::Context, path: &Path, name: &str) -> Option<Option<egui::TextureHandle>> {
+        let mut decodes = self.decodes.lock().unwrap();
+        
+        match decodes.get(path) {
+            Some(Decode::Queued) => return None,
+            Some(Decode::Done(_)) => {}
+            None => {
+                if self.requests.send(path.to_path_buf()).is_err() {
+                    // The decoder thread is gone, so the image will never load
+                    return Some(None);
+                }
+                decodes.insert(path.to_path_buf(), Decode::Queued);
+                return None;
+            }
+        }
+        
+        let Some(Decode::Done(image)) = decodes.remove(path) else {
+            return None;
+        };
+        
+        Some(image.map(|image| ctx.load_texture(name, image, egui::TextureOptions::default())))
+    }
+}
+
+/// Texture cache that drops the least recently drawn textures beyond a fixed size
+pub struct TextureCache {
+    /// Textures by key, with the tick they were last drawn at
+    entries: HashMap<String, (Option<egui::TextureHandle>, u64)>,
+    /// Incremented on every lookup to order entries by use
+    tick: u64,
+    /// Maximum number of entries kept
+    capacity: usize,
+}
+
+impl TextureCache {
+    /// Create an empty cache holding at most `capacity` textures
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            tick: 0,
+            capacity,
+        }
+    }
+    
+    /// Change the maximum number of textures, evicting any over the new limit
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+    
+    /// Get a texture and mark it as just drawn
+    ///
+    /// `Some(None)` means the image was already found to be unreadable.
+    pub fn get(&mut self, key: &str) -> Option<&Option<egui::TextureHandle>> {
+        self.tick += 1;
+        let tick = self.tick;
+        
+        self.entries.get_mut(key).map(|(texture, last_used)| {
+            *last_used = tick;
+            &*texture
+        })
+    }
+    
+    /// Check whether a texture is cached without marking it as drawn
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+    
+    /// Add a texture, evicting the least recently drawn ones if the cache is full
+    pub fn insert(&mut self, key: String, texture: Option<egui::TextureHandle>) {
+        self.tick += 1;
+        self.entries.insert(key, (texture, self.tick));
+        self.evict();
+    }
+    
+    /// Drop every texture
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+    
+    /// Remove the least recently drawn entries until the cache fits its capacity
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            let oldest = self.entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            
+            match oldest {
+                Some(key) => self.entries.remove(&key),
+                None => break,
+            };
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use eframe::egui;
+use egui::RichText;
+use std::time::Duration;
+
+use crate::installer::{JobStatus, QueuedJob};
+use crate::ui::app::InstallState;
+use crate::ui::helpers;
+
+/// How long the result of a finished installation stays in the panel
+const RESULT_DISPLAY_TIME: Duration = Duration::from_secs(10);
+
+/// Install panel action
+pub enum InstallPanelAction {
+    /// Cancel the running installation
+    Cancel,
+    /// Open the detail view of the game being installed
+    ShowGame(String),
+    /// Move a queued job one place earlier
+    MoveUp(u64),
+    /// Move a queued job one place later
+    MoveDown(u64),
+    /// Remove a queued or finished job
+    RemoveJob(u64),
+    /// Remove every finished job
+    ClearFinished,
+}
+
+/// Panel showing the running installation and the queue, whichever view is open
+#[derive(Default)]
+pub struct InstallPanel {
+    /// Installation shown in the panel
+    state: Option<InstallState>,
+    /// Queued, running and finished installations
+    jobs: Vec<QueuedJob>,
+    /// Whether queued installations are held back by offline mode
+    paused: bool,
+}
+
+impl InstallPanel {
+    /// Set the installation to show
+    pub fn set_state(&mut self, state: Option<InstallState>) {
+        self.state = state;
+    }
+    
+    /// Set the installation queue
+    pub fn set_jobs(&mut self, jobs: Vec<QueuedJob>) {
+        self.jobs = jobs;
+    }
+    
+    /// Set whether queued installations are held back
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+    
+    /// Check whether an installation is running or finished recently enough to show
+    pub fn is_showing_install(&self) -> bool {
+        self.state.as_ref().map_or(false, |state| {
+            state.is_running
+                || state.finished_at.map_or(false, |finished_at| finished_at.elapsed() < RESULT_DISPLAY_TIME)
+        })
+    }
+    
+    /// Check whether the panel has anything to show
+    pub fn is_visible(&self) -> bool {
+        self.is_showing_install() || !self.jobs.is_empty()
+    }
+    
+    /// Show the panel
+    pub fn show<F>(&mut self, ui: &mut egui::Ui, mut on_action: F)
+    where
+        F: FnMut(InstallPanelAction),
+    {
+        if self.is_showing_install() {
+            self.show_install(ui, &mut on_action);
+        }
+        
+        if !self.jobs.is_empty() {
+            self.show_queue(ui, &mut on_action);
+        }
+    }
+    
+    /// Show the progress or result of the current installation
+    fn show_install(&self, ui: &mut egui::Ui, on_action: &mut impl FnMut(InstallPanelAction)) {
+        let Some(state) = &self.state else {
+            return;
+        };
+        
+        ui.horizontal(|ui| {
+            if state.is_running {
+                ui.spinner();
+            }
+            ui.label(RichText::new(&state.game_title).strong());
+            
+            if state.is_running {
+                ui.label(&state.message);
+            } else if let Some(error) = &state.error {
+                ui.label(RichText::new(error).color(egui::Color32::RED));
+            } else {
+                ui.label(RichText::new(&state.message).color(egui::Color32::GREEN));
+            }
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if state.is_running && ui.button("Cancel").clicked() {
+                    on_action(InstallPanelAction::Cancel);
+                }
+                if ui.button("Show game").clicked() {
+                    on_action(InstallPanelAction::ShowGame(state.game_id.clone()));
+                }
+            });
+        });
+        
+        if !state.is_running {
+            return;
+        }
+        
+        if let Some(progress) = state.overall_progress() {
+            let mut text = format!(
+                "{} of {}",
+                helpers::format_bytes(state.downloaded_bytes()),
+                helpers::format_bytes(state.total_bytes)
+            );
+            if state.bytes_per_second >= 1.0 {
+                text.push_str(&format!(" at {}/s", helpers::format_bytes(state.bytes_per_second as u64)));
+            }
+            if let Some(eta) = state.eta() {
+                text.push_str(&format!(", {} left", format_eta(eta)));
+            }
+            
+            ui.add(egui::ProgressBar::new(progress).text(text));
+        }
+        
+        for file in state.files.iter().filter(|file| !file.is_done) {
+            let progress = if file.size == 0 {
+                0.0
+            } else {
+                file.downloaded as f32 / file.size as f32
+            };
+            
+            ui.horizontal(|ui| {
+                ui.label(helpers::truncate_chars(&file.name, 48));
+                ui.add(egui::ProgressBar::new(progress).show_percentage());
+            });
+        }
+    }
+    
+    /// Show the queued, running and finished installations with controls to reorder them
+    fn show_queue(&self, ui: &mut egui::Ui, on_action: &mut impl FnMut(InstallPanelAction)) {
+        let waiting = self.jobs.iter().filter(|job| job.status == JobStatus::Queued).count();
+        let finished = self.jobs.iter().filter(|job| !job.is_pending()).count();
+        
+        let mut title = format!("Install queue: {} waiting, {} finished", waiting, finished);
+        if self.paused && waiting > 0 {
+            title.push_str(" (paused while offline)");
+        }
+        
+        egui::CollapsingHeader::new(title)
+            .id_source("install_queue")
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        for (index, job) in self.jobs.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let (status, color) = match job.status {
+                                    JobStatus::Queued => ("Queued", ui.visuals().weak_text_color()),
+                                    JobStatus::Active => ("Installing", ui.visuals().text_color()),
+                                    JobStatus::Done => ("Done", egui::Color32::GREEN),
+                                    JobStatus::Failed => ("Failed", egui::Color32::RED),
+                                };
+                                ui.label(RichText::new(status).color(color));
+                                ui.label(format!("{} ({})", job.game.title, job.version.name));
+                                
+                                if let Some(message) = &job.message {
+                                    ui.label(RichText::new(helpers::truncate_chars(message, 60)).weak())
+                                        .on_hover_text(message);
+                                }
+                                
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    if job.status == JobStatus::Active {
+                                        return;
+                                    }
+                                    
+                                    if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                                        on_action(InstallPanelAction::RemoveJob(job.id));
+                                    }
+                                    if job.status == JobStatus::Queued {
+                                        let is_last = index + 1 == self.jobs.len();
+                                        if ui.add_enabled(!is_last, egui::Button::new("⬇").small()).clicked() {
+                                            on_action(InstallPanelAction::MoveDown(job.id));
+                                        }
+                                        if ui.add_enabled(index > 0, egui::Button::new("⬆").small()).clicked() {
+                                            on_action(InstallPanelAction::MoveUp(job.id));
+                                        }
+                                    }
+                                });
+                            });
+                        }
+                    });
+                
+                if finished > 0 && ui.button("Clear finished").clicked() {
+                    on_action(InstallPanelAction::ClearFinished);
+                }
+            });
+    }
+}
+
+/// Format a remaining time such as "1h 05m", "4m 20s" or "35s"
+fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
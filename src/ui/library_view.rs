@@ -1,11 +1,36 @@
+use chrono::Datelike;
 use eframe::egui;
 use egui::{Align, Layout};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::config::GroupBy;
 use crate::repository::GameInfo;
 use crate::metadata::MetadataHandler;
 use crate::ui::helpers; // Import helper for image loading
+use crate::ui::image_loader::{ImageLoader, TextureCache};
+
+/// How long the search query must stay unchanged before the library is filtered
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// How long a game counts as recently added after it first appeared
+const RECENT_DAYS: u64 = 30;
+
+/// Range of the cover width slider in the grid view
+const COVER_WIDTH_RANGE: std::ops::RangeInclusive<f32> = 100.0..=320.0;
+
+/// Number of cover textures kept in memory unless configured otherwise
+const DEFAULT_MAX_COVER_TEXTURES: usize = 300;
+
+/// Fewest cover textures kept, so a full screen of covers never evicts itself
+const MIN_COVER_TEXTURES: usize = 64;
+
+/// Height of a grid cover relative to its width
+const COVER_ASPECT: f32 = 1.375;
+
+/// Space between the cards of the grid view
+const GRID_SPACING: f32 = 20.0;
 
 /// View mode for the library
 #[derive(PartialEq)]
@@ -16,36 +41,151 @@ pub enum ViewMode {
     List,
 }
 
+/// Install state filter for the library
+#[derive(PartialEq, Clone, Copy)]
+pub enum InstallFilter {
+    /// Show every game
+    All,
+    /// Show installed games only
+    Installed,
+    /// Show games that are not installed
+    NotInstalled,
+}
+
+/// Everything the filtered game list depends on
+#[derive(PartialEq)]
+struct FilterKey {
+    query: String,
+    install_filter: InstallFilter,
+    genres: BTreeSet<String>,
+    modes: BTreeSet<String>,
+    favorites_only: bool,
+    favorites_first: bool,
+    favorites: HashSet<String>,
+    recent_only: bool,
+    game_count: usize,
+    installed_count: usize,
+}
+
 /// Library view action
 pub enum LibraryAction {
     /// Select a game
     SelectGame(usize),
     /// Refresh all metadata
     RefreshAll,
+    /// Refresh metadata for the games that failed in the last refresh
+    RetryFailed,
+    /// Stop a running metadata refresh
+    CancelRefresh,
+    /// Add or remove a game from the favorites
+    ToggleFavorite(String),
+    /// Re-fetch metadata for a game
+    RefreshGame(usize),
+    /// Install the latest version of a game
+    InstallLatest(usize),
+    /// Delete the cached metadata and images for a game
+    ClearMetadata(usize),
+    /// Re-fetch metadata for the selected games, by ID
+    RefreshSelected(Vec<String>),
+    /// Install the latest version of the selected games, by ID
+    InstallSelected(Vec<String>),
+    /// Remember a new grid cover width
+    SetCoverWidth(f32),
+    /// Remember a new grid grouping
+    SetGroupBy(GroupBy),
 }
 
 /// Library view
 pub struct LibraryView {
     /// Current view mode
     view_mode: ViewMode,
-    /// Search query
+    /// Search query as typed
     search_query: String,
+    /// Search query the library is filtered by, lagging behind typing
+    debounced_query: String,
+    /// When the search query was last edited
+    last_search_edit: Option<Instant>,
+    /// Indices of the games that passed the filters, with what they were computed from
+    filter_cache: Option<(FilterKey, Vec<usize>)>,
+    /// Install state filter
+    install_filter: InstallFilter,
+    /// Genres to show; empty shows every game
+    selected_genres: BTreeSet<String>,
+    /// Game modes to show, such as co-operative; empty shows every game
+    selected_modes: BTreeSet<String>,
+    /// IDs of the games marked as favorite
+    favorites: HashSet<String>,
+    /// Show favorite games only
+    favorites_only: bool,
+    /// Sort favorite games before the others
+    favorites_first: bool,
+    /// Only show games that recently appeared in the repository, newest first
+    recent_only: bool,
+    /// When each game first appeared in the repository, by ID
+    first_seen: HashMap<String, u64>,
+    /// Whether games are listed from several repositories, so their source is shown
+    show_sources: bool,
+    /// IDs of the games selected for batch actions
+    selected: HashSet<String>,
+    /// Position in the filtered list that Shift-click ranges start from
+    selection_anchor: Option<usize>,
+    /// Position in the filtered list of the game focused with the keyboard
+    focused_index: Option<usize>,
+    /// Whether the focused game should be scrolled into view this frame
+    scroll_to_focused: bool,
+    /// Width of a cover in the grid view
+    cover_width: f32,
+    /// Number of columns the grid view was last laid out with
+    grid_columns: usize,
+    /// How the grid view is split into sections
+    group_by: GroupBy,
+    /// Background decoder for cover and hero images
+    images: ImageLoader,
     /// Cache for loaded cover textures
-    cover_textures: HashMap<String, Option<egui::TextureHandle>>,
+    cover_textures: TextureCache,
+    /// Cache for loaded hero artwork textures
+    hero_textures: HashMap<String, Option<egui::TextureHandle>>,
+    /// Whether to show the featured game banner
+    show_banner: bool,
+    /// Number of games that failed in the last metadata refresh
+    failed_refreshes: usize,
 }
 
 impl LibraryView {
     /// Create a new library view
-    pub fn new() -> Self {
+    pub fn new(images: ImageLoader) -> Self {
         Self {
             view_mode: ViewMode::Grid,
             search_query: String::new(),
-            cover_textures: HashMap::new(),
+            debounced_query: String::new(),
+            last_search_edit: None,
+            filter_cache: None,
+            install_filter: InstallFilter::All,
+            selected_genres: BTreeSet::new(),
+            selected_modes: BTreeSet::new(),
+            favorites: HashSet::new(),
+            favorites_only: false,
+            favorites_first: true,
+            recent_only: false,
+            first_seen: HashMap::new(),
+            show_sources: false,
+            selected: HashSet::new(),
+            selection_anchor: None,
+            focused_index: None,
+            scroll_to_focused: false,
+            cover_width: 160.0,
+            grid_columns: 4,
+            group_by: GroupBy::None,
+            images,
+            cover_textures: TextureCache::new(DEFAULT_MAX_COVER_TEXTURES),
+            hero_textures: HashMap::new(),
+            show_banner: true,
+            failed_refreshes: 0,
         }
     }
     
     /// Show the library view
-    pub fn show<F>(&mut self, ui: &mut egui::Ui, games: &[GameInfo], metadata_handler: Option<&MetadataHandler>, mut on_action: F)
+    pub fn show<F>(&mut self, ui: &mut egui::Ui, games: &[GameInfo], installed: &HashSet<String>, metadata_handler: Option<&MetadataHandler>, mut on_action: F)
     where
         F: FnMut(LibraryAction),
     {
@@ -57,174 +197,919 @@ impl LibraryView {
             if ui.selectable_label(self.view_mode == ViewMode::List, "List").clicked() {
                 self.view_mode = ViewMode::List;
             }
+            if self.view_mode == ViewMode::Grid {
+                ui.label("Size:");
+                let size = ui.add(egui::Slider::new(&mut self.cover_width, COVER_WIDTH_RANGE).show_value(false));
+                if size.drag_released() || (size.changed() && !size.dragged()) {
+                    on_action(LibraryAction::SetCoverWidth(self.cover_width));
+                }
+                
+                let previous_group_by = self.group_by;
+                egui::ComboBox::from_id_source("library_group_by")
+                    .selected_text(match self.group_by {
+                        GroupBy::None => "No grouping",
+                        GroupBy::Genre => "By genre",
+                        GroupBy::Platform => "By platform",
+                        GroupBy::Decade => "By decade",
+                        GroupBy::Series => "By series",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.group_by, GroupBy::None, "No grouping");
+                        ui.selectable_value(&mut self.group_by, GroupBy::Genre, "By genre");
+                        ui.selectable_value(&mut self.group_by, GroupBy::Platform, "By platform");
+                        ui.selectable_value(&mut self.group_by, GroupBy::Decade, "By decade");
+                        ui.selectable_value(&mut self.group_by, GroupBy::Series, "By series");
+                    });
+                if self.group_by != previous_group_by {
+                    on_action(LibraryAction::SetGroupBy(self.group_by));
+                }
+            }
+            ui.checkbox(&mut self.show_banner, "Banner");
+            ui.separator();
+            ui.label("Show:");
+            for (filter, label) in [
+                (InstallFilter::All, "All"),
+                (InstallFilter::Installed, "Installed"),
+                (InstallFilter::NotInstalled, "Not installed"),
+            ] {
+                if ui.selectable_label(self.install_filter == filter, label).clicked() {
+                    self.install_filter = filter;
+                }
+            }
+            ui.separator();
+            ui.checkbox(&mut self.favorites_only, "Favorites only");
+            ui.checkbox(&mut self.favorites_first, "Favorites first");
+            ui.checkbox(&mut self.recent_only, "Recently added");
+            ui.separator();
+            self.genre_filter(ui, games, metadata_handler);
+            self.mode_filter(ui, games, metadata_handler);
             ui.separator();
             ui.label("Search:");
-            ui.text_edit_singleline(&mut self.search_query);
+            let search = ui.add(
+                egui::TextEdit::singleline(&mut self.search_query)
+                    .id(Self::search_id())
+                    .hint_text("Title, developer or publisher"),
+            );
+            if search.changed() {
+                self.last_search_edit = Some(Instant::now());
+                ui.ctx().request_repaint_after(SEARCH_DEBOUNCE);
+            }
             ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                 if ui.button("Refresh All Metadata").clicked() {
                     on_action(LibraryAction::RefreshAll);
                 }
+                if self.failed_refreshes > 0
+                    && ui.button(format!("Retry failed ({})", self.failed_refreshes)).clicked()
+                {
+                    on_action(LibraryAction::RetryFailed);
+                }
             });
         });
         
+        if !self.selected.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{} selected", self.selected.len()));
+                
+                let mut selected: Vec<String> = self.selected.iter().cloned().collect();
+                selected.sort();
+                
+                if ui.button("Refresh selected").clicked() {
+                    on_action(LibraryAction::RefreshSelected(selected.clone()));
+                }
+                if ui.button("Install selected").clicked() {
+                    on_action(LibraryAction::InstallSelected(selected));
+                }
+                if ui.button("Clear selection").clicked() {
+                    self.selected.clear();
+                    self.selection_anchor = None;
+                }
+            });
+        }
+        
         ui.separator();
         
-        let filtered_games: Vec<(usize, &GameInfo)> = games
+        let filtered_games = self.filtered_games(games, installed, metadata_handler);
+        self.handle_keyboard(ui, &filtered_games, &mut on_action);
+        
+        if self.show_banner {
+            if let Some(handler) = metadata_handler {
+                // Feature the first visible game that has hero artwork
+                let featured = filtered_games.iter().find(|(_, game)| handler.has_hero(&game.id));
+                if let Some((index, game)) = featured {
+                    let hero_path = handler.get_hero_path(&game.id);
+                    if self.render_banner(ui, game, handler.display_title(game), &hero_path) {
+                        on_action(LibraryAction::SelectGame(*index));
+                    }
+                    ui.separator();
+                }
+            }
+        }
+        
+        match self.view_mode {
+            ViewMode::Grid if self.group_by != GroupBy::None => {
+                self.show_grouped_grid_view(ui, &filtered_games, installed, metadata_handler, &mut on_action)
+            }
+            ViewMode::Grid => self.show_grid_view(ui, &filtered_games, installed, metadata_handler, &mut on_action),
+            ViewMode::List => self.show_list_view(ui, &filtered_games, installed, metadata_handler, &mut on_action),
+        }
+    }
+    
+    /// Get the IGDB genre names of a game
+    fn game_genres<'a>(game: &GameInfo, metadata_handler: Option<&'a MetadataHandler>) -> impl Iterator<Item = &'a str> {
+        metadata_handler
+            .and_then(|handler| handler.get_metadata(&game.id))
+            .and_then(|metadata| metadata.igdb_data.as_ref())
+            .and_then(|igdb_data| igdb_data.genres.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|genre| genre.name.as_str())
+    }
+    
+    /// Get the IGDB game mode names of a game
+    fn game_modes<'a>(game: &GameInfo, metadata_handler: Option<&'a MetadataHandler>) -> impl Iterator<Item = &'a str> {
+        metadata_handler
+            .and_then(|handler| handler.get_metadata(&game.id))
+            .and_then(|metadata| metadata.igdb_data.as_ref())
+            .and_then(|igdb_data| igdb_data.game_modes.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|mode| mode.name.as_str())
+    }
+    
+    /// Show the genre multi-select, offering every genre found in the library
+    fn genre_filter(&mut self, ui: &mut egui::Ui, games: &[GameInfo], metadata_handler: Option<&MetadataHandler>) {
+        let genres: BTreeSet<&str> = games
+            .iter()
+            .flat_map(|game| Self::game_genres(game, metadata_handler))
+            .collect();
+        
+        Self::multi_select(ui, "genre_filter", ("All genres", "Genres"), genres, &mut self.selected_genres);
+    }
+    
+    /// Show the game mode multi-select, offering every mode found in the library
+    fn mode_filter(&mut self, ui: &mut egui::Ui, games: &[GameInfo], metadata_handler: Option<&MetadataHandler>) {
+        let modes: BTreeSet<&str> = games
+            .iter()
+            .flat_map(|game| Self::game_modes(game, metadata_handler))
+            .collect();
+        
+        Self::multi_select(ui, "mode_filter", ("All modes", "Modes"), modes, &mut self.selected_modes);
+    }
+    
+    /// Show a drop-down of checkboxes, labelled with `labels.0` when nothing is selected
+    /// and with `labels.1` and the selection count otherwise
+    fn multi_select(
+        ui: &mut egui::Ui,
+        id_source: &str,
+        labels: (&str, &str),
+        options: BTreeSet<&str>,
+        selection: &mut BTreeSet<String>,
+    ) {
+        let label = if selection.is_empty() {
+            labels.0.to_string()
+        } else {
+            format!("{} ({})", labels.1, selection.len())
+        };
+        
+        egui::ComboBox::from_id_source(id_source)
+            .selected_text(label)
+            .show_ui(ui, |ui| {
+                if ui.button("Clear").clicked() {
+                    selection.clear();
+                }
+                
+                for option in options {
+                    let mut selected = selection.contains(option);
+                    if ui.checkbox(&mut selected, option).changed() {
+                        if selected {
+                            selection.insert(option.to_string());
+                        } else {
+                            selection.remove(option);
+                        }
+                    }
+                }
+            });
+    }
+    
+    /// Get the games matching the search query and filters, with their indices
+    ///
+    /// The result is reused until the filters or the library change, and the
+    /// search query only applies once typing has paused.
+    pub fn filtered_games<'a>(&mut self, games: &'a [GameInfo], installed: &HashSet<String>, metadata_handler: Option<&MetadataHandler>) -> Vec<(usize, &'a GameInfo)> {
+        let typing = self.last_search_edit.map_or(false, |edited| edited.elapsed() < SEARCH_DEBOUNCE);
+        if !typing && self.debounced_query != self.search_query {
+            self.debounced_query = self.search_query.clone();
+        }
+        
+        let key = FilterKey {
+            query: self.debounced_query.clone(),
+            install_filter: self.install_filter,
+            genres: self.selected_genres.clone(),
+            modes: self.selected_modes.clone(),
+            favorites_only: self.favorites_only,
+            favorites_first: self.favorites_first,
+            favorites: self.favorites.clone(),
+            recent_only: self.recent_only,
+            game_count: games.len(),
+            installed_count: installed.len(),
+        };
+        
+        let indices = match &self.filter_cache {
+            Some((cached_key, indices)) if *cached_key == key => indices.clone(),
+            _ => {
+                let indices: Vec<usize> = self.filter_games(games, installed, metadata_handler)
+                    .into_iter()
+                    .map(|(index, _)| index)
+                    .collect();
+                self.filter_cache = Some((key, indices.clone()));
+                indices
+            }
+        };
+        
+        indices.into_iter().filter_map(|index| games.get(index).map(|game| (index, game))).collect()
+    }
+    
+    /// Filter the games by the debounced search query and the other filters
+    fn filter_games<'a>(&self, games: &'a [GameInfo], installed: &HashSet<String>, metadata_handler: Option<&MetadataHandler>) -> Vec<(usize, &'a GameInfo)> {
+        let query = self.debounced_query.to_lowercase();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let recent_since = now.saturating_sub(RECENT_DAYS * 86400);
+        
+        let mut filtered: Vec<(usize, &GameInfo)> = games
             .iter()
             .enumerate()
+            .filter(|(_, game)| !self.favorites_only || self.favorites.contains(&game.id))
             .filter(|(_, game)| {
-                if self.search_query.is_empty() {
-                    return true;
-                }
-                let query = self.search_query.to_lowercase();
-                let title = game.title.to_lowercase();
-                title.contains(&query)
+                // Games from before tracking started are stamped 0 and never count as recent
+                !self.recent_only || self.first_seen.get(&game.id).map_or(false, |&seen| seen > 0 && seen >= recent_since)
             })
+            .filter(|(_, game)| match self.install_filter {
+                InstallFilter::All => true,
+                InstallFilter::Installed => installed.contains(&game.id),
+                InstallFilter::NotInstalled => !installed.contains(&game.id),
+            })
+            .filter(|(_, game)| {
+                self.selected_genres.is_empty()
+                    || Self::game_genres(game, metadata_handler).any(|genre| self.selected_genres.contains(genre))
+            })
+            .filter(|(_, game)| {
+                self.selected_modes.is_empty()
+                    || Self::game_modes(game, metadata_handler).any(|mode| self.selected_modes.contains(mode))
+            })
+            .filter(|(_, game)| query.is_empty() || Self::matches_search(game, &query, metadata_handler))
             .collect();
         
-        match self.view_mode {
-            ViewMode::Grid => self.show_grid_view(ui, &filtered_games, metadata_handler, &mut on_action),
-            ViewMode::List => self.show_list_view(ui, &filtered_games, metadata_handler, &mut on_action),
+        if self.recent_only {
+            filtered.sort_by_key(|(_, game)| std::cmp::Reverse(self.first_seen.get(&game.id).copied().unwrap_or(0)));
+        }
+        
+        if self.favorites_first {
+            // Stable, so games keep their library order within each group
+            filtered.sort_by_key(|(_, game)| !self.favorites.contains(&game.id));
         }
+        
+        filtered
+    }
+    
+    /// Check if a game's title, developer, publisher or IGDB companies contain `query`
+    ///
+    /// `query` must already be lowercase.
+    fn matches_search(game: &GameInfo, query: &str, metadata_handler: Option<&MetadataHandler>) -> bool {
+        let companies = metadata_handler
+            .and_then(|handler| handler.get_metadata(&game.id))
+            .and_then(|metadata| metadata.igdb_data.as_ref())
+            .and_then(|igdb_data| igdb_data.involved_companies.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|company| company.company.name.as_str());
+        
+        let overrides = metadata_handler
+            .and_then(|handler| handler.user_overrides(&game.id))
+            .into_iter()
+            .flat_map(|overrides| [&overrides.title, &overrides.developer, &overrides.publisher])
+            .flatten()
+            .map(String::as_str);
+        
+        [Some(game.title.as_str()), game.developer.as_deref(), game.publisher.as_deref()]
+            .into_iter()
+            .flatten()
+            .chain(companies)
+            .chain(overrides)
+            .any(|field| field.to_lowercase().contains(query))
+    }
+    
+    /// Get the title to show for a game, the user's own if they entered one
+    fn display_title<'a>(game: &'a GameInfo, metadata_handler: Option<&'a MetadataHandler>) -> &'a str {
+        metadata_handler.map_or(&game.title, |handler| handler.display_title(game))
     }
     
     /// Show grid view
-    fn show_grid_view<F>(&mut self, ui: &mut egui::Ui, games: &[(usize, &GameInfo)], metadata_handler: Option<&MetadataHandler>, on_action: &mut F)
+    ///
+    /// Only the rows inside the visible part of the scroll area are laid out,
+    /// so offscreen covers are never loaded.
+    fn show_grid_view<F>(&mut self, ui: &mut egui::Ui, games: &[(usize, &GameInfo)], installed: &HashSet<String>, metadata_handler: Option<&MetadataHandler>, on_action: &mut F)
     where
         F: FnMut(LibraryAction),
     {
-        const THUMBNAIL_SIZE: f32 = 160.0;
-        const COVER_HEIGHT: f32 = 220.0;
-        const ITEMS_PER_ROW: usize = 4;
+        let (columns, card_height) = self.grid_layout(ui);
+        let row_count = (games.len() + columns - 1) / columns;
         
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            let available_width = ui.available_width();
-            let item_width = (available_width / ITEMS_PER_ROW as f32).min(THUMBNAIL_SIZE + 20.0);
+        let mut scroll_area = egui::ScrollArea::vertical();
+        if self.scroll_to_focused {
+            // The focused card may be in a row that is not laid out yet, so scroll by row
+            if let Some(index) = self.focused_index {
+                let row_top = (index / columns) as f32 * (card_height + GRID_SPACING);
+                let offset = row_top - (ui.available_height() - card_height) / 2.0;
+                scroll_area = scroll_area.vertical_scroll_offset(offset.max(0.0));
+            }
+        }
+        
+        ui.scope(|ui| {
+            ui.spacing_mut().item_spacing = egui::vec2(GRID_SPACING, GRID_SPACING);
             
-            let mut grid = egui::Grid::new("game_grid")
-                .spacing([20.0, 20.0])
-                .min_col_width(item_width)
-                .max_col_width(item_width);
-                
-            grid.show(ui, |ui| {
-                for (i, (original_index, game)) in games.iter().enumerate() {
-                    if i > 0 && i % ITEMS_PER_ROW == 0 {
-                        ui.end_row();
-                    }
-                    
-                    ui.vertical(|ui| {
-                        if let Some(handler) = metadata_handler {
-                            if handler.has_cover(&game.id) {
-                                let cover_path = handler.get_cover_path(&game.id);
-                                self.render_game_cover(ui, &game.id, &cover_path, THUMBNAIL_SIZE, COVER_HEIGHT);
-                            } else {
-                                let cover_rect = egui::Rect::from_min_size(ui.cursor().min, egui::vec2(THUMBNAIL_SIZE, COVER_HEIGHT));
-                                ui.allocate_ui_at_rect(cover_rect, |ui| {
-                                    ui.painter().rect_filled(cover_rect, 4.0, egui::Color32::from_rgb(100, 100, 200));
-                                    ui.centered_and_justified(|ui| {
-                                        ui.label(&game.title);
-                                    });
-                                });
-                            }
-                        } else {
-                            let cover_rect = egui::Rect::from_min_size(ui.cursor().min, egui::vec2(THUMBNAIL_SIZE, COVER_HEIGHT));
-                            ui.allocate_ui_at_rect(cover_rect, |ui| {
-                                ui.painter().rect_filled(cover_rect, 4.0, egui::Color32::from_rgb(100, 100, 200));
-                                ui.centered_and_justified(|ui| {
-                                    ui.label(&game.title);
-                                });
-                            });
-                        }
-                        
-                        let title = if game.title.len() > 20 {
-                            format!("{}...", &game.title[..17])
-                        } else {
-                            game.title.clone()
-                        };
+            scroll_area.show_rows(ui, card_height, row_count, |ui, row_range| {
+                for row in row_range {
+                    let row_start = row * columns;
+                    let row_end = (row_start + columns).min(games.len());
+                    self.show_grid_row(ui, row_start..row_end, card_height, games, installed, metadata_handler, on_action);
+                }
+            });
+        });
+    }
+    
+    /// Show the grid split into collapsible sections by `group_by`
+    ///
+    /// Rows scrolled out of view only reserve their space, so their covers are not loaded.
+    fn show_grouped_grid_view<F>(&mut self, ui: &mut egui::Ui, games: &[(usize, &GameInfo)], installed: &HashSet<String>, metadata_handler: Option<&MetadataHandler>, on_action: &mut F)
+    where
+        F: FnMut(LibraryAction),
+    {
+        let (columns, card_height) = self.grid_layout(ui);
+        let groups = self.group_games(games, metadata_handler);
+        
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (label, positions) in &groups {
+                egui::CollapsingHeader::new(format!("{} ({})", label, positions.len()))
+                    .id_source(("library_group", label))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        ui.spacing_mut().item_spacing = egui::vec2(GRID_SPACING, GRID_SPACING);
                         
-                        let title_response = ui.button(title);
-                        if title_response.clicked() {
-                            on_action(LibraryAction::SelectGame(*original_index));
+                        for row in positions.chunks(columns) {
+                            let row_rect = egui::Rect::from_min_size(ui.cursor().min, egui::vec2(ui.available_width(), card_height));
+                            if ui.is_rect_visible(row_rect) {
+                                self.show_grid_row(ui, row.iter().copied(), card_height, games, installed, metadata_handler, on_action);
+                                continue;
+                            }
+                            
+                            ui.allocate_exact_size(row_rect.size(), egui::Sense::hover());
+                            if self.scroll_to_focused && self.focused_index.map_or(false, |index| row.contains(&index)) {
+                                ui.scroll_to_rect(row_rect, Some(Align::Center));
+                            }
                         }
-                        
-                        ui.label(format!("{} versions", game.versions.len()));
                     });
+            }
+        });
+    }
+    
+    /// Split the games into labelled sections, with games lacking the grouped metadata last
+    ///
+    /// Returns positions in `games`. A game with several genres, platforms or series
+    /// is listed in each of their sections.
+    fn group_games(&self, games: &[(usize, &GameInfo)], metadata_handler: Option<&MetadataHandler>) -> Vec<(String, Vec<usize>)> {
+        let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        let mut uncategorized = Vec::new();
+        
+        for (position, (_, game)) in games.iter().enumerate() {
+            let igdb_data = metadata_handler
+                .and_then(|handler| handler.get_metadata(&game.id))
+                .and_then(|metadata| metadata.igdb_data.as_ref());
+            
+            let labels: Vec<String> = match (self.group_by, igdb_data) {
+                (GroupBy::None, _) | (_, None) => Vec::new(),
+                (GroupBy::Genre, Some(igdb_data)) => igdb_data.genres.iter()
+                    .flatten()
+                    .map(|genre| genre.name.clone())
+                    .collect(),
+                (GroupBy::Platform, Some(igdb_data)) => igdb_data.platforms.iter()
+                    .flatten()
+                    .map(|platform| platform.name.clone())
+                    .collect(),
+                (GroupBy::Decade, Some(igdb_data)) => igdb_data.release_date()
+                    .map(|date| format!("{}s", date.year() / 10 * 10))
+                    .into_iter()
+                    .collect(),
+                (GroupBy::Series, Some(igdb_data)) => igdb_data.series()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect(),
+            };
+            
+            if labels.is_empty() {
+                uncategorized.push(position);
+            }
+            for label in labels {
+                groups.entry(label).or_default().push(position);
+            }
+        }
+        
+        let mut groups: Vec<(String, Vec<usize>)> = groups.into_iter().collect();
+        if !uncategorized.is_empty() {
+            groups.push(("Uncategorized".to_string(), uncategorized));
+        }
+        groups
+    }
+    
+    /// Work out the number of grid columns that fit and the height of a card
+    fn grid_layout(&mut self, ui: &egui::Ui) -> (usize, f32) {
+        let cover_height = self.cover_width * COVER_ASPECT;
+        let card_height = cover_height + 2.0 * (ui.spacing().interact_size.y + ui.spacing().item_spacing.y);
+        
+        // Fit as many columns as the width allows, leaving room for the scroll bar
+        let grid_width = ui.available_width() - ui.spacing().scroll_bar_width;
+        let columns = (((grid_width + GRID_SPACING) / (self.cover_width + GRID_SPACING)) as usize).max(1);
+        self.grid_columns = columns;
+        
+        (columns, card_height)
+    }
+    
+    /// Show one row of grid cards for the given positions in `games`
+    ///
+    /// Must be called with the grid spacing set; the cards themselves use the default spacing.
+    #[allow(clippy::too_many_arguments)]
+    fn show_grid_row<F>(&mut self, ui: &mut egui::Ui, positions: impl IntoIterator<Item = usize>, card_height: f32, games: &[(usize, &GameInfo)], installed: &HashSet<String>, metadata_handler: Option<&MetadataHandler>, on_action: &mut F)
+    where
+        F: FnMut(LibraryAction),
+    {
+        let card_spacing = ui.ctx().style().spacing.item_spacing;
+        
+        ui.horizontal(|ui| {
+            for i in positions {
+                let (original_index, game) = games[i];
+                
+                let (_, card) = ui.allocate_exact_size(egui::vec2(self.cover_width, card_height), egui::Sense::hover());
+                let mut card_ui = ui.child_ui(card.rect, Layout::top_down(Align::LEFT));
+                card_ui.spacing_mut().item_spacing = card_spacing;
+                self.show_grid_card(&mut card_ui, i, games, installed, metadata_handler, on_action);
+                
+                if self.selected.contains(&game.id) {
+                    ui.painter().rect_stroke(card.rect.expand(4.0), 4.0, ui.visuals().selection.stroke);
                 }
+                self.highlight_focused(ui, i, &card);
+                
+                ui.interact(card.rect, ui.id().with(("card", &game.id)), egui::Sense::click())
+                    .context_menu(|ui| self.game_context_menu(ui, original_index, game, on_action));
+            }
+        });
+    }
+    
+    /// Show the cover, title and install state of the game at `position` in the grid
+    fn show_grid_card<F>(&mut self, ui: &mut egui::Ui, position: usize, games: &[(usize, &GameInfo)], installed: &HashSet<String>, metadata_handler: Option<&MetadataHandler>, on_action: &mut F)
+    where
+        F: FnMut(LibraryAction),
+    {
+        let (_, game) = games[position];
+        let cover_size = egui::vec2(self.cover_width, self.cover_width * COVER_ASPECT);
+        
+        let cover_min = ui.cursor().min;
+        
+        if let Some(handler) = metadata_handler {
+            if handler.has_cover(&game.id) {
+                let cover_path = handler.get_cover_path(&game.id);
+                let rating = handler.get_metadata(&game.id)
+                    .and_then(|metadata| metadata.igdb_data.as_ref())
+                    .and_then(|igdb_data| igdb_data.total_rating);
+                self.render_game_cover(ui, &game.id, &cover_path, cover_size.x, cover_size.y, rating);
+            } else {
+                let cover_rect = egui::Rect::from_min_size(ui.cursor().min, cover_size);
+                ui.allocate_ui_at_rect(cover_rect, |ui| {
+                    ui.painter().rect_filled(cover_rect, 4.0, egui::Color32::from_rgb(100, 100, 200));
+                    ui.centered_and_justified(|ui| {
+                        ui.label(Self::display_title(game, metadata_handler));
+                    });
+                });
+            }
+        } else {
+            let cover_rect = egui::Rect::from_min_size(ui.cursor().min, cover_size);
+            ui.allocate_ui_at_rect(cover_rect, |ui| {
+                ui.painter().rect_filled(cover_rect, 4.0, egui::Color32::from_rgb(100, 100, 200));
+                ui.centered_and_justified(|ui| {
+                    ui.label(Self::display_title(game, metadata_handler));
+                });
             });
+        }
+        
+        let cover_rect = egui::Rect::from_min_size(cover_min, cover_size);
+        if self.favorite_toggle(ui, &game.id, cover_rect) {
+            on_action(LibraryAction::ToggleFavorite(game.id.clone()));
+        }
+        
+        let title = helpers::truncate_chars(Self::display_title(game, metadata_handler), 20);
+        
+        if ui.button(title).clicked() {
+            self.card_clicked(ui, position, games, on_action);
+        }
+        
+        ui.horizontal(|ui| {
+            ui.label(format!("{} versions", game.versions.len()));
+            if installed.contains(&game.id) {
+                Self::installed_badge(ui);
+            }
         });
     }
     
     /// Show list view
-    fn show_list_view<F>(&mut self, ui: &mut egui::Ui, games: &[(usize, &GameInfo)], metadata_handler: Option<&MetadataHandler>, on_action: &mut F)
+    fn show_list_view<F>(&mut self, ui: &mut egui::Ui, games: &[(usize, &GameInfo)], installed: &HashSet<String>, metadata_handler: Option<&MetadataHandler>, on_action: &mut F)
     where
         F: FnMut(LibraryAction),
     {
         egui::ScrollArea::vertical().show(ui, |ui| {
-            for (original_index, game) in games {
-                ui.horizontal(|ui| {
+            for (i, (original_index, game)) in games.iter().enumerate() {
+                let row = ui.horizontal(|ui| {
                     if let Some(handler) = metadata_handler {
                         if handler.has_cover(&game.id) {
                             let cover_path = handler.get_cover_path(&game.id);
-                            self.render_game_cover(ui, &game.id, &cover_path, 60.0, 80.0);
+                            self.render_game_cover(ui, &game.id, &cover_path, 60.0, 80.0, None);
                             ui.add_space(10.0);
                         }
                     }
                     
                     ui.vertical(|ui| {
-                        let response = ui.selectable_label(false, &game.title);
+                        let overrides = metadata_handler.and_then(|handler| handler.user_overrides(&game.id));
+                        let title = Self::display_title(game, metadata_handler);
+                        let response = ui.selectable_label(self.selected.contains(&game.id), title);
                         if response.clicked() {
-                            on_action(LibraryAction::SelectGame(*original_index));
+                            self.card_clicked(ui, i, games, on_action);
                         }
                         
                         ui.horizontal(|ui| {
-                            if let Some(developer) = &game.developer {
+                            let developer = overrides.and_then(|o| o.developer.as_ref()).or(game.developer.as_ref());
+                            if let Some(developer) = developer {
                                 ui.label(developer);
                                 ui.separator();
                             }
-                            if let Some(release_date) = &game.release_date {
+                            let release_date = overrides.and_then(|o| o.release_date.as_ref()).or(game.release_date.as_ref());
+                            if let Some(release_date) = release_date {
                                 ui.label(release_date);
                                 ui.separator();
                             }
-                            ui.label(format!("{} versions", game.versions.len()));
+                            ui.label(format!("{} versions", game.versions.len()))
+                                .on_hover_text(format!("Total size of all versions: {}", helpers::format_bytes(game.versions_size())));
+                            if self.show_sources && !game.source.is_empty() {
+                                ui.separator();
+                                ui.label(egui::RichText::new(&game.source).weak());
+                            }
+                            if installed.contains(&game.id) {
+                                ui.separator();
+                                Self::installed_badge(ui);
+                            }
                         });
                     });
-                });
+                }).response;
+                
+                self.highlight_focused(ui, i, &row);
+                
+                ui.interact(row.rect, ui.id().with(("row", &game.id)), egui::Sense::click())
+                    .context_menu(|ui| self.game_context_menu(ui, *original_index, game, on_action));
                 ui.separator();
             }
         });
     }
     
-    /// Render game cover using the helper function
-    fn render_game_cover(&mut self, ui: &mut egui::Ui, game_id: &str, path: &PathBuf, width: f32, height: f32) {
-        if !self.cover_textures.contains_key(game_id) {
-            let texture = helpers::load_texture_from_path(ui.ctx(), path, &format!("game_cover_{}", game_id));
-            self.cover_textures.insert(game_id.to_string(), texture);
+    /// ID of the search text field, so `/` can focus it
+    fn search_id() -> egui::Id {
+        egui::Id::new("library_search")
+    }
+    
+    /// Move the keyboard focus with the arrow keys and open the focused game with Enter
+    ///
+    /// Nothing is handled while a text field, such as the search box, has focus.
+    fn handle_keyboard<F>(&mut self, ui: &egui::Ui, games: &[(usize, &GameInfo)], on_action: &mut F)
+    where
+        F: FnMut(LibraryAction),
+    {
+        if ui.ctx().wants_keyboard_input() {
+            return;
+        }
+        
+        let slash = ui.input(|input| {
+            input.events.iter().any(|event| matches!(event, egui::Event::Text(text) if text == "/"))
+        });
+        if slash {
+            ui.memory_mut(|memory| memory.request_focus(Self::search_id()));
+            return;
+        }
+        
+        if games.is_empty() {
+            self.focused_index = None;
+            return;
+        }
+        
+        let (step_x, step_y) = match self.view_mode {
+            ViewMode::Grid => (1, self.grid_columns),
+            ViewMode::List => (0, 1),
+        };
+        let last = games.len() - 1;
+        let current = self.focused_index.map(|index| index.min(last));
+        
+        let (left, right, up, down, enter) = ui.input_mut(|input| (
+            input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowLeft),
+            input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowRight),
+            input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            input.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+        ));
+        
+        let moved = match current {
+            // The first arrow press focuses the first game
+            None if left || right || up || down => Some(0),
+            None => None,
+            Some(index) if left => Some(index.saturating_sub(step_x)),
+            Some(index) if right => Some((index + step_x).min(last)),
+            Some(index) if up => Some(index.saturating_sub(step_y)),
+            Some(index) if down => Some((index + step_y).min(last)),
+            Some(_) => None,
+        };
+        
+        if let Some(index) = moved {
+            self.focused_index = Some(index);
+            self.scroll_to_focused = true;
+        } else {
+            self.focused_index = current;
+        }
+        
+        if enter {
+            if let Some((original_index, _)) = self.focused_index.and_then(|index| games.get(index)) {
+                on_action(LibraryAction::SelectGame(*original_index));
+            }
+        }
+    }
+    
+    /// Outline the card at `position` if it has keyboard focus, scrolling to it after a move
+    fn highlight_focused(&mut self, ui: &egui::Ui, position: usize, card: &egui::Response) {
+        if self.focused_index != Some(position) {
+            return;
+        }
+        
+        ui.painter().rect_stroke(card.rect.expand(6.0), 4.0, ui.visuals().widgets.active.fg_stroke);
+        
+        if self.scroll_to_focused {
+            card.scroll_to_me(Some(Align::Center));
+            self.scroll_to_focused = false;
+        }
+    }
+    
+    /// Handle a click on the game at `position` in the filtered list
+    ///
+    /// Ctrl-click toggles the game in the selection and Shift-click selects
+    /// the range from the last Ctrl-clicked game; a plain click opens the game.
+    fn card_clicked<F>(&mut self, ui: &egui::Ui, position: usize, games: &[(usize, &GameInfo)], on_action: &mut F)
+    where
+        F: FnMut(LibraryAction),
+    {
+        let modifiers = ui.input(|input| input.modifiers);
+        let Some((original_index, game)) = games.get(position) else {
+            return;
+        };
+        
+        if modifiers.shift {
+            if let Some(anchor) = self.selection_anchor {
+                let range = anchor.min(position)..=anchor.max(position);
+                let ids = games.get(range).unwrap_or_default().iter().map(|(_, game)| game.id.clone());
+                self.selected.extend(ids);
+                return;
+            }
+        }
+        
+        if modifiers.command || modifiers.shift {
+            if !self.selected.remove(&game.id) {
+                self.selected.insert(game.id.clone());
+            }
+            self.selection_anchor = Some(position);
+            return;
+        }
+        
+        on_action(LibraryAction::SelectGame(*original_index));
+    }
+    
+    /// Show the right-click menu of a library card
+    fn game_context_menu<F>(&self, ui: &mut egui::Ui, index: usize, game: &GameInfo, on_action: &mut F)
+    where
+        F: FnMut(LibraryAction),
+    {
+        let mut action = None;
+        
+        if ui.button("Open").clicked() {
+            action = Some(LibraryAction::SelectGame(index));
+        }
+        if ui.button("Refresh metadata").clicked() {
+            action = Some(LibraryAction::RefreshGame(index));
+        }
+        if ui.add_enabled(!game.versions.is_empty(), egui::Button::new("Install latest")).clicked() {
+            action = Some(LibraryAction::InstallLatest(index));
+        }
+        
+        let favorite_label = if self.favorites.contains(&game.id) {
+            "Remove from favorites"
+        } else {
+            "Add to favorites"
+        };
+        if ui.button(favorite_label).clicked() {
+            action = Some(LibraryAction::ToggleFavorite(game.id.clone()));
+        }
+        
+        if ui.button("Clear cached metadata").clicked() {
+            action = Some(LibraryAction::ClearMetadata(index));
+        }
+        
+        if let Some(action) = action {
+            on_action(action);
+            ui.close_menu();
+        }
+    }
+    
+    /// Paint the favorite star in the top-right corner of a cover, returning true when it was clicked
+    ///
+    /// The star has its own click sense, so clicking it never selects the game.
+    fn favorite_toggle(&self, ui: &mut egui::Ui, game_id: &str, cover_rect: egui::Rect) -> bool {
+        let star_rect = egui::Rect::from_min_size(
+            egui::pos2(cover_rect.right() - 28.0, cover_rect.top() + 4.0),
+            egui::vec2(24.0, 24.0),
+        );
+        let response = ui.interact(star_rect, ui.id().with(("favorite", game_id)), egui::Sense::click());
+        
+        let is_favorite = self.favorites.contains(game_id);
+        if !is_favorite && !response.hovered() && !ui.rect_contains_pointer(cover_rect) {
+            return false;
+        }
+        
+        ui.painter().circle_filled(star_rect.center(), 12.0, egui::Color32::from_black_alpha(140));
+        ui.painter().text(
+            star_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            if is_favorite { "★" } else { "☆" },
+            egui::FontId::proportional(16.0),
+            egui::Color32::GOLD,
+        );
+        
+        response.on_hover_text("Toggle favorite").clicked()
+    }
+    
+    /// Render the "Installed" badge
+    fn installed_badge(ui: &mut egui::Ui) {
+        ui.label(
+            egui::RichText::new("Installed")
+                .small()
+                .color(egui::Color32::WHITE)
+                .background_color(egui::Color32::from_rgb(40, 140, 60)),
+        );
+    }
+    
+    /// Render game cover using the helper function, with the IGDB rating in the corner if given
+    fn render_game_cover(&mut self, ui: &mut egui::Ui, game_id: &str, path: &PathBuf, width: f32, height: f32, rating: Option<f32>) {
+        if !self.cover_textures.contains(game_id) {
+            if let Some(texture) = self.images.texture(ui.ctx(), path, &format!("game_cover_{}", game_id)) {
+                self.cover_textures.insert(game_id.to_string(), texture);
+            }
         }
         
         let cover_rect = egui::Rect::from_min_size(ui.cursor().min, egui::vec2(width, height));
         ui.allocate_rect(cover_rect, egui::Sense::click());
         
-        if let Some(Some(texture)) = self.cover_textures.get(game_id) {
-            ui.painter().image(
-                texture.id(),
-                cover_rect,
-                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                egui::Color32::WHITE,
-            );
-        } else {
+        let placeholder = match self.cover_textures.get(game_id) {
+            Some(Some(texture)) => {
+                ui.painter().image(
+                    texture.id(),
+                    cover_rect,
+                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    egui::Color32::WHITE,
+                );
+                None
+            }
+            Some(None) => Some("No Cover"),
+            None => Some("Loading..."),
+        };
+        
+        if let Some(placeholder) = placeholder {
             ui.painter().rect_filled(cover_rect, 4.0, egui::Color32::from_rgb(100, 100, 200));
             ui.painter().text(
                 cover_rect.center(),
                 egui::Align2::CENTER_CENTER,
-                "No Cover",
+                placeholder,
                 egui::FontId::default(),
                 egui::Color32::WHITE,
             );
         }
+        
+        if let Some(rating) = rating {
+            Self::rating_badge(ui, cover_rect, rating);
+        }
+    }
+    
+    /// Paint the IGDB rating as a pill in the top-left corner of a cover
+    fn rating_badge(ui: &egui::Ui, cover_rect: egui::Rect, rating: f32) {
+        let color = if rating >= 75.0 {
+            egui::Color32::from_rgb(40, 140, 60)
+        } else if rating >= 50.0 {
+            egui::Color32::from_rgb(190, 150, 20)
+        } else {
+            egui::Color32::from_rgb(170, 50, 50)
+        };
+        
+        let galley = ui.painter().layout_no_wrap(
+            format!("{:.0}", rating),
+            egui::FontId::proportional(13.0),
+            egui::Color32::WHITE,
+        );
+        let badge_rect = egui::Rect::from_min_size(
+            cover_rect.left_top() + egui::vec2(4.0, 4.0),
+            galley.size() + egui::vec2(12.0, 4.0),
+        );
+        
+        // A dark outline keeps the pill readable on covers of the same color
+        ui.painter().rect(badge_rect, badge_rect.height() / 2.0, color, egui::Stroke::new(1.0, egui::Color32::from_black_alpha(160)));
+        ui.painter().galley(badge_rect.min + egui::vec2(6.0, 2.0), galley);
+    }
+    
+    /// Render the featured game banner, returning true when it was clicked
+    fn render_banner(&mut self, ui: &mut egui::Ui, game: &GameInfo, title: &str, path: &PathBuf) -> bool {
+        if !self.hero_textures.contains_key(&game.id) {
+            if let Some(texture) = self.images.texture(ui.ctx(), path, &format!("game_hero_{}", game.id)) {
+                self.hero_textures.insert(game.id.clone(), texture);
+            }
+        }
+        
+        let Some(Some(texture)) = self.hero_textures.get(&game.id) else {
+            return false;
+        };
+        
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 140.0), egui::Sense::click());
+        helpers::paint_texture_fill(ui.painter(), texture, rect);
+        
+        let shade = egui::Rect::from_min_max(egui::pos2(rect.left(), rect.bottom() - 36.0), rect.max);
+        ui.painter().rect_filled(shade, 0.0, egui::Color32::from_black_alpha(160));
+        ui.painter().text(
+            egui::pos2(rect.left() + 10.0, rect.bottom() - 18.0),
+            egui::Align2::LEFT_CENTER,
+            title,
+            egui::TextStyle::Heading.resolve(ui.style()),
+            egui::Color32::WHITE,
+        );
+        
+        response.on_hover_cursor(egui::CursorIcon::PointingHand).clicked()
+    }
+    
+    /// Set the IDs of the games marked as favorite
+    pub fn set_favorites(&mut self, favorites: HashSet<String>) {
+        self.favorites = favorites;
+    }
+    
+    /// Set when each game first appeared in the repository
+    pub fn set_first_seen(&mut self, first_seen: HashMap<String, u64>) {
+        self.first_seen = first_seen;
+        self.invalidate_filter();
+    }
+    
+    /// Set whether the repository of each game is shown
+    pub fn set_show_sources(&mut self, show_sources: bool) {
+        self.show_sources = show_sources;
+    }
+    
+    /// Set the width of the covers in the grid view
+    pub fn set_cover_width(&mut self, width: f32) {
+        self.cover_width = width.clamp(*COVER_WIDTH_RANGE.start(), *COVER_WIDTH_RANGE.end());
+    }
+    
+    /// Set how many cover textures are kept before the least recently drawn are dropped
+    pub fn set_max_cover_textures(&mut self, max: usize) {
+        self.cover_textures.set_capacity(max.max(MIN_COVER_TEXTURES));
+    }
+    
+    /// Set how the grid view is split into sections
+    pub fn set_group_by(&mut self, group_by: GroupBy) {
+        self.group_by = group_by;
+    }
+    
+    /// Set how many games failed in the last metadata refresh
+    pub fn set_failed_refreshes(&mut self, count: usize) {
+        self.failed_refreshes = count;
+    }
+    
+    /// Recompute the filtered game list on the next frame, e.g. after metadata changed
+    pub fn invalidate_filter(&mut self) {
+        self.filter_cache = None;
     }
     
     /// Clear cover texture cache
+    ///
+    /// Also drops the filtered game list, since the metadata it was built
+    /// from has changed.
     pub fn clear_texture_cache(&mut self) {
         self.cover_textures.clear();
+        self.hero_textures.clear();
+        self.invalidate_filter();
     }
 }
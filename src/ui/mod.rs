@@ -3,6 +3,8 @@ pub mod game_detail;
 pub mod library_view;
 pub mod settings;
 pub mod helpers; // Add this line to include helpers.rs
+pub mod image_loader;
+pub mod install_panel;
 
 pub use library_view::LibraryAction;
 pub use game_detail::GameAction;
\ No newline at end of file
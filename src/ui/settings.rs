@@ -2,7 +2,52 @@ use eframe::egui;
 use log::info;
 use std::path::PathBuf;
 
-use crate::config::Config;
+use crate::config::{Config, IgdbConfig, RepositoryConfig, Theme};
+use crate::metadata::CacheStats;
+use crate::ui::helpers;
+
+/// Settings category shown in the settings view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingsTab {
+    Repository,
+    Paths,
+    Igdb,
+    Metadata,
+    Storage,
+    Appearance,
+}
+
+/// Directory that can be chosen with the folder picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSetting {
+    Install,
+    Cache,
+    Temp,
+}
+
+/// Settings view action
+pub enum SettingsAction {
+    /// Apply and persist the edited configuration
+    Save(Config),
+    /// Leave the settings without applying the edits
+    Cancel,
+    /// Open the folder picker for a directory, starting at its current value
+    PickFolder(PathSetting, PathBuf),
+    /// Try to list the repository with the edited settings
+    TestConnection(RepositoryConfig),
+    /// Search IGDB with the saved credentials
+    TestIgdb,
+    /// Authenticate with the edited IGDB credentials
+    ValidateIgdb(IgdbConfig),
+    /// Delete all cached metadata and images
+    ClearCache,
+    /// Write all cached metadata to a file, optionally with the covers
+    ExportMetadata(PathBuf, bool),
+    /// Restore cached metadata from a file
+    ImportMetadata(PathBuf),
+    /// Show the log directory in the file manager
+    OpenLogFolder,
+}
 
 /// Settings view
 pub struct SettingsView {
@@ -10,8 +55,30 @@ pub struct SettingsView {
     config: Config,
     /// Edited configuration
     edited_config: Config,
-    /// Save button clicked
-    save_clicked: bool,
+    /// Selected settings category
+    tab: SettingsTab,
+    /// Whether a repository connection test is running
+    is_testing_connection: bool,
+    /// Result of the last repository connection test (directory count or error)
+    connection_test_result: Option<Result<usize, String>>,
+    /// Whether the IGDB credentials are being checked
+    is_validating_igdb: bool,
+    /// Result of the last IGDB credential check
+    igdb_validation_result: Option<Result<(), String>>,
+    /// Disk usage of the metadata cache
+    cache_stats: Option<CacheStats>,
+    /// Whether clearing the metadata cache is waiting for confirmation
+    confirm_clear_cache: bool,
+    /// Whether a metadata refresh is running, which blocks clearing and importing
+    is_refreshing: bool,
+    /// File metadata is exported to and imported from
+    metadata_bundle_path: String,
+    /// Whether exports include the cover images
+    export_include_covers: bool,
+    /// Result of the last metadata export or import
+    metadata_transfer_result: Option<Result<String, String>>,
+    /// Why the last save was rejected
+    save_error: Option<String>,
 }
 
 impl SettingsView {
@@ -20,109 +87,254 @@ impl SettingsView {
         Self {
             config: config.clone(),
             edited_config: config,
-            save_clicked: false,
+            tab: SettingsTab::Repository,
+            is_testing_connection: false,
+            connection_test_result: None,
+            is_validating_igdb: false,
+            igdb_validation_result: None,
+            cache_stats: None,
+            confirm_clear_cache: false,
+            is_refreshing: false,
+            metadata_bundle_path: dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("game-library-metadata.zip")
+                .to_string_lossy()
+                .to_string(),
+            export_include_covers: true,
+            metadata_transfer_result: None,
+            save_error: None,
         }
     }
     
+    /// Switch to the IGDB credentials
+    pub fn show_igdb_tab(&mut self) {
+        self.tab = SettingsTab::Igdb;
+    }
+    
+    /// Set the state of the repository connection test
+    pub fn set_connection_test(&mut self, is_testing: bool, result: Option<Result<usize, String>>) {
+        self.is_testing_connection = is_testing;
+        self.connection_test_result = result;
+    }
+    
+    /// Set the state of the IGDB credential check
+    pub fn set_igdb_validation(&mut self, is_validating: bool, result: Option<Result<(), String>>) {
+        self.is_validating_igdb = is_validating;
+        self.igdb_validation_result = result;
+    }
+    
+    /// Set the disk usage of the metadata cache
+    pub fn set_cache_stats(&mut self, stats: Option<CacheStats>) {
+        self.cache_stats = stats;
+    }
+    
+    /// Set whether a metadata refresh is running
+    pub fn set_refreshing(&mut self, is_refreshing: bool) {
+        self.is_refreshing = is_refreshing;
+    }
+    
+    /// Set the result of the last metadata export or import
+    pub fn set_transfer_result(&mut self, result: Option<Result<String, String>>) {
+        self.metadata_transfer_result = result;
+    }
+    
+    /// Set a directory chosen in the folder picker
+    pub fn set_picked_folder(&mut self, setting: PathSetting, path: PathBuf) {
+        *self.path_mut(setting) = path;
+    }
+    
+    /// Set why the last save was rejected
+    pub fn set_save_error(&mut self, error: Option<String>) {
+        self.save_error = error;
+    }
+    
     /// Show the settings view
-    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<Config> {
-        self.save_clicked = false;
-        
+    pub fn show<F>(&mut self, ui: &mut egui::Ui, mut on_action: F)
+    where
+        F: FnMut(SettingsAction),
+    {
         ui.heading("Settings");
+        
+        let env_overrides = self.config.env_overrides();
+        if !env_overrides.is_empty() {
+            ui.label(egui::RichText::new(format!(
+                "Overridden by environment variables and not saved: {}",
+                env_overrides.join(", ")
+            )).color(egui::Color32::YELLOW));
+        }
+        
         ui.separator();
         
         // Create tabs for different settings categories
         egui::TopBottomPanel::top("settings_tabs").show_inside(ui, |ui| {
             ui.horizontal(|ui| {
-                ui.selectable_label(true, "Repository");
-                ui.selectable_label(false, "Paths");
-                ui.selectable_label(false, "IGDB API");
+                ui.selectable_value(&mut self.tab, SettingsTab::Repository, "Repository");
+                ui.selectable_value(&mut self.tab, SettingsTab::Paths, "Paths");
+                ui.selectable_value(&mut self.tab, SettingsTab::Igdb, "IGDB API");
+                ui.selectable_value(&mut self.tab, SettingsTab::Metadata, "Metadata");
+                ui.selectable_value(&mut self.tab, SettingsTab::Storage, "Storage");
+                ui.selectable_value(&mut self.tab, SettingsTab::Appearance, "Appearance");
+            });
+        });
+        
+        // Save and cancel buttons
+        egui::TopBottomPanel::bottom("settings_buttons").show_inside(ui, |ui| {
+            ui.horizontal(|ui| {
+                let has_changes = self.edited_config != self.config;
+                
+                if ui.add_enabled(has_changes, egui::Button::new("Save")).clicked() {
+                    info!("Saving configuration");
+                    self.save_error = None;
+                    on_action(SettingsAction::Save(self.edited_config.clone()));
+                }
+                
+                if ui.button("Cancel").clicked() {
+                    self.edited_config = self.config.clone();
+                    on_action(SettingsAction::Cancel);
+                }
+                
+                if let Some(error) = &self.save_error {
+                    ui.label(egui::RichText::new(error).color(egui::Color32::RED));
+                }
             });
         });
         
-        // Repository settings
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            match self.tab {
+                SettingsTab::Repository => self.show_repository(ui, &mut on_action),
+                SettingsTab::Paths => self.show_paths(ui, &mut on_action),
+                SettingsTab::Igdb => self.show_igdb(ui, &mut on_action),
+                SettingsTab::Metadata => self.show_metadata(ui),
+                SettingsTab::Storage => self.show_storage(ui, &mut on_action),
+                SettingsTab::Appearance => self.show_appearance(ui),
+            }
+        });
+    }
+    
+    /// Show the repository settings
+    fn show_repository(&mut self, ui: &mut egui::Ui, on_action: &mut impl FnMut(SettingsAction)) {
         ui.heading("Repository Settings");
         ui.separator();
         
+        ui.checkbox(&mut self.edited_config.offline, "Offline mode")
+            .on_hover_text("Show the cached library and metadata without contacting the repository or IGDB");
+        
+        ui.separator();
+        
+        self.show_repository_profiles(ui);
+        
+        ui.separator();
+        
+        let repository = self.edited_config.repository_mut();
+        
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            ui.text_edit_singleline(&mut repository.name);
+        });
+        
+        ui.checkbox(&mut repository.enabled, "List games from this repository");
+        
         ui.horizontal(|ui| {
             ui.label("Server:");
-            ui.text_edit_singleline(&mut self.edited_config.repository.server);
+            ui.text_edit_singleline(&mut repository.server);
         });
         
         ui.horizontal(|ui| {
             ui.label("Share:");
-            ui.text_edit_singleline(&mut self.edited_config.repository.share);
+            ui.text_edit_singleline(&mut repository.share);
         });
         
         ui.horizontal(|ui| {
             ui.label("Username:");
-            ui.text_edit_singleline(&mut self.edited_config.repository.username);
+            ui.text_edit_singleline(&mut repository.username);
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("Domain:");
+            ui.text_edit_singleline(&mut repository.domain);
         });
         
         ui.horizontal(|ui| {
             ui.label("Password:");
-            ui.add(egui::TextEdit::singleline(&mut self.edited_config.repository.password)
+            ui.add(egui::TextEdit::singleline(&mut repository.password)
                 .password(true));
         });
         
+        if repository.password.is_empty() && !repository.username.is_empty() {
+            ui.label(egui::RichText::new("No password stored in the system keyring. Enter it and save to store it.")
+                .color(egui::Color32::YELLOW));
+        }
+        
         ui.horizontal(|ui| {
             ui.label("Base Directory:");
-            ui.text_edit_singleline(&mut self.edited_config.repository.base_dir);
+            ui.text_edit_singleline(&mut repository.base_dir);
         });
         
-        ui.separator();
-        
-        // Path settings
-        ui.heading("Path Settings");
-        ui.separator();
-        
-        // Replace the path_setting method calls with inline code to avoid multiple mutable borrows
-        
-        // Install Directory
         ui.horizontal(|ui| {
-            ui.label("Install Directory:");
-            
-            let mut path_str = self.edited_config.paths.install_dir.to_string_lossy().to_string();
-            if ui.text_edit_singleline(&mut path_str).changed() {
-                self.edited_config.paths.install_dir = PathBuf::from(path_str);
+            if ui.add_enabled(!self.is_testing_connection, egui::Button::new("Test Connection")).clicked() {
+                on_action(SettingsAction::TestConnection(repository.clone()));
             }
             
-            if ui.button("Browse").clicked() {
-                info!("Browse button clicked for Install Directory");
+            if self.is_testing_connection {
+                ui.spinner();
             }
         });
         
-        // Cache Directory
-        ui.horizontal(|ui| {
-            ui.label("Cache Directory:");
-            
-            let mut path_str = self.edited_config.paths.cache_dir.to_string_lossy().to_string();
-            if ui.text_edit_singleline(&mut path_str).changed() {
-                self.edited_config.paths.cache_dir = PathBuf::from(path_str);
+        match &self.connection_test_result {
+            Some(Ok(count)) => {
+                ui.label(egui::RichText::new(format!("Connection successful: found {} directories", count))
+                    .color(egui::Color32::GREEN));
             }
-            
-            if ui.button("Browse").clicked() {
-                info!("Browse button clicked for Cache Directory");
+            Some(Err(error)) => {
+                ui.label(egui::RichText::new(format!("Connection failed: {}", error))
+                    .color(egui::Color32::RED));
             }
-        });
+            None => {}
+        }
+    }
+    
+    /// Show the repository profile selector with buttons to add and remove profiles
+    fn show_repository_profiles(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.edited_config;
         
-        // Temp Directory
         ui.horizontal(|ui| {
-            ui.label("Temp Directory:");
+            ui.label("Profile:");
+            egui::ComboBox::from_id_source("settings_repository_profile")
+                .selected_text(&config.repository().name)
+                .show_ui(ui, |ui| {
+                    for (i, repository) in config.repositories.iter().enumerate() {
+                        ui.selectable_value(&mut config.active_repository, i, &repository.name);
+                    }
+                });
             
-            let mut path_str = self.edited_config.paths.temp_dir.to_string_lossy().to_string();
-            if ui.text_edit_singleline(&mut path_str).changed() {
-                self.edited_config.paths.temp_dir = PathBuf::from(path_str);
+            if ui.button("Add").clicked() {
+                config.repositories.push(RepositoryConfig {
+                    name: format!("Repository {}", config.repositories.len() + 1),
+                    ..RepositoryConfig::default()
+                });
+                config.active_repository = config.repositories.len() - 1;
             }
             
-            if ui.button("Browse").clicked() {
-                info!("Browse button clicked for Temp Directory");
+            let can_remove = config.repositories.len() > 1;
+            if ui.add_enabled(can_remove, egui::Button::new("Remove")).clicked() {
+                config.repositories.remove(config.active_repository);
+                config.active_repository = config.active_repository.min(config.repositories.len() - 1);
             }
         });
-        
+    }
+    
+    /// Show the local path settings
+    fn show_paths(&mut self, ui: &mut egui::Ui, on_action: &mut impl FnMut(SettingsAction)) {
+        ui.heading("Path Settings");
         ui.separator();
         
-        // IGDB API settings
+        self.path_setting(ui, "Install Directory:", PathSetting::Install, on_action);
+        self.path_setting(ui, "Cache Directory:", PathSetting::Cache, on_action);
+        self.path_setting(ui, "Temp Directory:", PathSetting::Temp, on_action);
+    }
+    
+    /// Show the IGDB API settings
+    fn show_igdb(&mut self, ui: &mut egui::Ui, on_action: &mut impl FnMut(SettingsAction)) {
         ui.heading("IGDB API Settings");
         ui.separator();
         
@@ -137,40 +349,200 @@ impl SettingsView {
                 .password(true));
         });
         
+        ui.horizontal(|ui| {
+            let igdb = &self.edited_config.igdb;
+            let can_validate = !self.is_validating_igdb
+                && !igdb.client_id.is_empty()
+                && !igdb.client_secret.is_empty();
+            
+            if ui.add_enabled(can_validate, egui::Button::new("Validate"))
+                .on_hover_text("Checks the Client ID and Secret with Twitch")
+                .clicked()
+            {
+                on_action(SettingsAction::ValidateIgdb(self.edited_config.igdb.clone()));
+            }
+            
+            if self.is_validating_igdb {
+                ui.spinner();
+            }
+            
+            match &self.igdb_validation_result {
+                Some(Ok(())) => {
+                    ui.label(egui::RichText::new("✔ Credentials are valid").color(egui::Color32::GREEN));
+                }
+                Some(Err(error)) => {
+                    ui.label(egui::RichText::new(error).color(egui::Color32::RED));
+                }
+                None => {}
+            }
+        });
+        
         ui.separator();
         
-        // Save and cancel buttons
-        let mut return_value = None;
+        ui.heading("Network");
+        ui.separator();
         
         ui.horizontal(|ui| {
-            if ui.button("Save").clicked() {
-                self.save_clicked = true;
-                info!("Saving configuration");
+            ui.label("HTTP proxy:");
+            ui.add(egui::TextEdit::singleline(&mut self.edited_config.igdb.http_proxy)
+                .hint_text("http://proxy:8080"))
+                .on_hover_text("Used for IGDB, cover and screenshot requests. Leave empty to use the system settings");
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("Timeout:");
+            ui.add(egui::DragValue::new(&mut self.edited_config.igdb.http_timeout_secs)
+                .clamp_range(0..=600)
+                .suffix(" s"))
+                .on_hover_text("Connect and request timeout, 0 keeps the defaults");
+        });
+        
+        ui.separator();
+        
+        if ui.button("Test IGDB Connection").on_hover_text("Searches IGDB with the saved credentials").clicked() {
+            on_action(SettingsAction::TestIgdb);
+        }
+    }
+    
+    /// Show the metadata settings
+    fn show_metadata(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Metadata Settings");
+        ui.separator();
+        
+        ui.horizontal(|ui| {
+            ui.label("Refresh metadata older than:");
+            ui.add(egui::DragValue::new(&mut self.edited_config.metadata.stale_days)
+                .clamp_range(0..=36500)
+                .suffix(" days"));
+        });
+    }
+    
+    /// Show the metadata cache usage and maintenance actions
+    fn show_storage(&mut self, ui: &mut egui::Ui, on_action: &mut impl FnMut(SettingsAction)) {
+        ui.heading("Storage");
+        ui.separator();
+        
+        match &self.cache_stats {
+            Some(stats) => {
+                ui.label(format!(
+                    "Metadata: {} games, {}",
+                    stats.game_count,
+                    helpers::format_bytes(stats.metadata_bytes)
+                ));
+                ui.label(format!(
+                    "Images: {} files, {}",
+                    stats.image_count,
+                    helpers::format_bytes(stats.image_bytes)
+                ));
+            }
+            None => {
+                ui.label("Cache usage unavailable");
             }
+        }
+        
+        if self.confirm_clear_cache {
+            ui.horizontal(|ui| {
+                ui.label("Delete all cached metadata and images?");
+                if ui.button("Yes").clicked() {
+                    self.confirm_clear_cache = false;
+                    on_action(SettingsAction::ClearCache);
+                }
+                if ui.button("Cancel").clicked() {
+                    self.confirm_clear_cache = false;
+                }
+            });
+        } else if ui.add_enabled(!self.is_refreshing, egui::Button::new("Clear metadata cache")).clicked() {
+            self.confirm_clear_cache = true;
+        }
+        
+        ui.separator();
+        
+        ui.horizontal(|ui| {
+            ui.label("Export file:");
+            ui.text_edit_singleline(&mut self.metadata_bundle_path);
+        });
+        
+        ui.checkbox(&mut self.export_include_covers, "Include cover images");
+        
+        ui.horizontal(|ui| {
+            let path = PathBuf::from(self.metadata_bundle_path.trim());
             
-            if ui.button("Cancel").clicked() {
-                self.edited_config = self.config.clone();
-                return_value = Some(self.config.clone());
+            if ui.button("Export metadata").clicked() {
+                on_action(SettingsAction::ExportMetadata(path.clone(), self.export_include_covers));
+            }
+            if ui.add_enabled(!self.is_refreshing, egui::Button::new("Import metadata")).clicked() {
+                on_action(SettingsAction::ImportMetadata(path));
             }
         });
         
-        // If cancel was clicked, return immediately
-        if return_value.is_some() {
-            return return_value;
+        match &self.metadata_transfer_result {
+            Some(Ok(message)) => {
+                ui.label(egui::RichText::new(message).color(egui::Color32::GREEN));
+            }
+            Some(Err(error)) => {
+                ui.label(egui::RichText::new(error).color(egui::Color32::RED));
+            }
+            None => {}
         }
         
-        // Return config if save was clicked
-        if self.save_clicked {
-            Some(self.edited_config.clone())
-        } else {
-            None
+        ui.separator();
+        
+        ui.heading("Logs");
+        ui.separator();
+        
+        let logging = &mut self.edited_config.logging;
+        
+        ui.horizontal(|ui| {
+            ui.label("Log level:");
+            egui::ComboBox::from_id_source("settings_log_level")
+                .selected_text(logging.level.as_str())
+                .show_ui(ui, |ui| {
+                    for level in ["error", "warn", "info", "debug", "trace"] {
+                        ui.selectable_value(&mut logging.level, level.to_string(), level);
+                    }
+                });
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("Rotate log file at:");
+            ui.add(egui::DragValue::new(&mut logging.max_file_size_mb)
+                .clamp_range(1..=1024)
+                .suffix(" MB"));
+            ui.label("keeping");
+            ui.add(egui::DragValue::new(&mut logging.max_files)
+                .clamp_range(0..=20)
+                .suffix(" old files"));
+        });
+        
+        if ui.button("Open log folder").clicked() {
+            on_action(SettingsAction::OpenLogFolder);
         }
     }
     
-    // We're not using this method anymore, but keeping it for reference
-    // Instead, we've inlined the code directly in the show method
-    #[allow(dead_code)]
-    fn path_setting(&mut self, ui: &mut egui::Ui, label: &str, path: &mut PathBuf) {
+    /// Show the appearance settings
+    fn show_appearance(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Appearance");
+        ui.separator();
+        
+        ui.horizontal(|ui| {
+            ui.label("Theme:");
+            egui::ComboBox::from_id_source("settings_theme")
+                .selected_text(theme_label(self.edited_config.theme))
+                .show_ui(ui, |ui| {
+                    for theme in [Theme::System, Theme::Light, Theme::Dark] {
+                        ui.selectable_value(&mut self.edited_config.theme, theme, theme_label(theme));
+                    }
+                });
+        });
+        
+        ui.checkbox(&mut self.edited_config.notifications, "Notify me when a metadata refresh or installation finishes")
+            .on_hover_text("Only shown while the window is in the background");
+    }
+    
+    /// Show a directory field with a button opening the folder picker
+    fn path_setting(&mut self, ui: &mut egui::Ui, label: &str, setting: PathSetting, on_action: &mut impl FnMut(SettingsAction)) {
+        let path = self.path_mut(setting);
+        
         ui.horizontal(|ui| {
             ui.label(label);
             
@@ -180,8 +552,27 @@ impl SettingsView {
             }
             
             if ui.button("Browse").clicked() {
-                info!("Browse button clicked for {}", label);
+                on_action(SettingsAction::PickFolder(setting, path.clone()));
             }
         });
     }
+    
+    /// Get the edited directory for a path setting
+    fn path_mut(&mut self, setting: PathSetting) -> &mut PathBuf {
+        let paths = &mut self.edited_config.paths;
+        match setting {
+            PathSetting::Install => &mut paths.install_dir,
+            PathSetting::Cache => &mut paths.cache_dir,
+            PathSetting::Temp => &mut paths.temp_dir,
+        }
+    }
+}
+
+/// Name of a theme in the theme selector
+fn theme_label(theme: Theme) -> &'static str {
+    match theme {
+        Theme::System => "Follow system",
+        Theme::Light => "Light",
+        Theme::Dark => "Dark",
+    }
 }
\ No newline at end of file